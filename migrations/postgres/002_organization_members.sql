@@ -0,0 +1,30 @@
+-- ==========================================
+-- ORGANIZATION STAFF ACCOUNTS
+-- ==========================================
+
+-- Organization Members (managers, coaches, social media staff)
+CREATE TABLE organization_members (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    organization_id UUID NOT NULL REFERENCES organizations(id) ON DELETE CASCADE,
+    player_id UUID REFERENCES players(id) ON DELETE CASCADE, -- NULL until an email invite is accepted
+    invited_email VARCHAR(255),
+    role VARCHAR(20) NOT NULL DEFAULT 'viewer' CHECK (role IN ('owner', 'manager', 'tournament_admin', 'viewer')),
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'accepted', 'declined', 'expired')),
+    invited_by UUID,
+    joined_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    CONSTRAINT check_member_player_or_email
+        CHECK (player_id IS NOT NULL OR invited_email IS NOT NULL),
+    UNIQUE(organization_id, player_id)
+);
+
+CREATE INDEX idx_organization_members_org ON organization_members(organization_id, status);
+CREATE INDEX idx_organization_members_player ON organization_members(player_id, status) WHERE player_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_organization_members_pending_email ON organization_members(organization_id, invited_email) WHERE status = 'pending';
+
+-- At most one owner member per organization
+CREATE UNIQUE INDEX idx_organization_members_single_owner ON organization_members(organization_id) WHERE role = 'owner' AND status = 'accepted';
+
+CREATE TRIGGER trigger_organization_members_updated_at BEFORE UPDATE ON organization_members FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
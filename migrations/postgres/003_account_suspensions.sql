@@ -0,0 +1,23 @@
+-- ==========================================
+-- ADMIN MODERATION: ACCOUNT SUSPENSIONS & BANS
+-- ==========================================
+
+CREATE TABLE account_suspensions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    user_type VARCHAR(20) NOT NULL CHECK (user_type IN ('player', 'admin', 'organization')),
+    kind VARCHAR(20) NOT NULL CHECK (kind IN ('suspension', 'ban')),
+    reason TEXT NOT NULL,
+    issued_by UUID NOT NULL REFERENCES admins(id),
+    expires_at TIMESTAMPTZ, -- NULL = indefinite (always NULL for bans)
+    lifted_at TIMESTAMPTZ,
+    lifted_by UUID REFERENCES admins(id),
+    lift_reason TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_account_suspensions_user ON account_suspensions(user_id, created_at DESC);
+CREATE INDEX idx_account_suspensions_active ON account_suspensions(user_id) WHERE lifted_at IS NULL;
+
+CREATE TRIGGER trigger_account_suspensions_updated_at BEFORE UPDATE ON account_suspensions FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
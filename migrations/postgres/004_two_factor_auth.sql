@@ -0,0 +1,29 @@
+-- ==========================================
+-- TWO-FACTOR AUTHENTICATION (TOTP + RECOVERY CODES)
+-- ==========================================
+
+CREATE TABLE user_two_factor (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    user_type VARCHAR(20) NOT NULL CHECK (user_type IN ('player', 'admin', 'organization')),
+    secret VARCHAR(64) NOT NULL, -- base32 TOTP secret
+    enabled BOOLEAN NOT NULL DEFAULT FALSE, -- false until the first code is confirmed
+    confirmed_at TIMESTAMPTZ,
+    last_used_step BIGINT, -- last accepted TOTP time step, blocks code replay
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE(user_id, user_type)
+);
+
+CREATE TABLE two_factor_recovery_codes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    code_hash VARCHAR(64) NOT NULL, -- SHA-256 hex digest, plaintext is shown once
+    used_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_two_factor_recovery_codes_user ON two_factor_recovery_codes(user_id) WHERE used_at IS NULL;
+
+CREATE TRIGGER trigger_user_two_factor_updated_at BEFORE UPDATE ON user_two_factor FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
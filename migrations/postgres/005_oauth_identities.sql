@@ -0,0 +1,39 @@
+-- ==========================================
+-- SOCIAL LOGIN (OAUTH2 / OPENID CONNECT)
+-- ==========================================
+
+-- Accounts created through a provider have no password until they set one
+ALTER TABLE players ADD COLUMN has_password BOOLEAN NOT NULL DEFAULT TRUE;
+
+CREATE TABLE player_identities (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    provider VARCHAR(20) NOT NULL CHECK (provider IN ('discord', 'google', 'twitch')),
+    provider_user_id VARCHAR(255) NOT NULL, -- the provider's stable subject id
+    email VARCHAR(255),
+    username VARCHAR(255),
+    last_login_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE(provider, provider_user_id),
+    UNIQUE(player_id, provider)
+);
+
+CREATE INDEX idx_player_identities_player ON player_identities(player_id);
+
+-- In-flight authorization requests, consumed by the callback
+CREATE TABLE oauth_states (
+    state VARCHAR(64) PRIMARY KEY,
+    provider VARCHAR(20) NOT NULL CHECK (provider IN ('discord', 'google', 'twitch')),
+    code_verifier VARCHAR(128) NOT NULL, -- PKCE verifier, never leaves the server
+    nonce VARCHAR(64) NOT NULL,
+    link_player_id UUID REFERENCES players(id) ON DELETE CASCADE, -- set when linking from settings
+    redirect_to VARCHAR(500),
+    expires_at TIMESTAMPTZ NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_oauth_states_expires ON oauth_states(expires_at);
+
+CREATE TRIGGER trigger_player_identities_updated_at BEFORE UPDATE ON player_identities FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,15 @@
+-- ==========================================
+-- SINGLE-USE TOKENS
+-- ==========================================
+
+-- One row per redeemed single-use token (magic login links, ...). The primary
+-- key makes a second redemption fail even under concurrent requests.
+CREATE TABLE consumed_tokens (
+    jti UUID PRIMARY KEY,
+    token_type VARCHAR(50) NOT NULL,
+    user_id UUID NOT NULL,
+    consumed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ NOT NULL -- when the token itself expires; rows can be purged after this
+);
+
+CREATE INDEX idx_consumed_tokens_expires ON consumed_tokens(expires_at);
@@ -0,0 +1,19 @@
+-- ==========================================
+-- REFRESH TOKEN FAMILIES
+-- ==========================================
+
+-- Every session rotated out of the same login shares a family. Presenting a
+-- refresh token that was already rotated revokes the whole family, and the
+-- family's absolute expiry caps how long rotation can keep a login alive.
+ALTER TABLE user_sessions ADD COLUMN family_id UUID;
+ALTER TABLE user_sessions ADD COLUMN family_expires_at TIMESTAMPTZ;
+
+UPDATE user_sessions
+SET family_id = id,
+    family_expires_at = COALESCE(created_at, NOW()) + INTERVAL '30 days'
+WHERE family_id IS NULL;
+
+ALTER TABLE user_sessions ALTER COLUMN family_id SET NOT NULL;
+ALTER TABLE user_sessions ALTER COLUMN family_expires_at SET NOT NULL;
+
+CREATE INDEX idx_user_sessions_family ON user_sessions(family_id) WHERE NOT revoked;
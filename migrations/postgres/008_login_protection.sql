@@ -0,0 +1,34 @@
+-- ==========================================
+-- LOGIN BRUTE-FORCE PROTECTION
+-- ==========================================
+
+-- Per-account failure counter and lock state for every user type
+CREATE TABLE account_lockouts (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    user_id UUID NOT NULL,
+    user_type VARCHAR(20) NOT NULL CHECK (user_type IN ('player', 'admin', 'organization')),
+    failed_attempts INTEGER NOT NULL DEFAULT 0, -- since the last lock or successful login
+    lockout_count INTEGER NOT NULL DEFAULT 0, -- consecutive locks, drives the backoff
+    last_failed_at TIMESTAMPTZ,
+    locked_until TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+
+    UNIQUE(user_id, user_type)
+);
+
+-- One row per failed login, for sliding-window counts per IP and per email
+CREATE TABLE login_failures (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    email VARCHAR(255) NOT NULL, -- lowercased; recorded even when no account matches
+    ip_address VARCHAR(45),
+    user_id UUID,
+    user_type VARCHAR(20),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_login_failures_ip ON login_failures(ip_address, created_at DESC);
+CREATE INDEX idx_login_failures_email ON login_failures(email, created_at DESC);
+CREATE INDEX idx_login_failures_cleanup ON login_failures(created_at);
+
+CREATE TRIGGER trigger_account_lockouts_updated_at BEFORE UPDATE ON account_lockouts FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
@@ -0,0 +1,9 @@
+-- ==========================================
+-- DISTRIBUTED RATE LIMITING
+-- ==========================================
+
+-- rate_limits is the fallback store when Redis is unavailable; route policies
+-- can also be keyed by API key
+ALTER TABLE rate_limits DROP CONSTRAINT rate_limits_identifier_type_check;
+ALTER TABLE rate_limits ADD CONSTRAINT rate_limits_identifier_type_check
+    CHECK (identifier_type IN ('ip', 'user_id', 'email', 'api_key'));
@@ -0,0 +1,26 @@
+-- ==========================================
+-- TRANSACTIONAL OUTBOX
+-- ==========================================
+
+-- Domain events written in the same transaction as the change they describe.
+-- The dispatcher hands each one to every in-process subscriber at least once.
+CREATE TABLE outbox_events (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    event_type VARCHAR(50) NOT NULL,
+    aggregate_id UUID NOT NULL, -- the player, team, tournament or battle the event is about
+    payload JSONB NOT NULL,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'processed', 'failed')),
+    attempts INTEGER NOT NULL DEFAULT 0,
+    completed_subscribers TEXT[] NOT NULL DEFAULT '{}', -- skipped when a partly handled event is retried
+    next_attempt_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    last_error TEXT,
+    processed_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_outbox_events_due ON outbox_events(next_attempt_at) WHERE status = 'pending';
+CREATE INDEX idx_outbox_events_aggregate ON outbox_events(aggregate_id, created_at);
+
+CREATE TRIGGER update_outbox_events_updated_at BEFORE UPDATE ON outbox_events
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
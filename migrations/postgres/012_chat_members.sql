@@ -0,0 +1,45 @@
+-- ==========================================
+-- CHAT MEMBERS
+-- ==========================================
+
+-- Replaces the chats.participants JSON array. A member who leaves keeps the row
+-- with left_at set; joining again clears it.
+CREATE TABLE chat_members (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    chat_id UUID NOT NULL REFERENCES chats(id) ON DELETE CASCADE,
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL DEFAULT 'member' CHECK (role IN ('owner', 'admin', 'member')),
+    is_muted BOOLEAN NOT NULL DEFAULT FALSE, -- muted members can read but not send
+    muted_until TIMESTAMPTZ, -- NULL while muted means until unmuted
+    joined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    left_at TIMESTAMPTZ,
+    last_read_message_id UUID REFERENCES chat_messages(id) ON DELETE SET NULL,
+    last_read_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(chat_id, player_id)
+);
+
+CREATE INDEX idx_chat_members_player ON chat_members(player_id) WHERE left_at IS NULL;
+CREATE INDEX idx_chat_members_chat ON chat_members(chat_id) WHERE left_at IS NULL;
+
+CREATE TRIGGER update_chat_members_updated_at BEFORE UPDATE ON chat_members
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- Carry over the JSON participants; ids that no longer match a player are dropped
+INSERT INTO chat_members (chat_id, player_id, role, joined_at)
+SELECT c.id, p.id, CASE WHEN p.id = c.created_by THEN 'owner' ELSE 'member' END, c.created_at
+FROM chats c
+CROSS JOIN LATERAL jsonb_array_elements_text(
+    CASE WHEN jsonb_typeof(c.participants) = 'array' THEN c.participants ELSE '[]'::jsonb END
+) AS participant(value)
+JOIN players p ON p.id::text = participant.value
+ON CONFLICT (chat_id, player_id) DO NOTHING;
+
+-- Every chat keeps its creator as owner
+INSERT INTO chat_members (chat_id, player_id, role, joined_at)
+SELECT c.id, c.created_by, 'owner', c.created_at
+FROM chats c
+ON CONFLICT (chat_id, player_id) DO UPDATE SET role = 'owner';
+
+ALTER TABLE chats DROP COLUMN participants;
@@ -0,0 +1,16 @@
+-- ==========================================
+-- DIRECT MESSAGES
+-- ==========================================
+
+-- Who may open a new conversation with the player
+ALTER TABLE players ADD COLUMN dm_privacy VARCHAR(20) NOT NULL DEFAULT 'everyone'
+    CHECK (dm_privacy IN ('everyone', 'connections'));
+
+-- 1:1 conversations are 'direct' chats; small named or unnamed groups are 'group_dm'
+ALTER TABLE chats DROP CONSTRAINT chats_chat_type_check;
+ALTER TABLE chats ADD CONSTRAINT chats_chat_type_check
+    CHECK (chat_type IN ('general', 'team', 'tournament', 'community', 'direct', 'group_dm'));
+
+-- The two player ids of a direct chat in sorted order, so each pair has one chat
+ALTER TABLE chats ADD COLUMN direct_key VARCHAR(73);
+CREATE UNIQUE INDEX idx_chats_direct_key ON chats(direct_key) WHERE direct_key IS NOT NULL;
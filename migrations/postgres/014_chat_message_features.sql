@@ -0,0 +1,46 @@
+-- ==========================================
+-- CHAT MESSAGE EDITS, REACTIONS AND ATTACHMENTS
+-- ==========================================
+
+-- Earlier versions of edited messages. Dropped when the message is deleted.
+CREATE TABLE chat_message_edits (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    message_id UUID NOT NULL REFERENCES chat_messages(id) ON DELETE CASCADE,
+    previous_message TEXT NOT NULL,
+    edited_by UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    edited_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_chat_message_edits_message ON chat_message_edits(message_id, edited_at);
+
+-- One row per player and emoji; chat_messages.reactions keeps the counts
+CREATE TABLE chat_message_reactions (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    message_id UUID NOT NULL REFERENCES chat_messages(id) ON DELETE CASCADE,
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    emoji VARCHAR(64) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(message_id, player_id, emoji)
+);
+
+-- Files uploaded to a chat. Type, size and digest are worked out by the server
+-- from the uploaded bytes; message_id is set once a message links the file.
+-- Deleting the message discards the file, which is then removed from storage.
+CREATE TABLE chat_attachments (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    chat_id UUID NOT NULL REFERENCES chats(id) ON DELETE CASCADE,
+    uploaded_by UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    message_id UUID REFERENCES chat_messages(id) ON DELETE SET NULL,
+    storage_key TEXT NOT NULL,
+    url TEXT NOT NULL,
+    filename VARCHAR(255) NOT NULL,
+    content_type VARCHAR(100) NOT NULL,
+    size_bytes BIGINT NOT NULL CHECK (size_bytes > 0),
+    sha256 CHAR(64) NOT NULL,
+    discarded_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX idx_chat_attachments_message ON chat_attachments(message_id) WHERE message_id IS NOT NULL;
+CREATE INDEX idx_chat_attachments_unlinked ON chat_attachments(uploaded_by, created_at) WHERE message_id IS NULL;
+CREATE INDEX idx_chat_attachments_discarded ON chat_attachments(discarded_at) WHERE discarded_at IS NOT NULL;
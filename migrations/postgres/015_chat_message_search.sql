@@ -0,0 +1,14 @@
+-- ==========================================
+-- CHAT MESSAGE SEARCH
+-- ==========================================
+
+-- 'simple' keeps words as typed: chat is full of names, game terms and
+-- languages an English stemmer would mangle
+ALTER TABLE chat_messages ADD COLUMN search_vector tsvector
+    GENERATED ALWAYS AS (to_tsvector('simple', COALESCE(message, ''))) STORED;
+
+CREATE INDEX idx_chat_messages_search ON chat_messages USING GIN (search_vector) WHERE deleted_at IS NULL;
+
+-- Keyset pagination orders by (created_at, id)
+DROP INDEX IF EXISTS idx_chat_messages_chat_time;
+CREATE INDEX idx_chat_messages_chat_time ON chat_messages(chat_id, created_at DESC, id DESC);
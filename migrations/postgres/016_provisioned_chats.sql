@@ -0,0 +1,22 @@
+-- ==========================================
+-- PROVISIONED TEAM, TOURNAMENT AND BATTLE CHATS
+-- ==========================================
+
+-- Chats the server provisions for a team, tournament or battle have no creator
+ALTER TABLE chats ALTER COLUMN created_by DROP NOT NULL;
+
+ALTER TABLE chats DROP CONSTRAINT chats_chat_type_check;
+ALTER TABLE chats ADD CONSTRAINT chats_chat_type_check
+    CHECK (chat_type IN ('general', 'team', 'tournament', 'battle', 'community', 'direct', 'group_dm'));
+
+ALTER TABLE chats ADD COLUMN battle_id UUID REFERENCES battles(id) ON DELETE CASCADE;
+-- Archived chats keep their history but take no new messages
+ALTER TABLE chats ADD COLUMN archived_at TIMESTAMPTZ;
+
+-- One provisioned chat per team, tournament and battle
+CREATE UNIQUE INDEX idx_chats_team_channel ON chats(team_id)
+    WHERE chat_type = 'team' AND team_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_chats_tournament_lobby ON chats(tournament_id)
+    WHERE chat_type = 'tournament' AND tournament_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_chats_battle_lobby ON chats(battle_id)
+    WHERE battle_id IS NOT NULL;
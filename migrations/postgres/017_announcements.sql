@@ -0,0 +1,52 @@
+-- ==========================================
+-- SYSTEM ANNOUNCEMENTS
+-- ==========================================
+
+-- Platform notices from admins, or from organizations for their own
+-- tournaments and teams. Visible from publish_at until expires_at;
+-- published_at records when the real-time push went out.
+CREATE TABLE announcements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    title VARCHAR(200) NOT NULL,
+    body TEXT NOT NULL,
+    severity VARCHAR(20) NOT NULL DEFAULT 'info' CHECK (severity IN ('info', 'warning', 'critical')),
+    audience VARCHAR(20) NOT NULL CHECK (audience IN ('global', 'game', 'tournament', 'team')),
+    game game_type,
+    tournament_id UUID REFERENCES tournaments(id) ON DELETE CASCADE,
+    team_id UUID REFERENCES teams(id) ON DELETE CASCADE,
+    requires_ack BOOLEAN NOT NULL DEFAULT FALSE,
+    author_type VARCHAR(20) NOT NULL CHECK (author_type IN ('admin', 'organization')),
+    author_id UUID NOT NULL, -- admin or organization id
+    publish_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    expires_at TIMESTAMPTZ,
+    published_at TIMESTAMPTZ,
+    withdrawn_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    -- exactly the target the audience calls for
+    CHECK (
+        (audience = 'global' AND game IS NULL AND tournament_id IS NULL AND team_id IS NULL)
+        OR (audience = 'game' AND game IS NOT NULL AND tournament_id IS NULL AND team_id IS NULL)
+        OR (audience = 'tournament' AND game IS NULL AND tournament_id IS NOT NULL AND team_id IS NULL)
+        OR (audience = 'team' AND game IS NULL AND tournament_id IS NULL AND team_id IS NOT NULL)
+    ),
+    CHECK (expires_at IS NULL OR expires_at > publish_at)
+);
+
+CREATE INDEX idx_announcements_publish ON announcements(publish_at DESC) WHERE withdrawn_at IS NULL;
+CREATE INDEX idx_announcements_unpushed ON announcements(publish_at)
+    WHERE published_at IS NULL AND withdrawn_at IS NULL;
+CREATE INDEX idx_announcements_author ON announcements(author_type, author_id, created_at DESC);
+
+CREATE TRIGGER update_announcements_updated_at BEFORE UPDATE ON announcements
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+CREATE TABLE announcement_acknowledgements (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    announcement_id UUID NOT NULL REFERENCES announcements(id) ON DELETE CASCADE,
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    acknowledged_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(announcement_id, player_id)
+);
+
+CREATE INDEX idx_announcement_acks_player ON announcement_acknowledgements(player_id);
@@ -0,0 +1,46 @@
+-- ==========================================
+-- CONTENT MODERATION
+-- ==========================================
+
+-- Chat messages and community posts waiting for a moderator. Automatic flags
+-- and user reports on the same content share one open item; `reasons` keeps
+-- every finding and report summary that led here.
+CREATE TABLE moderation_queue (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    content_type VARCHAR(30) NOT NULL CHECK (content_type IN ('chat_message', 'community_post')),
+    content_id UUID NOT NULL,
+    author_id UUID REFERENCES players(id) ON DELETE CASCADE,
+    excerpt TEXT NOT NULL, -- the content as it was when first queued
+    reasons JSONB NOT NULL DEFAULT '[]',
+    report_count INTEGER NOT NULL DEFAULT 0,
+    status VARCHAR(20) NOT NULL DEFAULT 'pending' CHECK (status IN ('pending', 'removed', 'dismissed')),
+    reviewed_by UUID REFERENCES admins(id) ON DELETE SET NULL,
+    reviewed_at TIMESTAMPTZ,
+    resolution_note TEXT,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- At most one open item per piece of content
+CREATE UNIQUE INDEX idx_moderation_queue_open ON moderation_queue(content_type, content_id)
+    WHERE status = 'pending';
+CREATE INDEX idx_moderation_queue_status ON moderation_queue(status, created_at);
+CREATE INDEX idx_moderation_queue_author ON moderation_queue(author_id);
+
+CREATE TRIGGER update_moderation_queue_updated_at BEFORE UPDATE ON moderation_queue
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- One report per player per piece of content
+CREATE TABLE content_reports (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    content_type VARCHAR(30) NOT NULL CHECK (content_type IN ('chat_message', 'community_post')),
+    content_id UUID NOT NULL,
+    reporter_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    reason VARCHAR(20) NOT NULL CHECK (reason IN ('spam', 'harassment', 'hate_speech', 'sexual_content', 'other')),
+    details TEXT,
+    queue_item_id UUID NOT NULL REFERENCES moderation_queue(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    UNIQUE(content_type, content_id, reporter_id)
+);
+
+CREATE INDEX idx_content_reports_queue_item ON content_reports(queue_item_id);
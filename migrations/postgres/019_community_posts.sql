@@ -0,0 +1,136 @@
+-- ==========================================
+-- COMMUNITY POSTS, THREADED COMMENTS AND VOTES
+-- ==========================================
+
+-- Nesting level of a comment; top-level comments are 0
+ALTER TABLE post_comments ADD COLUMN depth SMALLINT NOT NULL DEFAULT 0;
+ALTER TABLE post_comments ADD CONSTRAINT check_comment_counts_positive
+    CHECK (upvotes >= 0 AND downvotes >= 0 AND reply_count >= 0);
+
+WITH RECURSIVE tree AS (
+    SELECT id, 0 AS depth FROM post_comments WHERE parent_id IS NULL
+    UNION ALL
+    SELECT c.id, t.depth + 1 FROM post_comments c JOIN tree t ON c.parent_id = t.id
+)
+UPDATE post_comments c SET depth = tree.depth FROM tree WHERE c.id = tree.id AND c.depth <> tree.depth;
+
+-- Threads are listed parent by parent, deleted comments included
+CREATE INDEX idx_post_comments_thread ON post_comments(post_id, parent_id, created_at);
+
+CREATE TRIGGER trigger_post_comments_updated_at BEFORE UPDATE ON post_comments
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- comment_count and reply_count count live comments, so a soft delete takes
+-- a comment off its post's and its parent's counts
+CREATE OR REPLACE FUNCTION update_post_comment_count()
+RETURNS TRIGGER AS $$
+DECLARE
+    delta INTEGER;
+    comment post_comments;
+BEGIN
+    IF TG_OP = 'INSERT' AND NEW.deleted_at IS NULL THEN
+        delta := 1;
+        comment := NEW;
+    ELSIF TG_OP = 'DELETE' AND OLD.deleted_at IS NULL THEN
+        delta := -1;
+        comment := OLD;
+    ELSIF TG_OP = 'UPDATE' AND OLD.deleted_at IS NULL AND NEW.deleted_at IS NOT NULL THEN
+        delta := -1;
+        comment := NEW;
+    ELSIF TG_OP = 'UPDATE' AND OLD.deleted_at IS NOT NULL AND NEW.deleted_at IS NULL THEN
+        delta := 1;
+        comment := NEW;
+    ELSE
+        RETURN NULL;
+    END IF;
+
+    UPDATE community_posts
+    SET comment_count = GREATEST(comment_count + delta, 0)
+    WHERE id = comment.post_id;
+
+    IF comment.parent_id IS NOT NULL THEN
+        UPDATE post_comments
+        SET reply_count = GREATEST(reply_count + delta, 0)
+        WHERE id = comment.parent_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER trigger_post_comment_count ON post_comments;
+CREATE TRIGGER trigger_post_comment_count
+    AFTER INSERT OR DELETE OR UPDATE OF deleted_at ON post_comments
+    FOR EACH ROW EXECUTE FUNCTION update_post_comment_count();
+
+UPDATE community_posts p SET comment_count = (
+    SELECT COUNT(*) FROM post_comments c WHERE c.post_id = p.id AND c.deleted_at IS NULL
+);
+UPDATE post_comments p SET reply_count = (
+    SELECT COUNT(*) FROM post_comments c WHERE c.parent_id = p.id AND c.deleted_at IS NULL
+);
+
+-- One vote per player on each post or comment
+CREATE TABLE community_votes (
+    id UUID PRIMARY KEY DEFAULT gen_random_uuid(),
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    post_id UUID REFERENCES community_posts(id) ON DELETE CASCADE,
+    comment_id UUID REFERENCES post_comments(id) ON DELETE CASCADE,
+    value SMALLINT NOT NULL CHECK (value IN (-1, 1)),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CHECK ((post_id IS NULL) <> (comment_id IS NULL))
+);
+
+CREATE UNIQUE INDEX idx_community_votes_post ON community_votes(post_id, player_id)
+    WHERE post_id IS NOT NULL;
+CREATE UNIQUE INDEX idx_community_votes_comment ON community_votes(comment_id, player_id)
+    WHERE comment_id IS NOT NULL;
+CREATE INDEX idx_community_votes_player ON community_votes(player_id);
+
+CREATE TRIGGER update_community_votes_updated_at BEFORE UPDATE ON community_votes
+    FOR EACH ROW EXECUTE FUNCTION update_updated_at_column();
+
+-- upvotes and downvotes on posts and comments follow the votes table
+CREATE OR REPLACE FUNCTION apply_community_vote()
+RETURNS TRIGGER AS $$
+DECLARE
+    up_delta INTEGER := 0;
+    down_delta INTEGER := 0;
+    vote community_votes;
+BEGIN
+    IF TG_OP IN ('UPDATE', 'DELETE') THEN
+        IF OLD.value = 1 THEN up_delta := up_delta - 1; ELSE down_delta := down_delta - 1; END IF;
+        vote := OLD;
+    END IF;
+    IF TG_OP IN ('INSERT', 'UPDATE') THEN
+        IF NEW.value = 1 THEN up_delta := up_delta + 1; ELSE down_delta := down_delta + 1; END IF;
+        vote := NEW;
+    END IF;
+    IF up_delta = 0 AND down_delta = 0 THEN
+        RETURN NULL;
+    END IF;
+
+    IF vote.post_id IS NOT NULL THEN
+        UPDATE community_posts
+        SET upvotes = upvotes + up_delta, downvotes = downvotes + down_delta
+        WHERE id = vote.post_id;
+    ELSE
+        UPDATE post_comments
+        SET upvotes = upvotes + up_delta, downvotes = downvotes + down_delta
+        WHERE id = vote.comment_id;
+    END IF;
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trigger_apply_community_vote
+    AFTER INSERT OR UPDATE OF value OR DELETE ON community_votes
+    FOR EACH ROW EXECUTE FUNCTION apply_community_vote();
+
+-- Comments can be reported and moderated like posts
+ALTER TABLE moderation_queue DROP CONSTRAINT moderation_queue_content_type_check;
+ALTER TABLE moderation_queue ADD CONSTRAINT moderation_queue_content_type_check
+    CHECK (content_type IN ('chat_message', 'community_post', 'post_comment'));
+ALTER TABLE content_reports DROP CONSTRAINT content_reports_content_type_check;
+ALTER TABLE content_reports ADD CONSTRAINT content_reports_content_type_check
+    CHECK (content_type IN ('chat_message', 'community_post', 'post_comment'));
@@ -0,0 +1,44 @@
+-- ==========================================
+-- COMMUNITY FEEDS AND VIEW COUNTS
+-- ==========================================
+
+-- Hot ranking: each tenfold of net votes is worth 12.5 hours of recency, so
+-- a post's rank against newer posts decays without being recomputed
+CREATE OR REPLACE FUNCTION community_hot_score(upvotes INTEGER, downvotes INTEGER, created_at TIMESTAMPTZ)
+RETURNS DOUBLE PRECISION AS $$
+    SELECT SIGN(upvotes - downvotes) * LOG(GREATEST(ABS(upvotes - downvotes), 1))
+        + EXTRACT(EPOCH FROM created_at) / 45000;
+$$ LANGUAGE sql IMMUTABLE;
+
+ALTER TABLE community_posts ADD COLUMN hot_score DOUBLE PRECISION NOT NULL DEFAULT 0;
+
+CREATE OR REPLACE FUNCTION update_community_post_hot_score()
+RETURNS TRIGGER AS $$
+BEGIN
+    NEW.hot_score := community_hot_score(NEW.upvotes, NEW.downvotes, NEW.created_at);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER trigger_community_posts_hot_score
+    BEFORE INSERT OR UPDATE OF upvotes, downvotes, created_at ON community_posts
+    FOR EACH ROW EXECUTE FUNCTION update_community_post_hot_score();
+
+UPDATE community_posts SET hot_score = community_hot_score(upvotes, downvotes, created_at);
+
+-- Feeds page by (pinned, sort key, id); the existing feed index covers "new"
+CREATE INDEX idx_community_posts_hot ON community_posts(community_id, pinned DESC, hot_score DESC, id DESC)
+    WHERE deleted_at IS NULL;
+CREATE INDEX idx_community_posts_top ON community_posts(community_id, pinned DESC, (upvotes - downvotes) DESC, id DESC)
+    WHERE deleted_at IS NULL;
+
+-- Last counted view of a post by each player; a player adds to view_count
+-- at most once per window
+CREATE TABLE community_post_views (
+    post_id UUID NOT NULL REFERENCES community_posts(id) ON DELETE CASCADE,
+    player_id UUID NOT NULL REFERENCES players(id) ON DELETE CASCADE,
+    viewed_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (post_id, player_id)
+);
+
+CREATE INDEX idx_community_post_views_player ON community_post_views(player_id);
@@ -1,6 +1,10 @@
 pub mod aws;
+pub mod moderation;
 pub mod permissions;
 pub mod settings;
 
 pub use aws::AwsClients;
-pub use settings::{EmailConfig, Settings};
+pub use moderation::ModerationConfig;
+pub use settings::{
+    ApiKeySettings, EmailConfig, OAuthClientConfig, OAuthSettings, SessionSettings, Settings,
+};
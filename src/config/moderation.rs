@@ -0,0 +1,65 @@
+use serde::Deserialize;
+use std::env;
+
+/// Content moderation rules, loaded from the file named by
+/// `AEGIS_MODERATION__FILE` (default `config/moderation.toml`; YAML also works).
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ModerationConfig {
+    #[serde(default)]
+    pub words: WordListConfig,
+    #[serde(default)]
+    pub links: LinkConfig,
+    #[serde(default)]
+    pub spam: SpamConfig,
+}
+
+/// Words are matched after leetspeak normalization, as whole words or with a
+/// common suffix, so listing a stem catches its plurals and verb forms.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct WordListConfig {
+    #[serde(default)]
+    pub block: Vec<String>, // content is rejected
+    #[serde(default)]
+    pub flag: Vec<String>, // content is published and queued for review
+}
+
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct LinkConfig {
+    #[serde(default)]
+    pub allowed_domains: Vec<String>, // subdomains are allowed too
+    #[serde(default)]
+    pub block_others: bool, // reject other links instead of flagging them
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default)]
+pub struct SpamConfig {
+    pub duplicate_window_secs: i64, // how far back identical content counts
+    pub max_duplicates: u64,        // identical copies allowed in that window
+    pub flood_window_secs: i64,
+    pub flood_max: u64, // messages or posts allowed per flood window
+}
+
+impl Default for SpamConfig {
+    fn default() -> Self {
+        Self {
+            duplicate_window_secs: 300,
+            max_duplicates: 2,
+            flood_window_secs: 10,
+            flood_max: 8,
+        }
+    }
+}
+
+impl ModerationConfig {
+    pub fn load(path: &str) -> Result<Self, config::ConfigError> {
+        config::Config::builder()
+            .add_source(config::File::with_name(path))
+            .build()?
+            .try_deserialize()
+    }
+}
+
+pub fn moderation_file_path() -> String {
+    env::var("AEGIS_MODERATION__FILE").unwrap_or_else(|_| "config/moderation.toml".to_string())
+}
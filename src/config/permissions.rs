@@ -1,131 +1,263 @@
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::env;
+
+pub type PermissionConfigError = Box<dyn std::error::Error>;
+
+const USER_TYPES: [&str; 3] = ["admin", "player", "organization"];
+const HTTP_METHODS: [&str; 7] = ["GET", "POST", "PUT", "PATCH", "DELETE", "HEAD", "OPTIONS"];
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PathPermission {
     pub path: String,
+    pub methods: Option<Vec<String>>,
     pub access: Vec<String>,
     pub require_verified: Option<bool>,
+    pub api_scope: Option<String>,
     pub description: Option<String>,
 }
 
-pub fn get_path_permissions() -> Vec<PathPermission> {
-    vec![
-        // Auth routes (public)
-        PathPermission {
-            path: "/auth/login".to_string(),
-            access: vec!["public".to_string()],
-            require_verified: None,
-            description: Some("User login".to_string()),
-        },
-        PathPermission {
-            path: "/auth/register".to_string(),
-            access: vec!["public".to_string()],
-            require_verified: None,
-            description: Some("User registration".to_string()),
-        },
-        PathPermission {
-            path: "/auth/logout".to_string(),
-            access: vec![
-                "admin".to_string(),
-                "player".to_string(),
-                "organization".to_string(),
-            ],
-            require_verified: Some(false),
-            description: Some("User logout".to_string()),
-        },
-        PathPermission {
-            path: "/me".to_string(),
-            access: vec![
-                "admin".to_string(),
-                "player".to_string(),
-                "organization".to_string(),
-            ],
-            require_verified: Some(false), // Allow unverified users to see their profile
-            description: Some("Current user profile".to_string()),
-        },
-        // Admin routes
-        PathPermission {
-            path: "/admin/*".to_string(),
-            access: vec!["admin".to_string()],
-            require_verified: Some(true),
-            description: Some("Admin panel access".to_string()),
-        },
-        // Player routes
-        PathPermission {
-            path: "/players".to_string(),
-            access: vec!["admin".to_string(), "player".to_string()],
-            require_verified: Some(true),
-            description: Some("Player list access".to_string()),
-        },
-        PathPermission {
-            path: "/players/me".to_string(),
-            access: vec!["player".to_string()],
-            require_verified: Some(true),
-            description: Some("Player profile access".to_string()),
-        },
-        PathPermission {
-            path: "/players/*".to_string(),
-            access: vec!["admin".to_string(), "player".to_string()],
-            require_verified: Some(true),
-            description: Some("Player management".to_string()),
-        },
-        // Organization routes
-        PathPermission {
-            path: "/organizations/*".to_string(),
-            access: vec!["admin".to_string(), "organization".to_string()],
-            require_verified: Some(true),
-            description: Some("Organization management".to_string()),
-        },
-        // Tournament routes
-        PathPermission {
-            path: "/tournaments/*".to_string(),
-            access: vec![
-                "admin".to_string(),
-                "player".to_string(),
-                "organization".to_string(),
-            ],
-            require_verified: Some(true),
-            description: Some("Tournament access".to_string()),
-        },
-        // Chat routes
-        PathPermission {
-            path: "/chats/*".to_string(),
-            access: vec![
-                "admin".to_string(),
-                "player".to_string(),
-                "organization".to_string(),
-            ],
-            require_verified: Some(true),
-            description: Some("Chat system".to_string()),
-        },
-        // Community routes
-        PathPermission {
-            path: "/communities/*".to_string(),
-            access: vec![
-                "admin".to_string(),
-                "player".to_string(),
-                "organization".to_string(),
-            ],
-            require_verified: Some(false),
-            description: Some("Community features".to_string()),
-        },
-        // Upload routes
-        PathPermission {
-            path: "/uploads/*".to_string(),
-            access: vec![
-                "admin".to_string(),
-                "player".to_string(),
-                "organization".to_string(),
-            ],
-            require_verified: Some(true),
-            description: Some("File uploads".to_string()),
-        },
-        //Dashboard routes
-        PathPermission {
-            path: "/dashboard/*".to_string(),
-            access: vec!["player".to_string(), "organization".to_string()],
-            require_verified: Some(true),
-            description: Some("Dashboard access".to_string()),
-        },
-    ]
+#[derive(Debug, Deserialize)]
+struct PermissionFile {
+    #[serde(rename = "rule", default)]
+    rules: Vec<PathPermission>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Segment {
+    Literal(String),
+    Param,
+    Wildcard,
+}
+
+#[derive(Debug, Clone)]
+struct CompiledRule {
+    permission: PathPermission,
+    segments: Vec<Segment>,
+}
+
+impl CompiledRule {
+    fn matches(&self, method: &str, path: &str) -> bool {
+        if let Some(methods) = &self.permission.methods {
+            if !methods.iter().any(|m| m.eq_ignore_ascii_case(method)) {
+                return false;
+            }
+        }
+
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        for (i, segment) in self.segments.iter().enumerate() {
+            match segment {
+                // A trailing wildcard needs at least one more segment
+                Segment::Wildcard => return parts.len() > i,
+                Segment::Param => {
+                    if i >= parts.len() {
+                        return false;
+                    }
+                }
+                Segment::Literal(literal) => {
+                    if parts.get(i) != Some(&literal.as_str()) {
+                        return false;
+                    }
+                }
+            }
+        }
+
+        parts.len() == self.segments.len()
+    }
+
+    /// Exact paths beat wildcards, literal segments beat params, longer beats
+    /// shorter, and method-specific rules beat method-agnostic ones.
+    fn specificity(&self) -> (bool, usize, usize, bool) {
+        let literals = self
+            .segments
+            .iter()
+            .filter(|s| matches!(s, Segment::Literal(_)))
+            .count();
+        (
+            !self.segments.contains(&Segment::Wildcard),
+            literals,
+            self.segments.len(),
+            self.permission.methods.is_some(),
+        )
+    }
+}
+
+/// The route permission table, loaded from the file named by
+/// `AEGIS_PERMISSIONS__FILE` (default `config/permissions.toml`; YAML also works).
+#[derive(Debug, Clone)]
+pub struct PermissionTable {
+    rules: Vec<CompiledRule>,
+}
+
+impl PermissionTable {
+    pub fn load(path: &str) -> Result<Self, PermissionConfigError> {
+        let file: PermissionFile = config::Config::builder()
+            .add_source(config::File::with_name(path))
+            .build()?
+            .try_deserialize()?;
+
+        Self::from_rules(file.rules)
+    }
+
+    pub fn from_rules(rules: Vec<PathPermission>) -> Result<Self, PermissionConfigError> {
+        if rules.is_empty() {
+            return Err("permission file defines no rules".into());
+        }
+
+        let mut seen = HashSet::new();
+        let mut compiled = Vec::with_capacity(rules.len());
+
+        for mut permission in rules {
+            let segments = parse_path(&permission.path)?;
+
+            if permission.access.is_empty() {
+                return Err(format!("{}: access list is empty", permission.path).into());
+            }
+            let is_public = permission.access.iter().any(|a| a == "public");
+            if is_public && permission.access.len() > 1 {
+                return Err(format!(
+                    "{}: \"public\" cannot be combined with other user types",
+                    permission.path
+                )
+                .into());
+            }
+            if let Some(unknown) = permission
+                .access
+                .iter()
+                .find(|a| *a != "public" && !USER_TYPES.contains(&a.as_str()))
+            {
+                return Err(
+                    format!("{}: unknown user type \"{}\"", permission.path, unknown).into(),
+                );
+            }
+
+            if permission
+                .api_scope
+                .as_deref()
+                .is_some_and(|s| s.is_empty())
+            {
+                return Err(format!("{}: api_scope is empty", permission.path).into());
+            }
+            if is_public && permission.api_scope.is_some() {
+                return Err(
+                    format!("{}: public routes don't take an api_scope", permission.path).into(),
+                );
+            }
+
+            if let Some(methods) = permission.methods.as_mut() {
+                if methods.is_empty() {
+                    return Err(format!(
+                        "{}: methods list is empty (omit it to match every method)",
+                        permission.path
+                    )
+                    .into());
+                }
+                for method in methods.iter_mut() {
+                    *method = method.to_uppercase();
+                    if !HTTP_METHODS.contains(&method.as_str()) {
+                        return Err(format!(
+                            "{}: unknown HTTP method \"{}\"",
+                            permission.path, method
+                        )
+                        .into());
+                    }
+                }
+            }
+
+            // Two rules with the same shape and an overlapping method would make
+            // the winner depend on file order
+            let shape: Vec<String> = segments
+                .iter()
+                .map(|s| match s {
+                    Segment::Literal(l) => l.clone(),
+                    Segment::Param => ":".to_string(),
+                    Segment::Wildcard => "*".to_string(),
+                })
+                .collect();
+            let methods: Vec<String> = match &permission.methods {
+                Some(m) => m.clone(),
+                None => vec!["*".to_string()],
+            };
+            for method in methods {
+                if !seen.insert((shape.clone(), method.clone())) {
+                    return Err(format!(
+                        "{}: duplicate rule for method {}",
+                        permission.path, method
+                    )
+                    .into());
+                }
+            }
+
+            compiled.push(CompiledRule {
+                permission,
+                segments,
+            });
+        }
+
+        Ok(Self { rules: compiled })
+    }
+
+    /// Finds the most specific rule for a request.
+    pub fn find(&self, method: &str, path: &str) -> Option<&PathPermission> {
+        let mut best: Option<&CompiledRule> = None;
+        for rule in self.rules.iter().filter(|r| r.matches(method, path)) {
+            if best.is_none_or(|b| rule.specificity() > b.specificity()) {
+                best = Some(rule);
+            }
+        }
+        best.map(|r| &r.permission)
+    }
+
+    /// A route is protected when it has a rule that isn't public. Unmatched
+    /// paths skip authentication so the router can answer 404.
+    pub fn is_protected(&self, method: &str, path: &str) -> bool {
+        self.find(method, path)
+            .is_some_and(|p| !p.access.iter().any(|a| a == "public"))
+    }
+
+    pub fn len(&self) -> usize {
+        self.rules.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+}
+
+fn parse_path(path: &str) -> Result<Vec<Segment>, PermissionConfigError> {
+    if !path.starts_with('/') {
+        return Err(format!("{}: path must start with '/'", path).into());
+    }
+
+    let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+    let mut segments = Vec::with_capacity(parts.len());
+
+    for (i, part) in parts.iter().enumerate() {
+        let segment = if *part == "*" {
+            if i != parts.len() - 1 {
+                return Err(format!("{}: '*' is only allowed as the last segment", path).into());
+            }
+            Segment::Wildcard
+        } else if let Some(name) = part.strip_prefix(':') {
+            if name.is_empty() {
+                return Err(format!("{}: path parameter needs a name", path).into());
+            }
+            Segment::Param
+        } else if part.contains('*') || part.contains(':') {
+            return Err(format!("{}: invalid segment \"{}\"", path, part).into());
+        } else {
+            Segment::Literal(part.to_string())
+        };
+        segments.push(segment);
+    }
+
+    Ok(segments)
+}
+
+pub fn permissions_file_path() -> String {
+    env::var("AEGIS_PERMISSIONS__FILE").unwrap_or_else(|_| "config/permissions.toml".to_string())
+}
+
+pub fn get_path_permissions() -> Result<PermissionTable, PermissionConfigError> {
+    PermissionTable::load(&permissions_file_path())
 }
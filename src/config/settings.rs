@@ -1,3 +1,4 @@
+use super::moderation::{moderation_file_path, ModerationConfig};
 use serde::Deserialize;
 use std::env;
 
@@ -8,6 +9,11 @@ pub struct Settings {
     pub jwt: JwtConfig,
     pub email: EmailConfig,
     pub redis: RedisSettings,
+    pub oauth: OAuthSettings,
+    pub session: SessionSettings,
+    pub geoip: GeoIpSettings,
+    pub api_keys: ApiKeySettings,
+    pub moderation: ModerationConfig,
 }
 
 #[derive(Debug, Deserialize, Clone)]
@@ -43,6 +49,66 @@ pub struct RedisSettings {
     pub url: Option<String>,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct SessionSettings {
+    pub refresh_lifetime_hours: i64, // how long one refresh token stays valid
+    pub absolute_lifetime_hours: i64, // cap for a login, however often it is refreshed
+    pub reuse_alert_email: bool,     // email the user when a rotated refresh token is replayed
+    pub idle_timeout_minutes: i64,   // sessions with no activity for this long stop working
+    pub activity_update_secs: i64,   // minimum gap between last_activity writes
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct GeoIpSettings {
+    pub lookup_url: Option<String>, // e.g. http://ip-api.com/json/{ip}; unset disables lookups
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthSettings {
+    pub callback_base_url: String, // public base URL the providers redirect back to
+    pub frontend_url: String,      // where the browser lands after the callback
+    pub discord: Option<OAuthClientConfig>,
+    pub google: Option<OAuthClientConfig>,
+    pub twitch: Option<OAuthClientConfig>,
+}
+
+/// Credentials for one identity provider. The endpoint overrides default to the
+/// provider's public endpoints and exist so a local mock provider can stand in.
+#[derive(Debug, Clone, Deserialize)]
+pub struct OAuthClientConfig {
+    pub client_id: String,
+    pub client_secret: String,
+    pub authorize_url: Option<String>,
+    pub token_url: Option<String>,
+    pub userinfo_url: Option<String>,
+    pub jwks_url: Option<String>,
+    pub issuer: Option<String>,
+}
+
+impl OAuthClientConfig {
+    /// Reads `AEGIS_OAUTH__<PROVIDER>__*`; the provider is disabled without a client id.
+    fn from_env(provider: &str) -> Option<Self> {
+        let var = |name: &str| env::var(format!("AEGIS_OAUTH__{}__{}", provider, name)).ok();
+
+        Some(Self {
+            client_id: var("CLIENT_ID").filter(|v| !v.is_empty())?,
+            client_secret: var("CLIENT_SECRET").unwrap_or_default(),
+            authorize_url: var("AUTHORIZE_URL"),
+            token_url: var("TOKEN_URL"),
+            userinfo_url: var("USERINFO_URL"),
+            jwks_url: var("JWKS_URL"),
+            issuer: var("ISSUER"),
+        })
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ApiKeySettings {
+    pub hmac_secret: String,       // changing it invalidates every issued key
+    pub cache_ttl_secs: u64,       // how long a verified key is trusted without a DB read
+    pub last_used_flush_secs: u64, // how often batched last_used_at values are written
+}
+
 impl Settings {
     pub fn new() -> Result<Self, Box<dyn std::error::Error>> {
         Ok(Settings {
@@ -74,6 +140,48 @@ impl Settings {
             redis: RedisSettings {
                 url: env::var("AEGIS_REDIS__URL").ok(),
             },
+            session: SessionSettings {
+                refresh_lifetime_hours: env::var("AEGIS_SESSION__REFRESH_LIFETIME_HOURS")
+                    .unwrap_or_else(|_| "720".to_string())
+                    .parse()?,
+                absolute_lifetime_hours: env::var("AEGIS_SESSION__ABSOLUTE_LIFETIME_HOURS")
+                    .unwrap_or_else(|_| "2160".to_string())
+                    .parse()?,
+                reuse_alert_email: env::var("AEGIS_SESSION__REUSE_ALERT_EMAIL")
+                    .map(|v| v == "true")
+                    .unwrap_or(true),
+                idle_timeout_minutes: env::var("AEGIS_SESSION__IDLE_TIMEOUT_MINUTES")
+                    .unwrap_or_else(|_| "20160".to_string())
+                    .parse()?,
+                activity_update_secs: env::var("AEGIS_SESSION__ACTIVITY_UPDATE_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+            },
+            geoip: GeoIpSettings {
+                lookup_url: env::var("AEGIS_GEOIP__LOOKUP_URL")
+                    .ok()
+                    .filter(|v| !v.is_empty()),
+            },
+            api_keys: ApiKeySettings {
+                hmac_secret: env::var("AEGIS_API_KEYS__HMAC_SECRET")
+                    .or_else(|_| env::var("AEGIS_JWT__SECRET"))?,
+                cache_ttl_secs: env::var("AEGIS_API_KEYS__CACHE_TTL_SECS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()?,
+                last_used_flush_secs: env::var("AEGIS_API_KEYS__LAST_USED_FLUSH_SECS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()?,
+            },
+            oauth: OAuthSettings {
+                callback_base_url: env::var("AEGIS_OAUTH__CALLBACK_BASE_URL")
+                    .unwrap_or_else(|_| "http://127.0.0.1:8000".to_string()),
+                frontend_url: env::var("AEGIS_OAUTH__FRONTEND_URL")
+                    .unwrap_or_else(|_| "http://localhost:5173".to_string()),
+                discord: OAuthClientConfig::from_env("DISCORD"),
+                google: OAuthClientConfig::from_env("GOOGLE"),
+                twitch: OAuthClientConfig::from_env("TWITCH"),
+            },
+            moderation: ModerationConfig::load(&moderation_file_path())?,
         })
     }
 }
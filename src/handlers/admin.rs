@@ -0,0 +1,822 @@
+use super::chat::ApiResponse;
+use crate::models::enums::{AdminCapability, AdminRole, ApprovalStatus, SuspensionKind};
+use crate::models::postgres::{account_suspension, admin, audit_log, tournament};
+use crate::services::auth_service::{Claims, UserType};
+use crate::services::AdminService;
+use crate::{utils::errors::AppError, AppState};
+use axum::extract::{Extension, Query};
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct AdminSearchQuery {
+    pub q: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct SuspendAccountRequest {
+    pub kind: SuspensionKind,
+    pub reason: String,
+    pub duration_hours: Option<i64>, // Ignored for bans; omit for an indefinite suspension
+}
+
+#[derive(Deserialize)]
+pub struct LiftSuspensionRequest {
+    pub reason: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AdminResetPasswordRequest {
+    pub new_password: Option<String>, // When omitted, a reset link is emailed instead
+}
+
+#[derive(Deserialize)]
+pub struct AuditTrailQuery {
+    pub limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct RejectRequest {
+    pub reason: String,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateAdminPermissionsRequest {
+    pub overrides: serde_json::Map<String, serde_json::Value>, // capability -> bool
+}
+
+#[derive(Serialize)]
+pub struct AdminPermissionsResponse {
+    pub role: AdminRole,
+    pub effective: Vec<AdminCapability>,
+    pub overrides: serde_json::Value,
+}
+
+#[derive(Serialize)]
+pub struct AdminAccountDetail {
+    pub account: serde_json::Value,
+    pub active_suspension: Option<account_suspension::Model>,
+}
+
+/// Loads the calling admin and checks it holds the capability the route declares.
+pub(crate) async fn require_admin(
+    state: &AppState,
+    claims: &Claims,
+    capability: AdminCapability,
+) -> Result<admin::Model, AppError> {
+    if claims.user_type != "admin" {
+        return Err(AppError::Forbidden);
+    }
+
+    let admin = state
+        .admin_service
+        .get_by_id(Uuid::parse_str(&claims.sub)?)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+
+    if !admin.is_active {
+        return Err(AppError::Unauthorized);
+    }
+
+    if !AdminService::capability_granted(&admin, capability) {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(admin)
+}
+
+pub(crate) fn parse_user_type(user_type: &str) -> Result<UserType, AppError> {
+    match user_type {
+        "player" => Ok(UserType::Player),
+        "admin" => Ok(UserType::Admin),
+        "organization" => Ok(UserType::Organization),
+        _ => Err(AppError::Validation("Invalid user type".to_string())),
+    }
+}
+
+/// Only super admins act on admin accounts, so a granted capability never
+/// reaches a super admin; nobody may act on themselves.
+fn guard_target(actor: &admin::Model, user_type: &UserType, user_id: Uuid) -> Result<(), AppError> {
+    if actor.id == user_id {
+        return Err(AppError::Validation(
+            "You cannot perform this action on your own account".to_string(),
+        ));
+    }
+
+    if matches!(user_type, UserType::Admin) && actor.role != AdminRole::SuperAdmin {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(())
+}
+
+/// Returns the target account's email, which doubles as an existence check.
+pub(crate) async fn account_email(
+    state: &AppState,
+    user_type: &UserType,
+    user_id: Uuid,
+) -> Result<String, AppError> {
+    let email = match user_type {
+        UserType::Player => state
+            .player_service
+            .get_by_id(user_id)
+            .await?
+            .map(|p| p.email),
+        UserType::Admin => state
+            .admin_service
+            .get_by_id(user_id)
+            .await?
+            .map(|a| a.email),
+        UserType::Organization => state
+            .organization_service
+            .get_by_id(user_id)
+            .await?
+            .map(|o| o.email),
+    };
+
+    email.ok_or(AppError::NotFound)
+}
+
+/// Serializes a record for the admin console with credentials stripped.
+fn redact<T: Serialize>(record: T) -> Result<serde_json::Value, AppError> {
+    let mut value = serde_json::to_value(record)?;
+    if let Some(fields) = value.as_object_mut() {
+        fields.remove("password");
+    }
+    Ok(value)
+}
+
+async fn audit_admin_action(
+    state: &AppState,
+    claims: &Claims,
+    action: &str,
+    user_type: &UserType,
+    user_id: Uuid,
+    details: serde_json::Value,
+) {
+    let _ = state
+        .audit_service
+        .log_action(
+            Uuid::parse_str(&claims.sub).ok(),
+            Some("admin".to_string()),
+            Uuid::parse_str(&claims.session_id).ok(),
+            action.to_string(),
+            Some(user_type.as_str().to_string()),
+            Some(user_id),
+            None,
+            None,
+            true,
+            None,
+            None,
+            Some(details),
+        )
+        .await;
+}
+
+// ========================================
+// ACCOUNT SEARCH & RECORDS
+// ========================================
+
+pub async fn admin_search_players(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<AdminSearchQuery>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+
+    let players = state
+        .admin_service
+        .search_players(
+            params.q,
+            params.limit.unwrap_or(20).min(100),
+            params.offset.unwrap_or(0),
+        )
+        .await?
+        .into_iter()
+        .map(redact)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(ApiResponse::success(players)))
+}
+
+pub async fn admin_get_player(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<AdminAccountDetail>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+
+    let player = state
+        .player_service
+        .get_by_id(player_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(AdminAccountDetail {
+        account: redact(player)?,
+        active_suspension: state.suspension_service.get_active(player_id).await?,
+    })))
+}
+
+pub async fn admin_search_organizations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<AdminSearchQuery>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+
+    let orgs = state
+        .admin_service
+        .search_organizations(
+            params.q,
+            params.limit.unwrap_or(20).min(100),
+            params.offset.unwrap_or(0),
+        )
+        .await?
+        .into_iter()
+        .map(redact)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(ApiResponse::success(orgs)))
+}
+
+pub async fn admin_get_organization(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<AdminAccountDetail>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+
+    let org = state
+        .organization_service
+        .get_by_id(org_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(AdminAccountDetail {
+        account: redact(org)?,
+        active_suspension: state.suspension_service.get_active(org_id).await?,
+    })))
+}
+
+pub async fn admin_search_admins(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<AdminSearchQuery>,
+) -> Result<Json<ApiResponse<Vec<serde_json::Value>>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+
+    let admins = state
+        .admin_service
+        .search_admins(
+            params.q,
+            params.limit.unwrap_or(20).min(100),
+            params.offset.unwrap_or(0),
+        )
+        .await?
+        .into_iter()
+        .map(redact)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(Json(ApiResponse::success(admins)))
+}
+
+pub async fn admin_get_admin(
+    State(state): State<AppState>,
+    Path(admin_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<AdminAccountDetail>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+
+    let admin = state
+        .admin_service
+        .get_by_id(admin_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(AdminAccountDetail {
+        account: redact(admin)?,
+        active_suspension: state.suspension_service.get_active(admin_id).await?,
+    })))
+}
+
+// ========================================
+// ACCOUNT MODERATION
+// ========================================
+
+pub async fn admin_suspend_account(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<SuspendAccountRequest>,
+) -> Result<Json<ApiResponse<account_suspension::Model>>, AppError> {
+    let capability = match payload.kind {
+        SuspensionKind::Ban => AdminCapability::UsersBan,
+        SuspensionKind::Suspension => AdminCapability::UsersSuspend,
+    };
+    let actor = require_admin(&state, &claims, capability).await?;
+    let user_type = parse_user_type(&user_type)?;
+    guard_target(&actor, &user_type, user_id)?;
+    account_email(&state, &user_type, user_id).await?;
+
+    let suspension = state
+        .suspension_service
+        .suspend(
+            user_id,
+            user_type.as_str().to_string(),
+            payload.kind,
+            payload.reason,
+            actor.id,
+            payload.duration_hours,
+        )
+        .await?;
+
+    state
+        .session_service
+        .revoke_all_user_sessions(user_id)
+        .await?;
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_account_suspend",
+        &user_type,
+        user_id,
+        serde_json::json!({
+            "suspension_id": suspension.id,
+            "kind": suspension.kind.as_str(),
+            "reason": suspension.reason,
+            "expires_at": suspension.expires_at,
+        }),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(suspension)))
+}
+
+pub async fn admin_lift_suspension(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<LiftSuspensionRequest>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::UsersSuspend).await?;
+    let user_type = parse_user_type(&user_type)?;
+    guard_target(&actor, &user_type, user_id)?;
+
+    // Lifting a ban needs the same capability as issuing one
+    let active = state.suspension_service.get_active(user_id).await?;
+    if matches!(active, Some(ref s) if s.kind == SuspensionKind::Ban)
+        && !AdminService::capability_granted(&actor, AdminCapability::UsersBan)
+    {
+        return Err(AppError::Forbidden);
+    }
+
+    let lifted = state
+        .suspension_service
+        .lift(user_id, actor.id, payload.reason.clone())
+        .await?;
+    if lifted == 0 {
+        return Err(AppError::NotFound);
+    }
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_account_unsuspend",
+        &user_type,
+        user_id,
+        serde_json::json!({"lifted": lifted, "reason": payload.reason}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success("Suspension lifted".to_string())))
+}
+
+pub async fn admin_list_suspensions(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<account_suspension::Model>>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+    parse_user_type(&user_type)?;
+
+    let history = state.suspension_service.list_for_user(user_id).await?;
+    Ok(Json(ApiResponse::success(history)))
+}
+
+pub async fn admin_revoke_sessions(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::UsersRevokeSessions).await?;
+    let user_type = parse_user_type(&user_type)?;
+    guard_target(&actor, &user_type, user_id)?;
+    account_email(&state, &user_type, user_id).await?;
+
+    state
+        .session_service
+        .revoke_all_user_sessions(user_id)
+        .await?;
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_revoke_sessions",
+        &user_type,
+        user_id,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(
+        "All sessions revoked".to_string(),
+    )))
+}
+
+/// Lifts a brute-force lockout and forgets the account's recent failed logins.
+pub async fn admin_unlock_account(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::UsersUnlock).await?;
+    let user_type = parse_user_type(&user_type)?;
+    guard_target(&actor, &user_type, user_id)?;
+    account_email(&state, &user_type, user_id).await?;
+
+    let was_locked = state
+        .login_protection_service
+        .unlock(user_id, user_type.as_str())
+        .await?;
+    if matches!(user_type, UserType::Admin) {
+        state.admin_service.clear_login_lock(user_id).await?;
+    }
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_account_unlock",
+        &user_type,
+        user_id,
+        serde_json::json!({"was_locked": was_locked}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success("Account unlocked".to_string())))
+}
+
+pub async fn admin_force_verify_email(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersVerify).await?;
+    let user_type = parse_user_type(&user_type)?;
+
+    let verified = match user_type {
+        UserType::Player => state.player_service.verify_email(user_id).await?,
+        UserType::Organization => state.organization_service.verify_email(user_id).await?,
+        UserType::Admin => {
+            return Err(AppError::Validation(
+                "Admin accounts are always verified".to_string(),
+            ))
+        }
+    };
+    if !verified {
+        return Err(AppError::NotFound);
+    }
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_force_verify_email",
+        &user_type,
+        user_id,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(
+        "Email marked as verified".to_string(),
+    )))
+}
+
+pub async fn admin_reset_password(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<AdminResetPasswordRequest>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::UsersResetPassword).await?;
+    let user_type = parse_user_type(&user_type)?;
+    guard_target(&actor, &user_type, user_id)?;
+    let email = account_email(&state, &user_type, user_id).await?;
+
+    let message = match payload.new_password {
+        Some(new_password) => {
+            crate::utils::validation::validate_password(&new_password)?;
+            let hashed_password = state.auth_service.hash_password(&new_password)?;
+            match user_type {
+                UserType::Player => {
+                    state
+                        .player_service
+                        .update_password(user_id, hashed_password)
+                        .await?
+                }
+                UserType::Admin => {
+                    state
+                        .admin_service
+                        .update_password(user_id, hashed_password)
+                        .await?
+                }
+                UserType::Organization => {
+                    state
+                        .organization_service
+                        .update_password(user_id, hashed_password)
+                        .await?
+                }
+            };
+
+            // A password set by an admin invalidates everything issued under the old one
+            state
+                .session_service
+                .revoke_all_user_sessions(user_id)
+                .await?;
+            "Password updated and sessions revoked"
+        }
+        None => {
+            let token = state.auth_service.generate_temp_token(
+                user_id,
+                user_type.clone(),
+                "reset_password",
+                1, // 1 hour expiry
+            )?;
+            let _ = state
+                .email_service
+                .send_password_reset(&email, &token)
+                .await;
+            "Password reset link sent"
+        }
+    };
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_reset_password",
+        &user_type,
+        user_id,
+        serde_json::json!({"message": message}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(message.to_string())))
+}
+
+pub async fn admin_get_audit_trail(
+    State(state): State<AppState>,
+    Path((user_type, user_id)): Path<(String, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<AuditTrailQuery>,
+) -> Result<Json<ApiResponse<Vec<audit_log::Model>>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::AuditView).await?;
+    parse_user_type(&user_type)?;
+
+    let activity = state
+        .audit_service
+        .get_user_activity(user_id, params.limit.unwrap_or(100).min(500))
+        .await?;
+
+    Ok(Json(ApiResponse::success(activity)))
+}
+
+// ========================================
+// APPROVALS
+// ========================================
+
+pub async fn admin_approve_organization(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::OrgsApprove).await?;
+
+    let org = state
+        .organization_service
+        .approve_organization(org_id, actor.id)
+        .await?;
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_org_approve",
+        &UserType::Organization,
+        org_id,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(redact(org)?)))
+}
+
+pub async fn admin_reject_organization(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<RejectRequest>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::OrgsApprove).await?;
+
+    let org = state
+        .organization_service
+        .reject_organization(org_id, payload.reason.clone())
+        .await?;
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_org_reject",
+        &UserType::Organization,
+        org_id,
+        serde_json::json!({"reason": payload.reason}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(redact(org)?)))
+}
+
+pub async fn admin_approve_tournament(
+    State(state): State<AppState>,
+    Path(tournament_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<tournament::Model>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::TournamentsApprove).await?;
+
+    let tournament = state
+        .tournament_service
+        .review_tournament(tournament_id, actor.id, ApprovalStatus::Approved, None)
+        .await?;
+
+    audit_tournament_review(
+        &state,
+        &claims,
+        "admin_tournament_approve",
+        tournament_id,
+        None,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(tournament)))
+}
+
+pub async fn admin_reject_tournament(
+    State(state): State<AppState>,
+    Path(tournament_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<RejectRequest>,
+) -> Result<Json<ApiResponse<tournament::Model>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::TournamentsApprove).await?;
+
+    let tournament = state
+        .tournament_service
+        .review_tournament(
+            tournament_id,
+            actor.id,
+            ApprovalStatus::Rejected,
+            Some(payload.reason.clone()),
+        )
+        .await?;
+
+    audit_tournament_review(
+        &state,
+        &claims,
+        "admin_tournament_reject",
+        tournament_id,
+        Some(payload.reason),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(tournament)))
+}
+
+async fn audit_tournament_review(
+    state: &AppState,
+    claims: &Claims,
+    action: &str,
+    tournament_id: Uuid,
+    reason: Option<String>,
+) {
+    let _ = state
+        .audit_service
+        .log_action(
+            Uuid::parse_str(&claims.sub).ok(),
+            Some("admin".to_string()),
+            Uuid::parse_str(&claims.session_id).ok(),
+            action.to_string(),
+            Some("tournament".to_string()),
+            Some(tournament_id),
+            None,
+            None,
+            true,
+            None,
+            None,
+            Some(serde_json::json!({"reason": reason})),
+        )
+        .await;
+}
+
+// ========================================
+// ADMIN PERMISSIONS
+// ========================================
+
+pub async fn admin_get_permissions(
+    State(state): State<AppState>,
+    Path(admin_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<AdminPermissionsResponse>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::UsersView).await?;
+
+    let admin = state
+        .admin_service
+        .get_by_id(admin_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    Ok(Json(ApiResponse::success(AdminPermissionsResponse {
+        role: admin.role.clone(),
+        effective: AdminService::effective_capabilities(&admin),
+        overrides: admin.permissions,
+    })))
+}
+
+pub async fn admin_update_permissions(
+    State(state): State<AppState>,
+    Path(admin_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateAdminPermissionsRequest>,
+) -> Result<Json<ApiResponse<AdminPermissionsResponse>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::AdminsManage).await?;
+
+    // Capability overrides are never delegable: only super admins edit them
+    if actor.role != AdminRole::SuperAdmin {
+        return Err(AppError::Forbidden);
+    }
+    if actor.id == admin_id {
+        return Err(AppError::Validation(
+            "You cannot change your own permissions".to_string(),
+        ));
+    }
+
+    let admin = state
+        .admin_service
+        .update_permissions(admin_id, payload.overrides)
+        .await?;
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_permissions_update",
+        &UserType::Admin,
+        admin_id,
+        serde_json::json!({"overrides": admin.permissions}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(AdminPermissionsResponse {
+        role: admin.role.clone(),
+        effective: AdminService::effective_capabilities(&admin),
+        overrides: admin.permissions,
+    })))
+}
+
+pub async fn admin_reload_route_permissions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::AdminsManage).await?;
+
+    let count = crate::middleware::permissions::reload_permissions()
+        .map_err(|e| AppError::Validation(format!("Invalid route permission file: {}", e)))?;
+
+    audit_admin_action(
+        &state,
+        &claims,
+        "admin_route_permissions_reload",
+        &UserType::Admin,
+        actor.id,
+        serde_json::json!({"rules": count}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(format!(
+        "Loaded {} route permission rules",
+        count
+    ))))
+}
@@ -0,0 +1,284 @@
+use super::admin::require_admin;
+use super::chat::ApiResponse;
+use super::organizations::{audit_org_action, authorize};
+use crate::models::enums::{
+    AdminCapability, AnnouncementAudience, AnnouncementSeverity, GameType, OrgPermission,
+};
+use crate::models::postgres::{announcement, announcement_acknowledgement};
+use crate::services::announcement_service::{
+    AcknowledgementSummary, AnnouncementAuthor, NewAnnouncement, PlayerAnnouncement,
+};
+use crate::services::auth_service::Claims;
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{Extension, Path, Query, State},
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: u64 = 20;
+const MAX_LIMIT: u64 = 100;
+
+#[derive(Deserialize)]
+pub struct CreateAnnouncementRequest {
+    pub title: String,
+    pub body: String,
+    pub severity: Option<AnnouncementSeverity>,
+    pub audience: AnnouncementAudience,
+    pub game: Option<GameType>,
+    pub tournament_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    pub requires_ack: Option<bool>,
+    pub publish_at: Option<DateTime<Utc>>, // Omit to publish now
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl From<CreateAnnouncementRequest> for NewAnnouncement {
+    fn from(request: CreateAnnouncementRequest) -> Self {
+        Self {
+            title: request.title,
+            body: request.body,
+            severity: request.severity.unwrap_or(AnnouncementSeverity::Info),
+            audience: request.audience,
+            game: request.game,
+            tournament_id: request.tournament_id,
+            team_id: request.team_id,
+            requires_ack: request.requires_ack.unwrap_or(false),
+            publish_at: request.publish_at,
+            expires_at: request.expires_at,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+pub struct AnnouncementListQuery {
+    pub pending: Option<bool>, // Only mandatory notices not yet acknowledged
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+impl AnnouncementListQuery {
+    fn page(&self) -> (u64, u64) {
+        (
+            self.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            self.offset.unwrap_or(0),
+        )
+    }
+}
+
+/// Organizations post to tournaments as tournament managers, to teams as team managers.
+fn org_permission(audience: AnnouncementAudience) -> OrgPermission {
+    match audience {
+        AnnouncementAudience::Team => OrgPermission::ManageTeams,
+        _ => OrgPermission::ManageTournaments,
+    }
+}
+
+// ========================================
+// PLAYERS
+// ========================================
+
+pub async fn list_announcements(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<AnnouncementListQuery>,
+) -> Result<Json<ApiResponse<Vec<PlayerAnnouncement>>>, AppError> {
+    let player_id = Uuid::parse_str(&claims.sub)?;
+    let (limit, offset) = params.page();
+
+    let announcements = state
+        .announcement_service
+        .list_for_player(player_id, params.pending.unwrap_or(false), limit, offset)
+        .await?;
+    Ok(Json(ApiResponse::success(announcements)))
+}
+
+pub async fn acknowledge_announcement(
+    State(state): State<AppState>,
+    Path(announcement_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<announcement_acknowledgement::Model>>, AppError> {
+    let player_id = Uuid::parse_str(&claims.sub)?;
+
+    let acknowledgement = state
+        .announcement_service
+        .acknowledge(player_id, announcement_id)
+        .await?;
+    Ok(Json(ApiResponse::success(acknowledgement)))
+}
+
+// ========================================
+// ADMIN
+// ========================================
+
+pub async fn admin_create_announcement(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateAnnouncementRequest>,
+) -> Result<Json<ApiResponse<announcement::Model>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::AnnouncementsManage).await?;
+
+    let announcement = state
+        .announcement_service
+        .create(AnnouncementAuthor::Admin(actor.id), payload.into())
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "admin_announcement_create",
+        "announcement",
+        announcement.id,
+        serde_json::json!({"audience": announcement.audience, "requires_ack": announcement.requires_ack}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(announcement)))
+}
+
+pub async fn admin_list_announcements(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<AnnouncementListQuery>,
+) -> Result<Json<ApiResponse<Vec<announcement::Model>>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::AnnouncementsManage).await?;
+    let (limit, offset) = params.page();
+
+    let announcements = state
+        .announcement_service
+        .list_for_author(AnnouncementAuthor::Admin(actor.id), limit, offset)
+        .await?;
+    Ok(Json(ApiResponse::success(announcements)))
+}
+
+pub async fn admin_withdraw_announcement(
+    State(state): State<AppState>,
+    Path(announcement_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<announcement::Model>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::AnnouncementsManage).await?;
+
+    let announcement = state
+        .announcement_service
+        .withdraw(AnnouncementAuthor::Admin(actor.id), announcement_id)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "admin_announcement_withdraw",
+        "announcement",
+        announcement.id,
+        serde_json::json!({}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(announcement)))
+}
+
+pub async fn admin_announcement_acknowledgements(
+    State(state): State<AppState>,
+    Path(announcement_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<AcknowledgementSummary>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::AnnouncementsManage).await?;
+
+    let summary = state
+        .announcement_service
+        .acknowledgement_summary(AnnouncementAuthor::Admin(actor.id), announcement_id)
+        .await?;
+    Ok(Json(ApiResponse::success(summary)))
+}
+
+// ========================================
+// ORGANIZATIONS
+// ========================================
+
+pub async fn create_org_announcement(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateAnnouncementRequest>,
+) -> Result<Json<ApiResponse<announcement::Model>>, AppError> {
+    authorize(&state, org_id, &claims, org_permission(payload.audience)).await?;
+
+    let announcement = state
+        .announcement_service
+        .create(AnnouncementAuthor::Organization(org_id), payload.into())
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_announcement_create",
+        "announcement",
+        announcement.id,
+        serde_json::json!({"organization_id": org_id, "audience": announcement.audience}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(announcement)))
+}
+
+pub async fn list_org_announcements(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<AnnouncementListQuery>,
+) -> Result<Json<ApiResponse<Vec<announcement::Model>>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::View).await?;
+    let (limit, offset) = params.page();
+
+    let announcements = state
+        .announcement_service
+        .list_for_author(AnnouncementAuthor::Organization(org_id), limit, offset)
+        .await?;
+    Ok(Json(ApiResponse::success(announcements)))
+}
+
+pub async fn withdraw_org_announcement(
+    State(state): State<AppState>,
+    Path((org_id, announcement_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<announcement::Model>>, AppError> {
+    let author = AnnouncementAuthor::Organization(org_id);
+    authorize(&state, org_id, &claims, OrgPermission::View).await?;
+    let existing = state
+        .announcement_service
+        .get_for_author(author, announcement_id)
+        .await?;
+    authorize(&state, org_id, &claims, org_permission(existing.audience)).await?;
+
+    let announcement = state
+        .announcement_service
+        .withdraw(author, announcement_id)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_announcement_withdraw",
+        "announcement",
+        announcement.id,
+        serde_json::json!({"organization_id": org_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(announcement)))
+}
+
+pub async fn org_announcement_acknowledgements(
+    State(state): State<AppState>,
+    Path((org_id, announcement_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<AcknowledgementSummary>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::View).await?;
+
+    let summary = state
+        .announcement_service
+        .acknowledgement_summary(AnnouncementAuthor::Organization(org_id), announcement_id)
+        .await?;
+    Ok(Json(ApiResponse::success(summary)))
+}
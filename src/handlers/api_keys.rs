@@ -0,0 +1,162 @@
+use super::admin::require_admin;
+use super::chat::ApiResponse;
+use crate::models::enums::AdminCapability;
+use crate::models::postgres::api_key;
+use crate::services::auth_service::Claims;
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{Extension, Path, State},
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_hour: Option<i32>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// An API key as shown to its owner; the secret hash never leaves the server.
+#[derive(Serialize)]
+pub struct ApiKeyResponse {
+    pub id: Uuid,
+    pub key_id: String,
+    pub name: String,
+    pub scopes: Vec<String>,
+    pub rate_limit_per_hour: i32,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<api_key::Model> for ApiKeyResponse {
+    fn from(key: api_key::Model) -> Self {
+        Self {
+            id: key.id,
+            key_id: key.key_id,
+            name: key.name,
+            scopes: key.scopes,
+            rate_limit_per_hour: key.rate_limit_per_hour,
+            expires_at: key.expires_at,
+            last_used_at: key.last_used_at,
+            created_at: key.created_at,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct CreatedApiKeyResponse {
+    pub key: ApiKeyResponse,
+    pub secret: String, // Only ever returned once
+}
+
+async fn audit_admin_key_action(state: &AppState, claims: &Claims, action: &str, key_id: &str) {
+    let _ = state
+        .audit_service
+        .log_action(
+            Uuid::parse_str(&claims.sub).ok(),
+            Some("admin".to_string()),
+            Uuid::parse_str(&claims.session_id).ok(),
+            action.to_string(),
+            Some("api_key".to_string()),
+            None,
+            None,
+            None,
+            true,
+            None,
+            None,
+            Some(serde_json::json!({"key_id": key_id})),
+        )
+        .await;
+}
+
+// ========================================
+// ADMIN API KEYS (the calling admin's own keys)
+// ========================================
+
+pub async fn list_admin_api_keys(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<ApiKeyResponse>>>, AppError> {
+    let admin = require_admin(&state, &claims, AdminCapability::ApiKeysManage).await?;
+
+    let keys = state
+        .api_key_service
+        .list_owner_keys(admin.id, "admin".to_string())
+        .await?;
+    Ok(Json(ApiResponse::success(
+        keys.into_iter().map(Into::into).collect(),
+    )))
+}
+
+pub async fn create_admin_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<Json<ApiResponse<CreatedApiKeyResponse>>, AppError> {
+    let admin = require_admin(&state, &claims, AdminCapability::ApiKeysManage).await?;
+
+    let (key, secret) = state
+        .api_key_service
+        .create_api_key(
+            payload.name,
+            admin.id,
+            "admin".to_string(),
+            payload.scopes,
+            payload.rate_limit_per_hour,
+            payload.expires_at,
+        )
+        .await?;
+
+    audit_admin_key_action(&state, &claims, "admin_api_key_create", &key.key_id).await;
+
+    Ok(Json(ApiResponse::success(CreatedApiKeyResponse {
+        key: key.into(),
+        secret,
+    })))
+}
+
+pub async fn rotate_admin_api_key(
+    State(state): State<AppState>,
+    Path(key_id): Path<String>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<CreatedApiKeyResponse>>, AppError> {
+    let admin = require_admin(&state, &claims, AdminCapability::ApiKeysManage).await?;
+
+    let key = state
+        .api_key_service
+        .get_owner_key(admin.id, "admin", &key_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let (key, secret) = state.api_key_service.rotate_api_key(key).await?;
+
+    audit_admin_key_action(&state, &claims, "admin_api_key_rotate", &key_id).await;
+
+    Ok(Json(ApiResponse::success(CreatedApiKeyResponse {
+        key: key.into(),
+        secret,
+    })))
+}
+
+pub async fn revoke_admin_api_key(
+    State(state): State<AppState>,
+    Path(key_id): Path<String>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let admin = require_admin(&state, &claims, AdminCapability::ApiKeysManage).await?;
+
+    state
+        .api_key_service
+        .get_owner_key(admin.id, "admin", &key_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    state.api_key_service.revoke_api_key(key_id.clone()).await?;
+
+    audit_admin_key_action(&state, &claims, "admin_api_key_revoke", &key_id).await;
+
+    Ok(Json(ApiResponse::success("API key revoked".to_string())))
+}
@@ -1,9 +1,13 @@
+use super::admin::{account_email, parse_user_type};
+use crate::models::enums::AdminRole;
 use crate::services::auth_service::{Claims, UserType};
+use crate::services::session_service::RefreshOutcome;
 use crate::{utils::errors::AppError, AppState};
 use axum::extract::Path;
 use axum::{
     extract::{ConnectInfo, Extension, State},
     http::HeaderMap,
+    response::{IntoResponse, Response},
     Json,
 };
 use serde::{Deserialize, Serialize};
@@ -101,7 +105,7 @@ fn create_refresh_cookie(refresh_token: &str) -> String {
     )
 }
 
-fn extract_client_info(
+pub(crate) fn extract_client_info(
     headers: &HeaderMap,
     addr: Option<SocketAddr>,
 ) -> (Option<String>, Option<String>) {
@@ -156,25 +160,304 @@ fn create_auth_response_with_session(
     ))
 }
 
+/// An account that has passed the password check, with everything needed to
+/// issue a session for it.
+pub(crate) struct LoginAccount {
+    pub id: Uuid,
+    pub user_type: UserType,
+    pub verified: bool,
+    pub user: UserInfo,
+    pub two_factor_mandatory: bool,
+}
+
+impl LoginAccount {
+    pub(crate) fn player(player: crate::models::postgres::player::Model) -> Self {
+        Self {
+            id: player.id,
+            user_type: UserType::Player,
+            verified: player.verified,
+            user: UserInfo {
+                id: player.id,
+                email: player.email,
+                username: Some(player.username),
+                org_name: None,
+                user_type: "player".to_string(),
+                verified: player.verified,
+                approval_status: None,
+            },
+            two_factor_mandatory: false,
+        }
+    }
+
+    fn admin(admin: crate::models::postgres::admin::Model) -> Self {
+        Self {
+            id: admin.id,
+            user_type: UserType::Admin,
+            verified: true,
+            two_factor_mandatory: admin.role == AdminRole::SuperAdmin,
+            user: UserInfo {
+                id: admin.id,
+                email: admin.email,
+                username: Some(admin.username),
+                org_name: None,
+                user_type: "admin".to_string(),
+                verified: true,
+                approval_status: Some(if admin.is_active {
+                    "active".to_string()
+                } else {
+                    "inactive".to_string()
+                }),
+            },
+        }
+    }
+
+    fn organization(org: crate::models::postgres::organization::Model) -> Self {
+        Self {
+            id: org.id,
+            user_type: UserType::Organization,
+            verified: org.email_verified,
+            user: UserInfo {
+                id: org.id,
+                email: org.email,
+                username: None,
+                org_name: Some(org.org_name),
+                user_type: "organization".to_string(),
+                verified: org.email_verified,
+                approval_status: Some(org.approval_status.as_str().to_string()),
+            },
+            two_factor_mandatory: false,
+        }
+    }
+
+    /// Reloads an account by id, for login steps that happen after the password check.
+    pub(crate) async fn load(
+        state: &AppState,
+        user_type: &str,
+        user_id: Uuid,
+    ) -> Result<Self, AppError> {
+        let account = match user_type {
+            "player" => state
+                .player_service
+                .get_by_id(user_id)
+                .await?
+                .map(Self::player),
+            "admin" => state
+                .admin_service
+                .get_by_id(user_id)
+                .await?
+                .filter(|a| a.is_active)
+                .map(Self::admin),
+            "organization" => state
+                .organization_service
+                .get_by_id(user_id)
+                .await?
+                .map(Self::organization),
+            _ => None,
+        };
+
+        account.ok_or(AppError::Unauthorized)
+    }
+}
+
+#[derive(Serialize)]
+pub struct TwoFactorChallengeResponse {
+    pub message: String,
+    pub two_factor_required: bool,
+    pub two_factor_setup_required: bool,
+    pub pending_token: String,
+}
+
+/// Issues the session, JWT and cookies for an account that has cleared every
+/// login step.
+pub(crate) async fn complete_login(
+    state: &AppState,
+    account: LoginAccount,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+    method: &str,
+) -> Result<(HeaderMap, Json<AuthResponse>), AppError> {
+    let user_type = account.user_type.as_str().to_string();
+
+    let session = state
+        .session_service
+        .create_session(
+            account.id,
+            user_type.clone(),
+            ip_address.clone(),
+            user_agent.clone(),
+        )
+        .await?;
+
+    let token = state.auth_service.generate_jwt(
+        account.id,
+        account.user_type,
+        session.id.to_string(),
+        account.verified,
+    )?;
+
+    // Audit log
+    let _ = state
+        .audit_service
+        .log_action(
+            Some(account.id),
+            Some(user_type.clone()),
+            Some(session.id),
+            "login".to_string(),
+            Some(user_type),
+            Some(account.id),
+            ip_address,
+            user_agent,
+            true,
+            None,
+            None,
+            Some(serde_json::json!({"method": method})),
+        )
+        .await;
+
+    create_auth_response_with_session(token, session, account.user, false)
+}
+
+/// Accounts with 2FA enabled (or that must set it up) get a short-lived pending
+/// token instead of a session. Returns `None` when the login can complete.
+pub(crate) async fn two_factor_challenge(
+    state: &AppState,
+    account: &LoginAccount,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<Option<TwoFactorChallengeResponse>, AppError> {
+    let two_factor_enabled = state.two_factor_service.is_enabled(account.id).await?;
+    if !two_factor_enabled && !account.two_factor_mandatory {
+        return Ok(None);
+    }
+
+    let token_type = if two_factor_enabled {
+        "2fa_pending"
+    } else {
+        "2fa_setup"
+    };
+    let pending_token = state.auth_service.generate_temp_token_with_ttl(
+        account.id,
+        account.user_type.clone(),
+        token_type,
+        chrono::Duration::minutes(5),
+    )?;
+
+    let _ = state
+        .audit_service
+        .log_action(
+            Some(account.id),
+            Some(account.user_type.as_str().to_string()),
+            None,
+            "login_2fa_challenge".to_string(),
+            Some(account.user_type.as_str().to_string()),
+            Some(account.id),
+            ip_address,
+            user_agent,
+            true,
+            None,
+            None,
+            Some(serde_json::json!({"token_type": token_type})),
+        )
+        .await;
+
+    Ok(Some(TwoFactorChallengeResponse {
+        message: if two_factor_enabled {
+            "Enter the code from your authenticator app.".to_string()
+        } else {
+            "Two-factor authentication must be set up before signing in.".to_string()
+        },
+        two_factor_required: two_factor_enabled,
+        two_factor_setup_required: !two_factor_enabled,
+        pending_token,
+    }))
+}
+
+/// Counts a failed password login against the client and, when known, the
+/// account. Emails the account owner when this failure locks the account.
+async fn handle_failed_login(
+    state: &AppState,
+    email: &str,
+    account: Option<(Uuid, &str)>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<(), AppError> {
+    let locked_until = state
+        .login_protection_service
+        .record_failure(email, ip_address.clone(), account)
+        .await?;
+
+    let Some((user_id, user_type)) = account else {
+        return Ok(());
+    };
+
+    // Audit log
+    let _ = state
+        .audit_service
+        .log_action(
+            Some(user_id),
+            Some(user_type.to_string()),
+            None,
+            "login_failed".to_string(),
+            Some(user_type.to_string()),
+            Some(user_id),
+            ip_address.clone(),
+            user_agent.clone(),
+            false,
+            Some("Invalid credentials".to_string()),
+            None,
+            None,
+        )
+        .await;
+
+    if let Some(locked_until) = locked_until {
+        let _ = state
+            .audit_service
+            .log_action(
+                Some(user_id),
+                Some(user_type.to_string()),
+                None,
+                "account_locked".to_string(),
+                Some(user_type.to_string()),
+                Some(user_id),
+                ip_address,
+                user_agent,
+                true,
+                None,
+                None,
+                Some(serde_json::json!({ "locked_until": locked_until })),
+            )
+            .await;
+
+        let _ = state
+            .email_service
+            .send_security_alert(
+                email,
+                "Account temporarily locked",
+                &format!(
+                    "We blocked sign-in to your account after several failed password attempts. \
+                     You can try again after {} UTC. If this wasn't you, consider changing your password.",
+                    locked_until.format("%Y-%m-%d %H:%M")
+                ),
+            )
+            .await;
+    }
+
+    Ok(())
+}
+
 pub async fn login(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
     headers: HeaderMap,
     Json(payload): Json<LoginRequest>,
-) -> Result<(HeaderMap, Json<AuthResponse>), AppError> {
+) -> Result<Response, AppError> {
     let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
 
-    // Rate limiting check
-    if let Some(ip) = &ip_address {
-        let is_blocked = state
-            .rate_limit_service
-            .is_blocked(ip.clone(), "ip".to_string(), "login".to_string())
-            .await?;
-
-        if is_blocked {
-            return Err(AppError::RateLimited);
-        }
-    }
+    // Brute-force protection: recent failures from this client or against this email
+    state
+        .login_protection_service
+        .check_client(ip_address.as_deref(), &payload.email)
+        .await?;
 
     let user_info = sqlx::query!(
         "SELECT id, 'player' as user_type FROM players WHERE email = $1
@@ -187,192 +470,70 @@ pub async fn login(
     )
     .fetch_optional(&state.sql_pool)
     .await?
-    .ok_or(AppError::Unauthorized)?;
+    .and_then(|u| Some((u.id?, u.user_type?)));
 
-    match user_info.user_type.as_deref() {
-        Some("player") => {
-            let user_id = user_info.id.ok_or(AppError::Unauthorized)?; // Fix: unwrap the Option
-            let (player, _) = state
-                .player_service
-                .authenticate_by_id(user_id, payload.password) // Use unwrapped user_id
-                .await?
-                .ok_or(AppError::Unauthorized)?;
-
-            let session = state
-                .session_service
-                .create_session(
-                    player.id,
-                    "player".to_string(),
-                    ip_address.clone(),
-                    user_agent.clone(),
-                )
-                .await?;
-
-            let token = state.auth_service.generate_jwt(
-                player.id,
-                UserType::Player,
-                session.id.to_string(),
-                player.verified,
-            )?;
-
-            // Audit log
-            let _ = state
-                .audit_service
-                .log_action(
-                    Some(player.id),
-                    Some("player".to_string()),
-                    Some(session.id),
-                    "login".to_string(),
-                    Some("player".to_string()),
-                    Some(player.id),
-                    ip_address.clone(),
-                    user_agent.clone(),
-                    true,
-                    None,
-                    None,
-                    None,
-                )
-                .await;
-
-            create_auth_response_with_session(
-                token,
-                session,
-                UserInfo {
-                    id: player.id,
-                    email: player.email,
-                    username: Some(player.username),
-                    org_name: None,
-                    user_type: "player".to_string(),
-                    verified: player.verified,
-                    approval_status: None,
-                },
-                false,
-            )
-        }
-        Some("admin") => {
-            let user_id = user_info.id.ok_or(AppError::Unauthorized)?; // Fix: unwrap the Option
-            let (admin, _) = state
-                .admin_service
-                .authenticate_by_id(user_id, payload.password) // Use unwrapped user_id
-                .await?
-                .ok_or(AppError::Unauthorized)?;
+    let Some((user_id, user_type)) = user_info else {
+        handle_failed_login(&state, &payload.email, None, ip_address, user_agent).await?;
+        return Err(AppError::Unauthorized);
+    };
 
-            let session = state
-                .session_service
-                .create_session(
-                    admin.id,
-                    "admin".to_string(),
-                    ip_address.clone(),
-                    user_agent.clone(),
-                )
-                .await?;
-
-            let token = state.auth_service.generate_jwt(
-                admin.id,
-                UserType::Admin,
-                session.id.to_string(),
-                true,
-            )?;
+    state
+        .login_protection_service
+        .check_account(user_id, &user_type)
+        .await?;
 
-            // Audit log
-            let _ = state
-                .audit_service
-                .log_action(
-                    Some(admin.id),
-                    Some("admin".to_string()),
-                    Some(session.id),
-                    "login".to_string(),
-                    Some("admin".to_string()),
-                    Some(admin.id),
-                    ip_address.clone(),
-                    user_agent.clone(),
-                    true,
-                    None,
-                    None,
-                    None,
-                )
-                .await;
+    let account = match user_type.as_str() {
+        "player" => state
+            .player_service
+            .authenticate_by_id(user_id, payload.password)
+            .await?
+            .map(|(player, _)| LoginAccount::player(player)),
+        "admin" => state
+            .admin_service
+            .authenticate_by_id(user_id, payload.password)
+            .await?
+            .map(|(admin, _)| LoginAccount::admin(admin)),
+        "organization" => state
+            .organization_service
+            .authenticate_by_id(user_id, payload.password)
+            .await?
+            .map(|(org, _)| LoginAccount::organization(org)),
+        _ => None,
+    };
 
-            create_auth_response_with_session(
-                token,
-                session,
-                UserInfo {
-                    id: admin.id,
-                    email: admin.email,
-                    username: Some(admin.username),
-                    org_name: None,
-                    user_type: "admin".to_string(),
-                    verified: true,
-                    approval_status: Some(if admin.is_active {
-                        "active".to_string()
-                    } else {
-                        "inactive".to_string()
-                    }),
-                },
-                false,
-            )
-        }
-        Some("organization") => {
-            let user_id = user_info.id.ok_or(AppError::Unauthorized)?; // Fix: unwrap the Option
-            let (org, _) = state
-                .organization_service
-                .authenticate_by_id(user_id, payload.password) // Use unwrapped user_id
-                .await?
-                .ok_or(AppError::Unauthorized)?;
+    let Some(account) = account else {
+        handle_failed_login(
+            &state,
+            &payload.email,
+            Some((user_id, &user_type)),
+            ip_address,
+            user_agent,
+        )
+        .await?;
+        return Err(AppError::Unauthorized);
+    };
 
-            let session = state
-                .session_service
-                .create_session(
-                    org.id,
-                    "organization".to_string(),
-                    ip_address.clone(),
-                    user_agent.clone(),
-                )
-                .await?;
-
-            let token = state.auth_service.generate_jwt(
-                org.id,
-                UserType::Organization,
-                session.id.to_string(),
-                org.email_verified,
-            )?;
+    state
+        .login_protection_service
+        .record_success(user_id, &user_type)
+        .await?;
 
-            // Audit log
-            let _ = state
-                .audit_service
-                .log_action(
-                    Some(org.id),
-                    Some("organization".to_string()),
-                    Some(session.id),
-                    "login".to_string(),
-                    Some("organization".to_string()),
-                    Some(org.id),
-                    ip_address.clone(),
-                    user_agent.clone(),
-                    true,
-                    None,
-                    None,
-                    None,
-                )
-                .await;
+    state
+        .suspension_service
+        .ensure_not_suspended(account.id)
+        .await?;
 
-            create_auth_response_with_session(
-                token,
-                session,
-                UserInfo {
-                    id: org.id,
-                    email: org.email,
-                    username: None,
-                    org_name: Some(org.org_name),
-                    user_type: "organization".to_string(),
-                    verified: org.email_verified,
-                    approval_status: Some(org.approval_status.as_str().to_string()),
-                },
-                false,
-            )
-        }
-        _ => Err(AppError::Unauthorized),
+    if let Some(challenge) =
+        two_factor_challenge(&state, &account, ip_address.clone(), user_agent.clone()).await?
+    {
+        return Ok(Json(challenge).into_response());
     }
+
+    Ok(
+        complete_login(&state, account, ip_address, user_agent, "password")
+            .await?
+            .into_response(),
+    )
 }
 
 pub async fn register(
@@ -461,20 +622,7 @@ async fn register_player(
         }
     };
 
-    println!("DEBUG: About to generate verification token");
-    let verification_token = state.auth_service.generate_temp_token(
-        player.id,
-        UserType::Player,
-        "verify_email",
-        24, // 24 hours expiry
-    )?;
-
-    println!("DEBUG: About to send verification email");
-    let _ = state
-        .email_service
-        .send_verification_email(&player.email, &verification_token)
-        .await;
-    println!("DEBUG: Email sending completed (may have failed silently)");
+    // The verification email goes out from the outbox (player.registered)
 
     println!("DEBUG: About to log audit action");
     let _ = state
@@ -671,6 +819,58 @@ async fn register_organization(
     result
 }
 
+/// A rotated refresh token came back, so it was copied. The session family is
+/// already revoked; record the event and warn the account owner.
+async fn handle_refresh_token_reuse(
+    state: &AppState,
+    session: &crate::models::postgres::user_session::Model,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) {
+    let _ = state
+        .audit_service
+        .log_action(
+            Some(session.user_id),
+            Some(session.user_type.clone()),
+            Some(session.id),
+            "refresh_token_reuse".to_string(),
+            Some("session".to_string()),
+            Some(session.family_id),
+            ip_address.clone(),
+            user_agent,
+            false,
+            Some("Rotated refresh token presented again; session family revoked".to_string()),
+            None,
+            Some(serde_json::json!({
+                "family_id": session.family_id,
+                "original_ip": session.ip_address,
+            })),
+        )
+        .await;
+
+    if !state.settings.session.reuse_alert_email {
+        return;
+    }
+    let Ok(user_type) = parse_user_type(&session.user_type) else {
+        return;
+    };
+    if let Ok(email) = account_email(state, &user_type, session.user_id).await {
+        let _ = state
+            .email_service
+            .send_security_alert(
+                &email,
+                "We signed you out for your safety",
+                &format!(
+                    "A sign-in token for your account was used twice, which can mean it was copied from one of your devices{}. We have signed that login out everywhere. If this wasn't you, change your password.",
+                    ip_address
+                        .map(|ip| format!(" (last seen from {})", ip))
+                        .unwrap_or_default()
+                ),
+            )
+            .await;
+    }
+}
+
 pub async fn refresh_token(
     State(state): State<AppState>,
     ConnectInfo(addr): ConnectInfo<SocketAddr>,
@@ -682,11 +882,18 @@ pub async fn refresh_token(
     let refresh_token =
         extract_refresh_token_from_cookies(&headers).ok_or(AppError::Unauthorized)?;
 
-    let session = state
+    let session = match state
         .session_service
-        .refresh_session(&refresh_token)
+        .refresh_session(&refresh_token, ip_address.clone(), user_agent.clone())
         .await?
-        .ok_or(AppError::Unauthorized)?;
+    {
+        RefreshOutcome::Rotated(session) => session,
+        RefreshOutcome::Reused(session) => {
+            handle_refresh_token_reuse(&state, &session, ip_address, user_agent).await;
+            return Err(AppError::Unauthorized);
+        }
+        RefreshOutcome::Invalid => return Err(AppError::Unauthorized),
+    };
 
     let user_id = session.user_id;
     let user_type = &session.user_type;
@@ -1,12 +1,19 @@
-use crate::models::postgres::{chat, chat_message};
+use crate::models::enums::ChatMemberRole;
+use crate::models::postgres::{chat, chat_member, chat_message_edit};
 use crate::services::auth_service::Claims;
+use crate::services::chat_realtime_service::{
+    MESSAGE_DELETED, MESSAGE_EDITED, MESSAGE_NEW, MESSAGE_REACTIONS, READ,
+};
+use crate::services::chat_service::{
+    ChatMessageView, MessagePage, MessageSearchHit, NewChatMessage, DEFAULT_PAGE_SIZE,
+};
 use crate::{utils::errors::AppError, AppState};
 use axum::extract::Extension;
 use axum::{
     extract::{Path, Query, State},
-    http::StatusCode,
     response::Json,
 };
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
@@ -18,13 +25,58 @@ pub struct CreateChatRequest {
 
 #[derive(Deserialize)]
 pub struct SendMessageRequest {
+    #[serde(default)]
     pub message: String,
     pub message_type: Option<String>,
+    pub reply_to: Option<Uuid>,
+    #[serde(default)]
+    pub attachment_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct EditMessageRequest {
+    pub message: String,
 }
 
 #[derive(Deserialize)]
 pub struct GetMessagesQuery {
-    pub limit: Option<i32>,
+    pub limit: Option<u64>,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct MessageContextQuery {
+    pub limit: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct SearchMessagesQuery {
+    pub q: String,
+    pub chat_id: Option<Uuid>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct AddChatMemberRequest {
+    pub player_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateChatMemberRequest {
+    pub role: ChatMemberRole,
+}
+
+#[derive(Deserialize)]
+pub struct MuteChatMemberRequest {
+    pub muted: bool,
+    pub until: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize)]
+pub struct MarkReadRequest {
+    pub message_id: Uuid,
 }
 
 #[derive(Serialize)]
@@ -56,98 +108,343 @@ pub async fn create_chat(
     State(state): State<AppState>,
     Extension(claims): Extension<Claims>,
     Json(payload): Json<CreateChatRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    let creator_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::BAD_REQUEST)?;
-    match state
-        .chat_service
-        .create_chat(payload.name, payload.chat_type, creator_id.to_string()) // Use JWT user ID
-        .await
-    {
-        Ok(chat_id) => Ok(Json(ApiResponse::success(chat_id))),
-        Err(e) => {
-            tracing::error!("Failed to create chat: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let creator_id = Uuid::parse_str(&claims.sub)?;
+    let chat = state
+        .chat_service
+        .create_chat(payload.name, payload.chat_type, creator_id)
+        .await?;
+    Ok(Json(ApiResponse::success(chat.id.to_string())))
 }
 
 pub async fn get_chat(
     State(state): State<AppState>,
-    Path(chat_id): Path<String>,
-) -> Result<Json<ApiResponse<chat::Model>>, StatusCode> {
-    match state.chat_service.get_chat(&chat_id).await {
-        Ok(Some(chat)) => Ok(Json(ApiResponse::success(chat))),
-        Ok(None) => Err(StatusCode::NOT_FOUND),
-        Err(e) => {
-            tracing::error!("Failed to get chat: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+    Path(chat_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<chat::Model>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    state.chat_service.require_member(chat_id, user_id).await?;
+
+    let chat = state
+        .chat_service
+        .get_chat(chat_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    Ok(Json(ApiResponse::success(chat)))
 }
 
 pub async fn send_message(
     State(state): State<AppState>,
-    Path(chat_id): Path<String>,
+    Path(chat_id): Path<Uuid>,
     Extension(claims): Extension<Claims>,
     Json(payload): Json<SendMessageRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    let sender_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::BAD_REQUEST)?;
+) -> Result<Json<ApiResponse<ChatMessageView>>, AppError> {
+    let sender_id = Uuid::parse_str(&claims.sub)?;
 
-    match state
+    let message = state
         .chat_service
         .send_message(
             chat_id,
-            sender_id.to_string(),
-            payload.message,
-            payload.message_type.unwrap_or_else(|| "text".to_string()),
+            sender_id,
+            NewChatMessage {
+                message: payload.message,
+                message_type: payload.message_type.unwrap_or_else(|| "text".to_string()),
+                reply_to: payload.reply_to,
+                attachment_ids: payload.attachment_ids,
+            },
         )
-        .await
-    {
-        Ok(message_id) => Ok(Json(ApiResponse::success(message_id))),
-        Err(e) => {
-            tracing::error!("Failed to send message: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
+        .await?;
+
+    if let Ok(data) = serde_json::to_value(&message) {
+        state
+            .chat_realtime_service
+            .broadcast(chat_id, MESSAGE_NEW, data)
+            .await;
     }
+    Ok(Json(ApiResponse::success(message)))
 }
 
 pub async fn get_messages(
     State(state): State<AppState>,
-    Path(chat_id): Path<String>,
+    Path(chat_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
     Query(params): Query<GetMessagesQuery>,
-) -> Result<Json<ApiResponse<Vec<chat_message::Model>>>, StatusCode> {
-    match state
-        .chat_service
-        .get_messages(&chat_id, params.limit)
-        .await
-    {
-        Ok(messages) => Ok(Json(ApiResponse::success(messages))),
-        Err(e) => {
-            tracing::error!("Failed to get messages: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
+) -> Result<Json<ApiResponse<MessagePage>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let page = state
+        .chat_service
+        .get_messages(
+            chat_id,
+            user_id,
+            params.before.as_deref(),
+            params.after.as_deref(),
+            params.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(page)))
+}
+
+pub async fn get_message_context(
+    State(state): State<AppState>,
+    Path((chat_id, message_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<MessageContextQuery>,
+) -> Result<Json<ApiResponse<MessagePage>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let page = state
+        .chat_service
+        .message_context(
+            chat_id,
+            user_id,
+            message_id,
+            params.limit.unwrap_or(DEFAULT_PAGE_SIZE),
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(page)))
+}
+
+pub async fn search_messages(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<SearchMessagesQuery>,
+) -> Result<Json<ApiResponse<Vec<MessageSearchHit>>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let hits = state
+        .chat_service
+        .search_messages(
+            user_id,
+            &params.q,
+            params.chat_id,
+            params.limit.unwrap_or(20),
+            params.offset.unwrap_or(0),
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(hits)))
+}
+
+pub async fn edit_message(
+    State(state): State<AppState>,
+    Path((chat_id, message_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<EditMessageRequest>,
+) -> Result<Json<ApiResponse<ChatMessageView>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let message = state
+        .chat_service
+        .edit_message(chat_id, user_id, message_id, payload.message)
+        .await?;
+
+    if let Ok(data) = serde_json::to_value(&message) {
+        state
+            .chat_realtime_service
+            .broadcast(chat_id, MESSAGE_EDITED, data)
+            .await;
     }
+    Ok(Json(ApiResponse::success(message)))
+}
+
+pub async fn delete_message(
+    State(state): State<AppState>,
+    Path((chat_id, message_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let message = state
+        .chat_service
+        .delete_message(chat_id, user_id, message_id)
+        .await?;
+
+    state
+        .chat_realtime_service
+        .broadcast(
+            chat_id,
+            MESSAGE_DELETED,
+            serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "deleted_at": message.deleted_at,
+            }),
+        )
+        .await;
+    Ok(Json(ApiResponse::success("Message deleted".to_string())))
+}
+
+pub async fn get_message_edits(
+    State(state): State<AppState>,
+    Path((chat_id, message_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<chat_message_edit::Model>>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let edits = state
+        .chat_service
+        .message_edits(chat_id, user_id, message_id)
+        .await?;
+    Ok(Json(ApiResponse::success(edits)))
+}
+
+pub async fn add_reaction(
+    State(state): State<AppState>,
+    Path((chat_id, message_id, emoji)): Path<(Uuid, Uuid, String)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let reactions = state
+        .chat_service
+        .add_reaction(chat_id, user_id, message_id, &emoji)
+        .await?;
+    broadcast_reactions(&state, chat_id, message_id, &reactions).await;
+    Ok(Json(ApiResponse::success(reactions)))
+}
+
+pub async fn remove_reaction(
+    State(state): State<AppState>,
+    Path((chat_id, message_id, emoji)): Path<(Uuid, Uuid, String)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<serde_json::Value>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let reactions = state
+        .chat_service
+        .remove_reaction(chat_id, user_id, message_id, &emoji)
+        .await?;
+    broadcast_reactions(&state, chat_id, message_id, &reactions).await;
+    Ok(Json(ApiResponse::success(reactions)))
+}
+
+async fn broadcast_reactions(
+    state: &AppState,
+    chat_id: Uuid,
+    message_id: Uuid,
+    reactions: &serde_json::Value,
+) {
+    state
+        .chat_realtime_service
+        .broadcast(
+            chat_id,
+            MESSAGE_REACTIONS,
+            serde_json::json!({
+                "chat_id": chat_id,
+                "message_id": message_id,
+                "reactions": reactions,
+            }),
+        )
+        .await;
 }
 
 pub async fn join_chat(
     State(state): State<AppState>,
-    Path((chat_id, user_id)): Path<(String, String)>,
+    Path(chat_id): Path<Uuid>,
     Extension(claims): Extension<Claims>,
 ) -> Result<Json<ApiResponse<String>>, AppError> {
-    // Extract user_id from path and claims
-    let requesting_user_id = Uuid::parse_str(&claims.sub)?;
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    state.chat_service.join_chat(chat_id, user_id).await?;
+    Ok(Json(ApiResponse::success(
+        "Joined chat successfully".to_string(),
+    )))
+}
 
-    // Authorization check
-    if requesting_user_id.to_string() != user_id {
-        return Err(AppError::Forbidden);
-    }
-    match state.chat_service.join_chat(&chat_id, &user_id).await {
-        Ok(_) => Ok(Json(ApiResponse::success(
-            "Joined chat successfully".to_string(),
-        ))),
-        Err(e) => {
-            tracing::error!("Failed to join chat: {}", e);
-            Err(AppError::InternalServerError)
-        }
+pub async fn leave_chat(
+    State(state): State<AppState>,
+    Path(chat_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    state.chat_service.leave_chat(chat_id, user_id).await?;
+    state.chat_realtime_service.evict(chat_id, user_id).await;
+    Ok(Json(ApiResponse::success("Left chat".to_string())))
+}
+
+pub async fn list_chat_members(
+    State(state): State<AppState>,
+    Path(chat_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<chat_member::Model>>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let members = state.chat_service.list_members(chat_id, user_id).await?;
+    Ok(Json(ApiResponse::success(members)))
+}
+
+pub async fn add_chat_member(
+    State(state): State<AppState>,
+    Path(chat_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<AddChatMemberRequest>,
+) -> Result<Json<ApiResponse<chat_member::Model>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let member = state
+        .chat_service
+        .add_member(chat_id, user_id, payload.player_id)
+        .await?;
+    Ok(Json(ApiResponse::success(member)))
+}
+
+pub async fn update_chat_member(
+    State(state): State<AppState>,
+    Path((chat_id, player_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateChatMemberRequest>,
+) -> Result<Json<ApiResponse<chat_member::Model>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let member = state
+        .chat_service
+        .set_member_role(chat_id, user_id, player_id, payload.role)
+        .await?;
+    Ok(Json(ApiResponse::success(member)))
+}
+
+pub async fn remove_chat_member(
+    State(state): State<AppState>,
+    Path((chat_id, player_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    state
+        .chat_service
+        .remove_member(chat_id, user_id, player_id)
+        .await?;
+    state.chat_realtime_service.evict(chat_id, player_id).await;
+    Ok(Json(ApiResponse::success("Member removed".to_string())))
+}
+
+pub async fn mute_chat_member(
+    State(state): State<AppState>,
+    Path((chat_id, player_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<MuteChatMemberRequest>,
+) -> Result<Json<ApiResponse<chat_member::Model>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let member = state
+        .chat_service
+        .set_member_muted(chat_id, user_id, player_id, payload.muted, payload.until)
+        .await?;
+    Ok(Json(ApiResponse::success(member)))
+}
+
+pub async fn mark_chat_read(
+    State(state): State<AppState>,
+    Path(chat_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<MarkReadRequest>,
+) -> Result<Json<ApiResponse<chat_member::Model>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let member = state
+        .chat_service
+        .mark_read(chat_id, user_id, payload.message_id)
+        .await?;
+
+    if member.last_read_message_id == Some(payload.message_id) {
+        state
+            .chat_realtime_service
+            .broadcast(
+                chat_id,
+                READ,
+                serde_json::json!({
+                    "chat_id": chat_id,
+                    "user_id": user_id,
+                    "message_id": payload.message_id,
+                    "read_at": member.last_read_at,
+                }),
+            )
+            .await;
     }
+    Ok(Json(ApiResponse::success(member)))
 }
@@ -0,0 +1,183 @@
+use crate::middleware::auth::{authenticate_token, token_from_headers};
+use crate::services::chat_realtime_service::{ChatRealtimeService, CHAT_NAMESPACE, READ, TYPING};
+use crate::utils::errors::AppError;
+use crate::AppState;
+use serde::Deserialize;
+use socketioxide::extract::{AckSender, Data, SocketRef, TryData};
+use uuid::Uuid;
+
+/// Handshake auth: `io("/chat", { auth: { token } })`. Browsers can also rely
+/// on the `token` cookie, like the REST API.
+#[derive(Deserialize)]
+pub struct SocketAuth {
+    pub token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ChatRef {
+    chat_id: Uuid,
+}
+
+#[derive(Deserialize)]
+struct TypingPayload {
+    chat_id: Uuid,
+    is_typing: bool,
+}
+
+#[derive(Deserialize)]
+struct ReadPayload {
+    chat_id: Uuid,
+    message_id: Uuid,
+}
+
+/// Sets up the `/chat` namespace. Sockets authenticate on connect with the same
+/// JWT and session checks as the REST API, then `chat:join` the rooms of chats
+/// they are members of to receive `message:*`, `typing` and `read` events.
+pub fn register_chat_namespace(state: AppState) {
+    let io = state.chat_realtime_service.io().clone();
+    io.ns(
+        CHAT_NAMESPACE,
+        move |socket: SocketRef, TryData(auth): TryData<SocketAuth>| async move {
+            let token = auth.ok().and_then(|a| a.token);
+            let (user_id, session_id) = match authenticate_socket(&state, &socket, token).await {
+                Ok(user) => user,
+                Err(e) => {
+                    let _ = socket.emit("error", serde_json::json!({ "message": e.to_string() }));
+                    let _ = socket.disconnect();
+                    return;
+                }
+            };
+
+            // Announcements and other player-wide pushes
+            let _ = socket.join(ChatRealtimeService::player_room(user_id));
+            register_events(&socket, state, user_id, session_id);
+        },
+    );
+}
+
+/// The user id and session id behind the handshake token.
+async fn authenticate_socket(
+    state: &AppState,
+    socket: &SocketRef,
+    token: Option<String>,
+) -> Result<(Uuid, String), AppError> {
+    let token = token
+        .or_else(|| token_from_headers(&socket.req_parts().headers))
+        .ok_or(AppError::Unauthorized)?;
+    let claims = authenticate_token(state, &token).await?;
+
+    // Same requirement as the /chats routes
+    if !claims.verified {
+        return Err(AppError::Forbidden);
+    }
+    Ok((Uuid::parse_str(&claims.sub)?, claims.session_id))
+}
+
+/// Whether the socket's session is still live and the user still a member.
+/// Sessions can be revoked while the socket stays open.
+async fn may_use_chat(state: &AppState, session_id: &str, chat_id: Uuid, user_id: Uuid) -> bool {
+    match state.session_service.validate_session(session_id).await {
+        Ok(Some(_)) => state
+            .chat_service
+            .is_member(chat_id, user_id)
+            .await
+            .unwrap_or(false),
+        _ => false,
+    }
+}
+
+fn in_room(socket: &SocketRef, chat_id: Uuid) -> bool {
+    let room = ChatRealtimeService::room(chat_id);
+    socket
+        .rooms()
+        .is_ok_and(|rooms| rooms.iter().any(|r| *r == room))
+}
+
+fn register_events(socket: &SocketRef, state: AppState, user_id: Uuid, session_id: String) {
+    let join_state = state.clone();
+    let join_session_id = session_id.clone();
+    socket.on(
+        "chat:join",
+        move |socket: SocketRef, Data(payload): Data<ChatRef>, ack: AckSender| async move {
+            let allowed =
+                may_use_chat(&join_state, &join_session_id, payload.chat_id, user_id).await;
+            if allowed
+                && socket
+                    .join(ChatRealtimeService::room(payload.chat_id))
+                    .is_ok()
+            {
+                let _ = ack.send(serde_json::json!({ "ok": true }));
+            } else {
+                let _ = ack.send(serde_json::json!({ "ok": false, "error": "Forbidden" }));
+            }
+        },
+    );
+
+    socket.on(
+        "chat:leave",
+        |socket: SocketRef, Data(payload): Data<ChatRef>| {
+            let _ = socket.leave(ChatRealtimeService::room(payload.chat_id));
+        },
+    );
+
+    let typing_state = state.clone();
+    socket.on(
+        TYPING,
+        move |socket: SocketRef, Data(payload): Data<TypingPayload>| async move {
+            if !in_room(&socket, payload.chat_id) {
+                return;
+            }
+            if !may_use_chat(&typing_state, &session_id, payload.chat_id, user_id).await {
+                let _ = socket.leave(ChatRealtimeService::room(payload.chat_id));
+                return;
+            }
+            typing_state
+                .chat_realtime_service
+                .broadcast(
+                    payload.chat_id,
+                    TYPING,
+                    serde_json::json!({
+                        "chat_id": payload.chat_id,
+                        "user_id": user_id,
+                        "is_typing": payload.is_typing,
+                    }),
+                )
+                .await;
+        },
+    );
+
+    socket.on(
+        READ,
+        move |socket: SocketRef, Data(payload): Data<ReadPayload>| async move {
+            if !in_room(&socket, payload.chat_id) {
+                return;
+            }
+            // Also moves the member's read pointer; stale acks aren't rebroadcast
+            let member = match state
+                .chat_service
+                .mark_read(payload.chat_id, user_id, payload.message_id)
+                .await
+            {
+                Ok(member) if member.last_read_message_id == Some(payload.message_id) => member,
+                Ok(_) => return,
+                Err(e) => {
+                    tracing::debug!("Ignoring read receipt from {}: {:?}", user_id, e);
+                    return;
+                }
+            };
+            state
+                .chat_realtime_service
+                .broadcast(
+                    payload.chat_id,
+                    READ,
+                    serde_json::json!({
+                        "chat_id": payload.chat_id,
+                        "user_id": user_id,
+                        "message_id": payload.message_id,
+                        "read_at": member.last_read_at,
+                    }),
+                )
+                .await;
+        },
+    );
+}
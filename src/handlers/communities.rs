@@ -1,10 +1,14 @@
 use super::chat::ApiResponse;
-use crate::models::postgres::{community, community_member, community_post};
+use crate::models::enums::CommunityPostType;
+use crate::models::postgres::{community, community_member, post_comment};
 use crate::services::auth_service::Claims;
+use crate::services::community_service::{
+    CommentNode, CommentPage, FeedPage, FeedQuery, NewPost, PostAttachment, PostView, VoteTally,
+};
 use crate::{utils::errors::AppError, AppState};
 use axum::extract::Extension;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::Json,
 };
@@ -19,9 +23,23 @@ pub struct CreateCommunityRequest {
 }
 
 #[derive(Deserialize)]
-pub struct AddPostToCommunityRequest {
-    pub post_id: String,
-    pub pinned: Option<bool>,
+pub struct CreatePostRequest {
+    pub title: String,
+    pub body: Option<String>,                 // Markdown
+    pub post_type: Option<CommunityPostType>, // Defaults to text
+    pub tags: Option<Vec<String>>,
+    pub attachments: Option<Vec<PostAttachment>>,
+}
+
+#[derive(Deserialize)]
+pub struct CreateCommentRequest {
+    pub content: String,
+    pub parent_id: Option<Uuid>, // Set when replying to a comment
+}
+
+#[derive(Deserialize)]
+pub struct VoteRequest {
+    pub value: i16, // 1, -1, or 0 to clear
 }
 
 pub async fn create_community(
@@ -62,43 +80,161 @@ pub async fn get_community(
     }
 }
 
-pub async fn add_post_to_community(
+pub async fn create_post(
     State(state): State<AppState>,
-    Path(community_id): Path<String>,
+    Path(community_id): Path<Uuid>,
     Extension(claims): Extension<Claims>,
-    Json(payload): Json<AddPostToCommunityRequest>,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
-    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| StatusCode::BAD_REQUEST)?;
-    let pinned = payload.pinned.unwrap_or(false);
+    Json(payload): Json<CreatePostRequest>,
+) -> Result<Json<ApiResponse<PostView>>, AppError> {
+    let author_id = Uuid::parse_str(&claims.sub)?;
+    let post = state
+        .community_service
+        .create_post(
+            community_id,
+            author_id,
+            NewPost {
+                title: payload.title,
+                body: payload.body.unwrap_or_default(),
+                post_type: payload.post_type.unwrap_or(CommunityPostType::Text),
+                tags: payload.tags.unwrap_or_default(),
+                attachments: payload.attachments.unwrap_or_default(),
+            },
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(post)))
+}
 
-    match state
+pub async fn get_post(
+    State(state): State<AppState>,
+    Path((community_id, post_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<PostView>>, AppError> {
+    let viewer_id = Uuid::parse_str(&claims.sub)?;
+    // Only players have views to count; other accounts just read the post
+    let post = if claims.user_type == "player" {
+        state
+            .community_service
+            .view_post(community_id, post_id, viewer_id)
+            .await?
+    } else {
+        state
+            .community_service
+            .get_post(community_id, post_id, viewer_id)
+            .await?
+    };
+    Ok(Json(ApiResponse::success(post)))
+}
+
+pub async fn delete_post(
+    State(state): State<AppState>,
+    Path((community_id, post_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor_id = Uuid::parse_str(&claims.sub)?;
+    state
         .community_service
-        .add_post_to_community(community_id, payload.post_id, pinned, user_id.to_string())
-        .await
-    {
-        Ok(id) => Ok(Json(ApiResponse::success(id))),
-        Err(e) => {
-            tracing::error!("Failed to add post to community: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+        .delete_post(community_id, post_id, actor_id)
+        .await?;
+    Ok(Json(ApiResponse::success("Post deleted".to_string())))
 }
 
-pub async fn get_community_posts(
+pub async fn vote_post(
     State(state): State<AppState>,
-    Path(community_id): Path<String>,
-) -> Result<Json<ApiResponse<Vec<community_post::Model>>>, StatusCode> {
-    match state
+    Path((community_id, post_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<VoteRequest>,
+) -> Result<Json<ApiResponse<VoteTally>>, AppError> {
+    let player_id = Uuid::parse_str(&claims.sub)?;
+    let tally = state
         .community_service
-        .get_community_posts(&community_id)
-        .await
-    {
-        Ok(posts) => Ok(Json(ApiResponse::success(posts))),
-        Err(e) => {
-            tracing::error!("Failed to get community posts: {}", e);
-            Err(StatusCode::INTERNAL_SERVER_ERROR)
-        }
-    }
+        .vote_post(community_id, post_id, player_id, payload.value)
+        .await?;
+    Ok(Json(ApiResponse::success(tally)))
+}
+
+pub async fn list_post_comments(
+    State(state): State<AppState>,
+    Path((community_id, post_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Query(page): Query<CommentPage>,
+) -> Result<Json<ApiResponse<Vec<CommentNode>>>, AppError> {
+    let viewer_id = Uuid::parse_str(&claims.sub)?;
+    let comments = state
+        .community_service
+        .list_comments(community_id, post_id, viewer_id, page)
+        .await?;
+    Ok(Json(ApiResponse::success(comments)))
+}
+
+pub async fn create_post_comment(
+    State(state): State<AppState>,
+    Path((community_id, post_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateCommentRequest>,
+) -> Result<Json<ApiResponse<post_comment::Model>>, AppError> {
+    let author_id = Uuid::parse_str(&claims.sub)?;
+    let comment = state
+        .community_service
+        .create_comment(
+            community_id,
+            post_id,
+            author_id,
+            payload.parent_id,
+            payload.content,
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(comment)))
+}
+
+pub async fn delete_post_comment(
+    State(state): State<AppState>,
+    Path((community_id, post_id, comment_id)): Path<(Uuid, Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    let actor_id = Uuid::parse_str(&claims.sub)?;
+    state
+        .community_service
+        .delete_comment(community_id, post_id, comment_id, actor_id)
+        .await?;
+    Ok(Json(ApiResponse::success("Comment deleted".to_string())))
+}
+
+pub async fn vote_post_comment(
+    State(state): State<AppState>,
+    Path((community_id, post_id, comment_id)): Path<(Uuid, Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<VoteRequest>,
+) -> Result<Json<ApiResponse<VoteTally>>, AppError> {
+    let player_id = Uuid::parse_str(&claims.sub)?;
+    let tally = state
+        .community_service
+        .vote_comment(community_id, post_id, comment_id, player_id, payload.value)
+        .await?;
+    Ok(Json(ApiResponse::success(tally)))
+}
+
+pub async fn get_community_feed(
+    State(state): State<AppState>,
+    Path(community_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<FeedQuery>,
+) -> Result<Json<ApiResponse<FeedPage>>, AppError> {
+    let viewer_id = Uuid::parse_str(&claims.sub)?;
+    let page = state
+        .community_service
+        .community_feed(community_id, viewer_id, query)
+        .await?;
+    Ok(Json(ApiResponse::success(page)))
+}
+
+pub async fn get_home_feed(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(query): Query<FeedQuery>,
+) -> Result<Json<ApiResponse<FeedPage>>, AppError> {
+    let player_id = Uuid::parse_str(&claims.sub)?;
+    let page = state.community_service.home_feed(player_id, query).await?;
+    Ok(Json(ApiResponse::success(page)))
 }
 
 pub async fn join_community(
@@ -0,0 +1,65 @@
+use super::chat::ApiResponse;
+use crate::models::postgres::chat;
+use crate::services::auth_service::Claims;
+use crate::services::direct_message_service::InboxEntry;
+use crate::{utils::errors::AppError, AppState};
+use axum::extract::{Extension, Path, Query, State};
+use axum::Json;
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct CreateGroupDmRequest {
+    pub name: Option<String>,
+    pub player_ids: Vec<Uuid>,
+}
+
+#[derive(Deserialize)]
+pub struct InboxQuery {
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+/// Finds or starts the 1:1 conversation with `player_id`.
+pub async fn open_direct_message(
+    State(state): State<AppState>,
+    Path(player_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<chat::Model>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let chat = state
+        .direct_message_service
+        .open_direct(user_id, player_id)
+        .await?;
+    Ok(Json(ApiResponse::success(chat)))
+}
+
+pub async fn create_group_dm(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateGroupDmRequest>,
+) -> Result<Json<ApiResponse<chat::Model>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let chat = state
+        .direct_message_service
+        .create_group(user_id, payload.name, payload.player_ids)
+        .await?;
+    Ok(Json(ApiResponse::success(chat)))
+}
+
+pub async fn get_dm_inbox(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<InboxQuery>,
+) -> Result<Json<ApiResponse<Vec<InboxEntry>>>, AppError> {
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let entries = state
+        .direct_message_service
+        .inbox(
+            user_id,
+            params.limit.unwrap_or(20).clamp(1, 100),
+            params.offset.unwrap_or(0),
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(entries)))
+}
@@ -0,0 +1,184 @@
+use super::auth::{complete_login, extract_client_info, two_factor_challenge, LoginAccount};
+use crate::services::auth_service::{AuthService, UserType};
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{ConnectInfo, State},
+    http::HeaderMap,
+    response::{IntoResponse, Json, Response},
+};
+use chrono::{TimeZone, Utc};
+use serde::Deserialize;
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+const MAGIC_LINK_TTL_MINUTES: i64 = 15;
+
+#[derive(Deserialize)]
+pub struct MagicLinkRequest {
+    pub email: String,
+}
+
+#[derive(Deserialize)]
+pub struct MagicLinkVerifyRequest {
+    pub token: String,
+}
+
+/// Emails a one-time sign-in link to a player. The response is the same
+/// whether or not the email belongs to an account.
+pub async fn request_magic_link(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<MagicLinkRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let email = payload.email.trim().to_lowercase();
+
+    // Rate limiting check, per client and per mailbox
+    if let Some(ip) = &ip_address {
+        state
+            .rate_limit_service
+            .check_rate_limit(
+                ip.clone(),
+                "ip".to_string(),
+                "magic_link".to_string(),
+                5,  // 5 links per window
+                15, // 15 minutes window
+            )
+            .await?;
+    }
+    state
+        .rate_limit_service
+        .check_rate_limit(
+            email.clone(),
+            "email".to_string(),
+            "magic_link".to_string(),
+            3,
+            15,
+        )
+        .await?;
+
+    if let Some(player) = state.player_service.get_by_email(email).await? {
+        let suspended = state
+            .suspension_service
+            .get_active(player.id)
+            .await?
+            .is_some();
+
+        if !suspended {
+            let fingerprint =
+                AuthService::client_fingerprint(ip_address.as_deref(), user_agent.as_deref());
+            let (token, _) = state.auth_service.generate_bound_temp_token(
+                player.id,
+                UserType::Player,
+                "magic_login",
+                chrono::Duration::minutes(MAGIC_LINK_TTL_MINUTES),
+                fingerprint,
+            )?;
+
+            let _ = state
+                .email_service
+                .send_magic_login_link(&player.email, &token, MAGIC_LINK_TTL_MINUTES)
+                .await;
+
+            let _ = state
+                .audit_service
+                .log_action(
+                    Some(player.id),
+                    Some("player".to_string()),
+                    None,
+                    "magic_link_requested".to_string(),
+                    Some("player".to_string()),
+                    Some(player.id),
+                    ip_address,
+                    user_agent,
+                    true,
+                    None,
+                    None,
+                    None,
+                )
+                .await;
+        }
+    }
+
+    Ok(Json(serde_json::json!({
+        "message": "If an account exists with that email, a sign-in link has been sent"
+    })))
+}
+
+/// Redeems a magic link. The link must be opened from the client that asked
+/// for it, and works once.
+pub async fn verify_magic_link(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<MagicLinkVerifyRequest>,
+) -> Result<Response, AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let invalid = || AppError::Validation("Invalid or expired sign-in link".to_string());
+
+    let claims = state.auth_service.verify_temp_token(&payload.token)?;
+    if claims.token_type != "magic_login" || claims.user_type != "player" {
+        return Err(AppError::Validation("Invalid token type".to_string()));
+    }
+
+    let fingerprint = AuthService::client_fingerprint(ip_address.as_deref(), user_agent.as_deref());
+    if claims.fingerprint.as_deref() != Some(fingerprint.as_str()) {
+        let _ = state
+            .audit_service
+            .log_action(
+                Uuid::parse_str(&claims.sub).ok(),
+                Some("player".to_string()),
+                None,
+                "magic_link_rejected".to_string(),
+                Some("player".to_string()),
+                Uuid::parse_str(&claims.sub).ok(),
+                ip_address,
+                user_agent,
+                false,
+                Some("Client fingerprint mismatch".to_string()),
+                None,
+                None,
+            )
+            .await;
+        return Err(invalid());
+    }
+
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    let jti = claims
+        .jti
+        .as_deref()
+        .and_then(|j| Uuid::parse_str(j).ok())
+        .ok_or_else(invalid)?;
+    let expires_at = Utc
+        .timestamp_opt(claims.exp as i64, 0)
+        .single()
+        .ok_or_else(invalid)?;
+
+    if !state
+        .consumed_token_service
+        .consume(jti, "magic_login", user_id, expires_at)
+        .await?
+    {
+        return Err(invalid());
+    }
+
+    let account = LoginAccount::load(&state, "player", user_id).await?;
+    state
+        .suspension_service
+        .ensure_not_suspended(account.id)
+        .await?;
+
+    // A magic link replaces the password, not the second factor
+    if let Some(challenge) =
+        two_factor_challenge(&state, &account, ip_address.clone(), user_agent.clone()).await?
+    {
+        return Ok(Json(challenge).into_response());
+    }
+
+    Ok(
+        complete_login(&state, account, ip_address, user_agent, "magic_link")
+            .await?
+            .into_response(),
+    )
+}
@@ -1,10 +1,22 @@
+pub mod admin;
+pub mod announcements;
+pub mod api_keys;
 pub mod auth;
 pub mod chat;
+pub mod chat_socket;
 pub mod communities;
 pub mod dashboard;
+pub mod direct_messages;
+pub mod magic_link;
+pub mod moderation;
+pub mod oauth;
+pub mod organizations;
 pub mod players;
+pub mod sessions;
 pub mod tournaments;
+pub mod two_factor;
 pub mod uploads;
+pub mod webhooks;
 
 pub use auth::{
     forgot_password, login as auth_login, logout as auth_logout, refresh_token,
@@ -12,14 +24,25 @@ pub use auth::{
     verify_email,
 };
 
+pub use admin::*;
+pub use announcements::*;
+pub use api_keys::*;
 pub use chat::*;
 pub use communities::*;
+pub use direct_messages::*;
+pub use magic_link::*;
+pub use moderation::*;
+pub use oauth::*;
+pub use organizations::*;
 pub use players::{
     get_current_player_profile, get_current_user, get_player_by_id, get_player_by_username,
     list_players, update_player_profile,
 };
 
+pub use sessions::*;
+pub use two_factor::*;
 pub use uploads::*;
+pub use webhooks::*;
 
 pub use dashboard::{dashboard_health, get_dashboard_data};
 
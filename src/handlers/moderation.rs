@@ -0,0 +1,215 @@
+use super::admin::require_admin;
+use super::chat::ApiResponse;
+use super::organizations::audit_org_action;
+use crate::models::enums::{AdminCapability, ModeratedContent, ModerationStatus, ReportReason};
+use crate::models::postgres::{content_report, moderation_queue_item};
+use crate::services::auth_service::Claims;
+use crate::services::chat_realtime_service::MESSAGE_DELETED;
+use crate::services::moderation_service::{QueueItemDetail, QueuedContent};
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{Extension, Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+const DEFAULT_LIMIT: u64 = 50;
+const MAX_LIMIT: u64 = 100;
+
+#[derive(Deserialize)]
+pub struct ReportContentRequest {
+    pub reason: ReportReason,
+    pub details: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ModerationQueueQuery {
+    pub status: Option<ModerationStatus>, // Defaults to pending
+    pub content_type: Option<ModeratedContent>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Deserialize)]
+pub struct ResolveModerationRequest {
+    pub status: ModerationStatus, // removed or dismissed
+    pub note: Option<String>,
+}
+
+// ========================================
+// REPORTS
+// ========================================
+
+pub async fn report_chat_message(
+    State(state): State<AppState>,
+    Path((chat_id, message_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ReportContentRequest>,
+) -> Result<Json<ApiResponse<content_report::Model>>, AppError> {
+    let reporter_id = Uuid::parse_str(&claims.sub)?;
+    let message = state
+        .chat_service
+        .reportable_message(chat_id, reporter_id, message_id)
+        .await?;
+
+    let report = state
+        .moderation_service
+        .report(
+            reporter_id,
+            QueuedContent {
+                content_type: ModeratedContent::ChatMessage,
+                content_id: message.id,
+                author_id: message.sender_id,
+                text: &message.message,
+            },
+            payload.reason,
+            payload.details,
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+pub async fn report_community_post(
+    State(state): State<AppState>,
+    Path((community_id, post_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ReportContentRequest>,
+) -> Result<Json<ApiResponse<content_report::Model>>, AppError> {
+    let reporter_id = Uuid::parse_str(&claims.sub)?;
+    let post = state
+        .community_service
+        .get_post(community_id, post_id, reporter_id)
+        .await?
+        .post;
+    let text = format!("{}\n{}", post.title.unwrap_or_default(), post.content);
+
+    let report = state
+        .moderation_service
+        .report(
+            reporter_id,
+            QueuedContent {
+                content_type: ModeratedContent::CommunityPost,
+                content_id: post.id,
+                author_id: post.author_id,
+                text: &text,
+            },
+            payload.reason,
+            payload.details,
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+pub async fn report_post_comment(
+    State(state): State<AppState>,
+    Path((community_id, post_id, comment_id)): Path<(Uuid, Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ReportContentRequest>,
+) -> Result<Json<ApiResponse<content_report::Model>>, AppError> {
+    let reporter_id = Uuid::parse_str(&claims.sub)?;
+    let comment = state
+        .community_service
+        .get_comment(community_id, post_id, comment_id, reporter_id)
+        .await?;
+
+    let report = state
+        .moderation_service
+        .report(
+            reporter_id,
+            QueuedContent {
+                content_type: ModeratedContent::PostComment,
+                content_id: comment.id,
+                author_id: comment.author_id,
+                text: &comment.content,
+            },
+            payload.reason,
+            payload.details,
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(report)))
+}
+
+// ========================================
+// REVIEW QUEUE
+// ========================================
+
+pub async fn admin_list_moderation_queue(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Query(params): Query<ModerationQueueQuery>,
+) -> Result<Json<ApiResponse<Vec<moderation_queue_item::Model>>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::ModerationReview).await?;
+
+    let items = state
+        .moderation_service
+        .list_queue(
+            params.status.unwrap_or(ModerationStatus::Pending),
+            params.content_type,
+            params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT),
+            params.offset.unwrap_or(0),
+        )
+        .await?;
+    Ok(Json(ApiResponse::success(items)))
+}
+
+pub async fn admin_get_moderation_item(
+    State(state): State<AppState>,
+    Path(item_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<QueueItemDetail>>, AppError> {
+    require_admin(&state, &claims, AdminCapability::ModerationReview).await?;
+
+    let detail = state.moderation_service.get_item(item_id).await?;
+    Ok(Json(ApiResponse::success(detail)))
+}
+
+pub async fn admin_resolve_moderation_item(
+    State(state): State<AppState>,
+    Path(item_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<ResolveModerationRequest>,
+) -> Result<Json<ApiResponse<moderation_queue_item::Model>>, AppError> {
+    let actor = require_admin(&state, &claims, AdminCapability::ModerationReview).await?;
+
+    let resolution = state
+        .moderation_service
+        .resolve(item_id, actor.id, payload.status, payload.note)
+        .await?;
+    let item = resolution.item;
+
+    if let Some(message) = resolution.removed_message {
+        state
+            .chat_realtime_service
+            .broadcast(
+                message.chat_id,
+                MESSAGE_DELETED,
+                serde_json::json!({
+                    "chat_id": message.chat_id,
+                    "message_id": message.id,
+                    "deleted_at": message.deleted_at,
+                }),
+            )
+            .await;
+    }
+
+    audit_org_action(
+        &state,
+        &claims,
+        match item.status {
+            ModerationStatus::Removed => "moderation_remove",
+            _ => "moderation_dismiss",
+        },
+        "moderation_queue",
+        item.id,
+        serde_json::json!({
+            "content_type": item.content_type,
+            "content_id": item.content_id,
+            "author_id": item.author_id,
+            "note": item.resolution_note,
+        }),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(item)))
+}
@@ -0,0 +1,464 @@
+use super::auth::{complete_login, extract_client_info, two_factor_challenge, LoginAccount};
+use super::chat::ApiResponse;
+use crate::models::enums::OAuthProvider;
+use crate::models::postgres::{oauth_state, player_identity};
+use crate::services::auth_service::Claims;
+use crate::services::oauth_service::{ExternalProfile, OAuthService, SocialLogin};
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{ConnectInfo, Extension, Path, Query, State},
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{IntoResponse, Json, Redirect, Response},
+};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+// Frontend pages the callback sends the browser to
+const LOGIN_ERROR_PATH: &str = "/login";
+const TWO_FACTOR_PATH: &str = "/login/2fa";
+const CONNECTIONS_PATH: &str = "/settings/connections";
+
+// Ties a pending state to the browser that started the flow
+const BINDING_COOKIE: &str = "oauth_binding";
+
+#[derive(Deserialize)]
+pub struct OAuthAuthorizeQuery {
+    pub redirect_to: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct OAuthCallbackQuery {
+    pub code: Option<String>,
+    pub state: Option<String>,
+    pub error: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct OAuthProvidersResponse {
+    pub providers: Vec<OAuthProvider>,
+}
+
+#[derive(Serialize)]
+pub struct OAuthLinkResponse {
+    pub authorization_url: String,
+}
+
+#[derive(Serialize)]
+pub struct LinkedIdentityResponse {
+    pub provider: OAuthProvider,
+    pub email: Option<String>,
+    pub username: Option<String>,
+    pub linked_at: chrono::DateTime<chrono::Utc>,
+    pub last_login_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<player_identity::Model> for LinkedIdentityResponse {
+    fn from(identity: player_identity::Model) -> Self {
+        Self {
+            provider: identity.provider,
+            email: identity.email,
+            username: identity.username,
+            linked_at: identity.created_at,
+            last_login_at: identity.last_login_at,
+        }
+    }
+}
+
+/// Cookie carrying the state binding, scoped to the OAuth routes and living
+/// as long as the pending request. An empty binding clears it.
+fn binding_cookie(binding: &str) -> HeaderValue {
+    let secure = if cfg!(debug_assertions) {
+        ""
+    } else {
+        "; Secure"
+    };
+    let max_age = if binding.is_empty() { 0 } else { 10 * 60 };
+    format!(
+        "{}={}; HttpOnly; SameSite=Lax; Max-Age={}; Path=/auth/oauth{}",
+        BINDING_COOKIE, binding, max_age, secure
+    )
+    .parse()
+    .unwrap()
+}
+
+fn binding_from_cookies(headers: &HeaderMap) -> Option<&str> {
+    headers
+        .get_all(header::COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|cookies| cookies.split(';'))
+        .find_map(|cookie| {
+            cookie
+                .trim()
+                .strip_prefix(BINDING_COOKIE)
+                .and_then(|rest| rest.strip_prefix('='))
+        })
+}
+
+fn parse_provider(provider: &str) -> Result<OAuthProvider, AppError> {
+    OAuthProvider::parse(provider).ok_or(AppError::NotFound)
+}
+
+fn player_id(claims: &Claims) -> Result<Uuid, AppError> {
+    if claims.user_type != "player" {
+        return Err(AppError::Forbidden);
+    }
+    claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::Validation("Invalid user ID".to_string()))
+}
+
+/// Builds a frontend URL, appending query parameters (or a fragment, for values
+/// that shouldn't reach server logs).
+fn frontend_redirect(
+    state: &AppState,
+    path: &str,
+    query: &[(&str, &str)],
+    fragment: Option<&str>,
+) -> String {
+    let base = state.oauth_service.frontend_url(path);
+    let mut url = match reqwest::Url::parse(&base) {
+        Ok(url) => url,
+        Err(_) => return base,
+    };
+    if !query.is_empty() {
+        url.query_pairs_mut().extend_pairs(query);
+    }
+    url.set_fragment(fragment);
+    url.to_string()
+}
+
+async fn audit_identity(
+    state: &AppState,
+    player_id: Uuid,
+    action: &str,
+    provider: OAuthProvider,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) {
+    let _ = state
+        .audit_service
+        .log_action(
+            Some(player_id),
+            Some("player".to_string()),
+            None,
+            action.to_string(),
+            Some("player".to_string()),
+            Some(player_id),
+            ip_address,
+            user_agent,
+            true,
+            None,
+            None,
+            Some(serde_json::json!({"provider": provider.as_str()})),
+        )
+        .await;
+}
+
+// ============================================================================
+// PUBLIC LOGIN FLOW
+// ============================================================================
+
+pub async fn list_oauth_providers(
+    State(state): State<AppState>,
+) -> Result<Json<ApiResponse<OAuthProvidersResponse>>, AppError> {
+    Ok(Json(ApiResponse::success(OAuthProvidersResponse {
+        providers: state.oauth_service.enabled_providers(),
+    })))
+}
+
+/// Starts a social login by redirecting the browser to the provider.
+pub async fn oauth_authorize(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthAuthorizeQuery>,
+) -> Result<(HeaderMap, Redirect), AppError> {
+    let provider = parse_provider(&provider)?;
+    let request = state
+        .oauth_service
+        .authorization_url(provider, None, query.redirect_to)
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::SET_COOKIE, binding_cookie(&request.binding));
+    Ok((headers, Redirect::to(&request.url)))
+}
+
+/// Provider callback for both login and account linking. Always answers with a
+/// redirect to the frontend; failures carry an `oauth_error` query parameter.
+pub async fn oauth_callback(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+    Query(query): Query<OAuthCallbackQuery>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Response {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let binding = binding_from_cookies(&headers);
+
+    let mut response =
+        match handle_callback(&state, &provider, query, binding, ip_address, user_agent).await {
+            Ok(response) => response,
+            Err(e) => {
+                tracing::warn!("OAuth callback for {} failed: {:?}", provider, e);
+                let message = match e {
+                    AppError::Validation(message) => message,
+                    AppError::Forbidden => "This account is suspended".to_string(),
+                    _ => "Sign-in with this provider failed".to_string(),
+                };
+                Redirect::to(&frontend_redirect(
+                    &state,
+                    LOGIN_ERROR_PATH,
+                    &[("oauth_error", &message)],
+                    None,
+                ))
+                .into_response()
+            }
+        };
+
+    // The binding is single use, like the state it covers
+    response
+        .headers_mut()
+        .append(header::SET_COOKIE, binding_cookie(""));
+    response
+}
+
+async fn handle_callback(
+    state: &AppState,
+    provider: &str,
+    query: OAuthCallbackQuery,
+    binding: Option<&str>,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<Response, AppError> {
+    let provider = parse_provider(provider)?;
+    let state_token = query.state.ok_or(AppError::Validation(
+        "Invalid or expired login request".to_string(),
+    ))?;
+    // A callback link opened in another browser doesn't carry the binding, so
+    // nobody can finish their own flow in a victim's session
+    if !OAuthService::binding_matches(&state_token, binding) {
+        return Err(AppError::Validation(
+            "Invalid or expired login request".to_string(),
+        ));
+    }
+
+    // Consume the state even when the user declined, so it can't be reused
+    let pending = state
+        .oauth_service
+        .consume_state(provider, &state_token)
+        .await?;
+    if query.error.is_some() {
+        return Err(AppError::Validation(
+            "Sign-in was cancelled at the provider".to_string(),
+        ));
+    }
+    let code = query.code.ok_or(AppError::Validation(
+        "Provider did not return an authorization code".to_string(),
+    ))?;
+
+    let profile = state.oauth_service.exchange_code(&pending, &code).await?;
+
+    match pending.link_player_id {
+        Some(player_id) => finish_link(state, player_id, &profile, ip_address, user_agent).await,
+        None => finish_login(state, &pending, &profile, ip_address, user_agent).await,
+    }
+}
+
+async fn finish_link(
+    state: &AppState,
+    player_id: Uuid,
+    profile: &ExternalProfile,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<Response, AppError> {
+    state
+        .oauth_service
+        .link_identity(player_id, profile)
+        .await?;
+    audit_identity(
+        state,
+        player_id,
+        "identity_linked",
+        profile.provider,
+        ip_address,
+        user_agent,
+    )
+    .await;
+
+    Ok(Redirect::to(&frontend_redirect(
+        state,
+        CONNECTIONS_PATH,
+        &[("linked", profile.provider.as_str())],
+        None,
+    ))
+    .into_response())
+}
+
+async fn finish_login(
+    state: &AppState,
+    pending: &oauth_state::Model,
+    profile: &ExternalProfile,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) -> Result<Response, AppError> {
+    let player = match state.oauth_service.resolve_login(profile).await? {
+        Some(SocialLogin::Existing(player)) => player,
+        Some(SocialLogin::Linked(player)) => {
+            audit_identity(
+                state,
+                player.id,
+                "identity_linked",
+                profile.provider,
+                ip_address.clone(),
+                user_agent.clone(),
+            )
+            .await;
+            player
+        }
+        None => {
+            let email = profile.email.clone().ok_or(AppError::Validation(
+                "Provider did not share an email address".to_string(),
+            ))?;
+            let hint = profile
+                .username
+                .clone()
+                .unwrap_or_else(|| email.split('@').next().unwrap_or_default().to_string());
+
+            let player = state
+                .player_service
+                .create_social_player(email, &hint, profile.email_verified)
+                .await?;
+            state
+                .oauth_service
+                .link_identity(player.id, profile)
+                .await?;
+
+            let _ = state
+                .audit_service
+                .log_action(
+                    Some(player.id),
+                    Some("player".to_string()),
+                    None,
+                    "register".to_string(),
+                    Some("player".to_string()),
+                    Some(player.id),
+                    ip_address.clone(),
+                    user_agent.clone(),
+                    true,
+                    None,
+                    None,
+                    Some(serde_json::json!({"method": "oauth", "provider": profile.provider.as_str()})),
+                )
+                .await;
+            player
+        }
+    };
+
+    state
+        .suspension_service
+        .ensure_not_suspended(player.id)
+        .await?;
+
+    let account = LoginAccount::player(player);
+    if let Some(challenge) =
+        two_factor_challenge(state, &account, ip_address.clone(), user_agent.clone()).await?
+    {
+        let fragment = format!("pending_token={}", challenge.pending_token);
+        return Ok(Redirect::to(&frontend_redirect(
+            state,
+            TWO_FACTOR_PATH,
+            &[],
+            Some(&fragment),
+        ))
+        .into_response());
+    }
+
+    let method = format!("oauth:{}", profile.provider.as_str());
+    let (mut headers, _) = complete_login(state, account, ip_address, user_agent, &method).await?;
+
+    let target = frontend_redirect(
+        state,
+        pending.redirect_to.as_deref().unwrap_or("/"),
+        &[],
+        None,
+    );
+    headers.insert(
+        header::LOCATION,
+        HeaderValue::from_str(&target).map_err(|_| AppError::InternalServerError)?,
+    );
+
+    Ok((StatusCode::FOUND, headers).into_response())
+}
+
+// ============================================================================
+// ACCOUNT LINKING (SETTINGS)
+// ============================================================================
+
+pub async fn list_linked_identities(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<LinkedIdentityResponse>>>, AppError> {
+    let player_id = player_id(&claims)?;
+    let identities = state.oauth_service.list_identities(player_id).await?;
+
+    Ok(Json(ApiResponse::success(
+        identities.into_iter().map(Into::into).collect(),
+    )))
+}
+
+/// Returns the provider URL that links another identity to the caller. The
+/// frontend navigates there; the shared callback finishes the link. The
+/// response sets the binding cookie, so it must be requested with credentials.
+pub async fn start_identity_link(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(provider): Path<String>,
+) -> Result<(HeaderMap, Json<ApiResponse<OAuthLinkResponse>>), AppError> {
+    let player_id = player_id(&claims)?;
+    let provider = parse_provider(&provider)?;
+
+    let request = state
+        .oauth_service
+        .authorization_url(provider, Some(player_id), None)
+        .await?;
+
+    let mut headers = HeaderMap::new();
+    headers.insert(header::SET_COOKIE, binding_cookie(&request.binding));
+    Ok((
+        headers,
+        Json(ApiResponse::success(OAuthLinkResponse {
+            authorization_url: request.url,
+        })),
+    ))
+}
+
+pub async fn unlink_identity(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(provider): Path<String>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<()>>, AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let player_id = player_id(&claims)?;
+    let provider = parse_provider(&provider)?;
+
+    state
+        .oauth_service
+        .unlink_identity(player_id, provider)
+        .await?;
+    audit_identity(
+        &state,
+        player_id,
+        "identity_unlinked",
+        provider,
+        ip_address,
+        user_agent,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(())))
+}
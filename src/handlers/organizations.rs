@@ -0,0 +1,630 @@
+use super::api_keys::{ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+use super::chat::ApiResponse;
+use crate::models::enums::{
+    ApprovalStatus, OrgPermission, OrgRole, TournamentStatus, WebhookEvent,
+};
+use crate::models::postgres::{battle, organization_member, team, tournament, tournament_team};
+use crate::services::auth_service::Claims;
+use crate::services::battle_service::BattleResult;
+use crate::{utils::errors::AppError, AppState};
+use axum::extract::Extension;
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use serde::Deserialize;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct InviteMemberRequest {
+    pub email: String,
+    pub role: OrgRole,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateMemberRoleRequest {
+    pub role: OrgRole,
+}
+
+#[derive(Deserialize)]
+pub struct TransferOwnershipRequest {
+    pub member_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct CreateOrgTeamRequest {
+    pub team_name: String,
+    pub team_tag: Option<String>,
+    pub captain_id: Uuid,
+}
+
+#[derive(Deserialize)]
+pub struct CreateOrgTournamentRequest {
+    pub tournament_name: String,
+    pub game_title: String,
+    pub start_date: chrono::DateTime<chrono::Utc>,
+    pub end_date: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Deserialize)]
+pub struct RegisterTournamentTeamRequest {
+    pub team_id: Uuid,
+    pub qualified_through: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct VerifyBattleResultsRequest {
+    pub results: Vec<BattleResult>,
+}
+
+/// Checks the caller's org role and, for anything beyond viewing, that the
+/// organization has been approved by an admin.
+pub(crate) async fn authorize(
+    state: &AppState,
+    org_id: Uuid,
+    claims: &Claims,
+    permission: OrgPermission,
+) -> Result<OrgRole, AppError> {
+    let org = state
+        .organization_service
+        .get_by_id(org_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let role = state
+        .organization_member_service
+        .require_permission(org_id, claims, permission)
+        .await?;
+
+    if permission != OrgPermission::View && org.approval_status != ApprovalStatus::Approved {
+        return Err(AppError::Forbidden);
+    }
+
+    Ok(role)
+}
+
+pub(crate) async fn audit_org_action(
+    state: &AppState,
+    claims: &Claims,
+    action: &str,
+    resource: &str,
+    resource_id: Uuid,
+    details: serde_json::Value,
+) {
+    let _ = state
+        .audit_service
+        .log_action(
+            Uuid::parse_str(&claims.sub).ok(),
+            Some(claims.user_type.clone()),
+            Uuid::parse_str(&claims.session_id).ok(),
+            action.to_string(),
+            Some(resource.to_string()),
+            Some(resource_id),
+            None,
+            None,
+            true,
+            None,
+            None,
+            Some(details),
+        )
+        .await;
+}
+
+// ========================================
+// MEMBERS
+// ========================================
+
+pub async fn list_org_members(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<organization_member::Model>>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::View).await?;
+
+    let members = state
+        .organization_member_service
+        .list_members(org_id)
+        .await?;
+    Ok(Json(ApiResponse::success(members)))
+}
+
+pub async fn invite_org_member(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<InviteMemberRequest>,
+) -> Result<Json<ApiResponse<organization_member::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageMembers).await?;
+    crate::utils::validation::validate_email(&payload.email)?;
+
+    let inviter_id = Uuid::parse_str(&claims.sub)?;
+    let (member, token) = state
+        .organization_member_service
+        .invite_member(org_id, inviter_id, payload.email, payload.role)
+        .await?;
+
+    let org = state
+        .organization_service
+        .get_by_id(org_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    if let Some(email) = &member.invited_email {
+        let _ = state
+            .email_service
+            .send_organization_invite(email, &org.org_name, member.role.as_str(), &token)
+            .await;
+    }
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_member_invite",
+        "organization_member",
+        member.id,
+        serde_json::json!({"organization_id": org_id, "role": member.role.as_str()}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(member)))
+}
+
+pub async fn update_org_member_role(
+    State(state): State<AppState>,
+    Path((org_id, member_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateMemberRoleRequest>,
+) -> Result<Json<ApiResponse<organization_member::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageMembers).await?;
+
+    let member = state
+        .organization_member_service
+        .update_role(org_id, member_id, payload.role)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_member_role_update",
+        "organization_member",
+        member.id,
+        serde_json::json!({"organization_id": org_id, "role": member.role.as_str()}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(member)))
+}
+
+pub async fn remove_org_member(
+    State(state): State<AppState>,
+    Path((org_id, member_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageMembers).await?;
+
+    state
+        .organization_member_service
+        .remove_member(org_id, member_id)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_member_remove",
+        "organization_member",
+        member_id,
+        serde_json::json!({"organization_id": org_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success("Member removed".to_string())))
+}
+
+pub async fn transfer_org_ownership(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<TransferOwnershipRequest>,
+) -> Result<Json<ApiResponse<organization_member::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::TransferOwnership).await?;
+
+    let new_owner = state
+        .organization_member_service
+        .transfer_ownership(org_id, payload.member_id)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_ownership_transfer",
+        "organization",
+        org_id,
+        serde_json::json!({"new_owner_member_id": new_owner.id, "player_id": new_owner.player_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(new_owner)))
+}
+
+pub async fn accept_org_invite(
+    State(state): State<AppState>,
+    Path(token): Path<String>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<organization_member::Model>>, AppError> {
+    if claims.user_type != "player" {
+        return Err(AppError::Forbidden);
+    }
+
+    let invite = state.auth_service.verify_temp_token(&token)?;
+    if invite.token_type != "org_invite" {
+        return Err(AppError::Validation("Invalid token type".to_string()));
+    }
+
+    let player_id = Uuid::parse_str(&claims.sub)?;
+    let player = state
+        .player_service
+        .get_by_id(player_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    let member = state
+        .organization_member_service
+        .accept_invite(Uuid::parse_str(&invite.sub)?, &player)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_invite_accept",
+        "organization_member",
+        member.id,
+        serde_json::json!({"organization_id": member.organization_id}),
+    )
+    .await;
+
+    state
+        .webhook_service
+        .notify(
+            member.organization_id,
+            WebhookEvent::InviteAccepted,
+            serde_json::json!({
+                "member_id": member.id,
+                "player_id": member.player_id,
+                "username": player.username,
+                "role": member.role,
+            }),
+        )
+        .await;
+
+    Ok(Json(ApiResponse::success(member)))
+}
+
+pub async fn get_my_org_memberships(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<organization_member::Model>>>, AppError> {
+    if claims.user_type != "player" {
+        return Err(AppError::Forbidden);
+    }
+
+    let player_id = Uuid::parse_str(&claims.sub)?;
+    let memberships = state
+        .organization_member_service
+        .list_player_memberships(player_id)
+        .await?;
+
+    Ok(Json(ApiResponse::success(memberships)))
+}
+
+// ========================================
+// ORG-OWNED TEAMS & TOURNAMENTS
+// ========================================
+
+pub async fn list_org_teams(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<team::Model>>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::View).await?;
+
+    let teams = state.team_service.get_by_organization(org_id).await?;
+    Ok(Json(ApiResponse::success(teams)))
+}
+
+pub async fn create_org_team(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateOrgTeamRequest>,
+) -> Result<Json<ApiResponse<team::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageTeams).await?;
+
+    if state
+        .organization_member_service
+        .get_active_membership(org_id, payload.captain_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::Validation(
+            "The captain must be a member of this organization".to_string(),
+        ));
+    }
+
+    let team = state
+        .team_service
+        .create_team(
+            payload.team_name,
+            payload.team_tag,
+            payload.captain_id,
+            Some(org_id),
+        )
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_team_create",
+        "team",
+        team.id,
+        serde_json::json!({"organization_id": org_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(team)))
+}
+
+pub async fn list_org_tournaments(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<tournament::Model>>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::View).await?;
+
+    let tournaments = state.tournament_service.get_by_organizer(org_id).await?;
+    Ok(Json(ApiResponse::success(tournaments)))
+}
+
+pub async fn create_org_tournament(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateOrgTournamentRequest>,
+) -> Result<Json<ApiResponse<tournament::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageTournaments).await?;
+
+    if payload.end_date < payload.start_date {
+        return Err(AppError::Validation(
+            "End date must be after start date".to_string(),
+        ));
+    }
+
+    let tournament = state
+        .tournament_service
+        .create_tournament(
+            payload.tournament_name,
+            payload.game_title,
+            payload.start_date,
+            payload.end_date,
+            Some(org_id),
+        )
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_tournament_create",
+        "tournament",
+        tournament.id,
+        serde_json::json!({"organization_id": org_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(tournament)))
+}
+
+/// The organization's tournament, or `NotFound` for anyone else's.
+async fn org_tournament(
+    state: &AppState,
+    org_id: Uuid,
+    tournament_id: Uuid,
+) -> Result<tournament::Model, AppError> {
+    state
+        .tournament_service
+        .get_by_id(tournament_id)
+        .await?
+        .filter(|t| t.submitted_by == Some(org_id))
+        .ok_or(AppError::NotFound)
+}
+
+pub async fn register_org_tournament_team(
+    State(state): State<AppState>,
+    Path((org_id, tournament_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<RegisterTournamentTeamRequest>,
+) -> Result<Json<ApiResponse<tournament_team::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageTournaments).await?;
+
+    let tournament = org_tournament(&state, org_id, tournament_id).await?;
+    if matches!(
+        tournament.status,
+        TournamentStatus::Completed | TournamentStatus::Cancelled
+    ) {
+        return Err(AppError::Validation(
+            "Teams can't join a tournament that has ended".to_string(),
+        ));
+    }
+    if state
+        .team_service
+        .get_by_id(payload.team_id)
+        .await?
+        .is_none()
+    {
+        return Err(AppError::Validation("Team not found".to_string()));
+    }
+
+    let entry = state
+        .tournament_team_service
+        .join_tournament(tournament.id, payload.team_id, payload.qualified_through)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_tournament_team_register",
+        "tournament",
+        tournament.id,
+        serde_json::json!({"organization_id": org_id, "team_id": payload.team_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(entry)))
+}
+
+pub async fn verify_org_battle_results(
+    State(state): State<AppState>,
+    Path((org_id, tournament_id, battle_id)): Path<(Uuid, Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<VerifyBattleResultsRequest>,
+) -> Result<Json<ApiResponse<battle::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageTournaments).await?;
+
+    let tournament = org_tournament(&state, org_id, tournament_id).await?;
+    let battle = state
+        .battle_service
+        .get_by_id(battle_id)
+        .await?
+        .filter(|b| b.tournament == tournament.id)
+        .ok_or(AppError::NotFound)?;
+
+    let battle = state
+        .battle_service
+        .verify_results(battle, payload.results)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_battle_results_verify",
+        "battle",
+        battle.id,
+        serde_json::json!({"organization_id": org_id, "tournament_id": tournament.id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(battle)))
+}
+
+// ========================================
+// ORG API KEYS
+// ========================================
+
+pub async fn list_org_api_keys(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<ApiKeyResponse>>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageApiKeys).await?;
+
+    let keys = state
+        .api_key_service
+        .list_owner_keys(org_id, "organization".to_string())
+        .await?;
+    Ok(Json(ApiResponse::success(
+        keys.into_iter().map(Into::into).collect(),
+    )))
+}
+
+pub async fn create_org_api_key(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateApiKeyRequest>,
+) -> Result<Json<ApiResponse<CreatedApiKeyResponse>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageApiKeys).await?;
+
+    let (key, secret) = state
+        .api_key_service
+        .create_api_key(
+            payload.name,
+            org_id,
+            "organization".to_string(),
+            payload.scopes,
+            payload.rate_limit_per_hour,
+            payload.expires_at,
+        )
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_api_key_create",
+        "api_key",
+        key.id,
+        serde_json::json!({"organization_id": org_id, "key_id": key.key_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(CreatedApiKeyResponse {
+        key: key.into(),
+        secret,
+    })))
+}
+
+/// Issues a new secret for the key. Integrations must switch to it right away;
+/// the old secret stops working.
+pub async fn rotate_org_api_key(
+    State(state): State<AppState>,
+    Path((org_id, key_id)): Path<(Uuid, String)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<CreatedApiKeyResponse>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageApiKeys).await?;
+
+    let key = state
+        .api_key_service
+        .get_owner_key(org_id, "organization", &key_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+    let (key, secret) = state.api_key_service.rotate_api_key(key).await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_api_key_rotate",
+        "api_key",
+        key.id,
+        serde_json::json!({"organization_id": org_id, "key_id": key_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(CreatedApiKeyResponse {
+        key: key.into(),
+        secret,
+    })))
+}
+
+pub async fn revoke_org_api_key(
+    State(state): State<AppState>,
+    Path((org_id, key_id)): Path<(Uuid, String)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageApiKeys).await?;
+
+    let key = state
+        .api_key_service
+        .get_owner_key(org_id, "organization", &key_id)
+        .await?
+        .ok_or(AppError::NotFound)?;
+
+    state.api_key_service.revoke_api_key(key_id.clone()).await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_api_key_revoke",
+        "api_key",
+        key.id,
+        serde_json::json!({"organization_id": org_id, "key_id": key_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success("API key revoked".to_string())))
+}
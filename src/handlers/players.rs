@@ -1,4 +1,4 @@
-use crate::models::enums::GameType;
+use crate::models::enums::{DmPrivacy, GameType};
 use crate::services::auth_service::Claims;
 use crate::services::player_service::UpdateProfileRequest;
 use crate::{utils::errors::AppError, AppState};
@@ -105,6 +105,7 @@ pub struct PlayerProfileResponse {
 
     // Preferences
     pub profile_visibility: String,
+    pub dm_privacy: DmPrivacy,
     pub card_theme: String,
 
     // Gamification
@@ -132,12 +133,7 @@ fn extract_client_info(
 pub async fn get_player_by_id(
     State(state): State<AppState>,
     Path(id): Path<Uuid>,
-    Extension(claims): Extension<Claims>,
 ) -> Result<Json<PlayerResponse>, AppError> {
-    let requesting_user_id = Uuid::parse_str(&claims.sub)?;
-    if requesting_user_id != id {
-        return Err(AppError::Forbidden);
-    }
     let player = state
         .player_service
         .get_by_id(id)
@@ -284,6 +280,7 @@ pub async fn get_current_player_profile(
         youtube: player.youtube,
         twitter: player.twitter,
         profile_visibility: player.profile_visibility,
+        dm_privacy: player.dm_privacy,
         card_theme: player.card_theme,
         coins: player.coins,
         check_in_streak: player.check_in_streak,
@@ -379,6 +376,7 @@ pub async fn update_player_profile(
         youtube: updated_player.youtube,
         twitter: updated_player.twitter,
         profile_visibility: updated_player.profile_visibility,
+        dm_privacy: updated_player.dm_privacy,
         card_theme: updated_player.card_theme,
         coins: updated_player.coins,
         check_in_streak: updated_player.check_in_streak,
@@ -0,0 +1,115 @@
+use super::auth::extract_client_info;
+use super::chat::ApiResponse;
+use crate::services::auth_service::Claims;
+use crate::utils::user_agent::{parse_user_agent, DeviceInfo};
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{ConnectInfo, Extension, Path, State},
+    http::HeaderMap,
+    response::Json,
+};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+#[derive(Serialize)]
+pub struct ActiveSessionResponse {
+    pub id: Uuid,
+    pub current: bool,
+    pub device: DeviceInfo,
+    pub ip_address: Option<String>,
+    pub location: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub last_activity: DateTime<Utc>,
+    pub expires_at: DateTime<Utc>,
+}
+
+fn claims_user_id(claims: &Claims) -> Result<Uuid, AppError> {
+    claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::Validation("Invalid user ID".to_string()))
+}
+
+/// Lists the caller's usable sessions with the device and rough location they
+/// were used from.
+pub async fn list_sessions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<ActiveSessionResponse>>>, AppError> {
+    let user_id = claims_user_id(&claims)?;
+    let sessions = state
+        .session_service
+        .list_active_sessions(user_id, &claims.user_type)
+        .await?;
+
+    let mut response = Vec::with_capacity(sessions.len());
+    for session in sessions {
+        let location = match &session.ip_address {
+            Some(ip) => state.geo_ip_service.locate(ip).await,
+            None => None,
+        };
+
+        response.push(ActiveSessionResponse {
+            id: session.id,
+            current: session.id.to_string() == claims.session_id,
+            device: parse_user_agent(session.user_agent.as_deref().unwrap_or_default()),
+            ip_address: session.ip_address,
+            location,
+            created_at: session.created_at,
+            last_activity: session.last_activity,
+            expires_at: session.expires_at.min(session.family_expires_at),
+        });
+    }
+
+    Ok(Json(ApiResponse::success(response)))
+}
+
+/// Signs out one session (and anything rotated from it) without touching the
+/// caller's other devices.
+pub async fn revoke_session(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(session_id): Path<Uuid>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+) -> Result<Json<ApiResponse<()>>, AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let user_id = claims_user_id(&claims)?;
+
+    let session = state
+        .session_service
+        .get_session(session_id)
+        .await?
+        .filter(|s| s.user_id == user_id && s.user_type == claims.user_type && !s.revoked)
+        .ok_or(AppError::NotFound)?;
+
+    state
+        .session_service
+        .revoke_family(session.family_id, "user_revoked")
+        .await?;
+
+    // Audit log
+    let _ = state
+        .audit_service
+        .log_action(
+            Some(user_id),
+            Some(claims.user_type.clone()),
+            claims.session_id.parse().ok(),
+            "session_revoked".to_string(),
+            Some("session".to_string()),
+            Some(session.id),
+            ip_address,
+            user_agent,
+            true,
+            None,
+            None,
+            Some(serde_json::json!({
+                "current": session.id.to_string() == claims.session_id,
+            })),
+        )
+        .await;
+
+    Ok(Json(ApiResponse::success(())))
+}
@@ -0,0 +1,385 @@
+use super::auth::{complete_login, extract_client_info, AuthResponse, LoginAccount};
+use super::chat::ApiResponse;
+use crate::services::auth_service::{Claims, TempTokenClaims};
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{ConnectInfo, Extension, State},
+    http::HeaderMap,
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use std::net::SocketAddr;
+use uuid::Uuid;
+
+#[derive(Deserialize)]
+pub struct TwoFactorVerifyRequest {
+    pub pending_token: String,
+    pub code: String, // TOTP code or a recovery code
+}
+
+#[derive(Deserialize)]
+pub struct TwoFactorSetupRequest {
+    pub pending_token: String,
+}
+
+#[derive(Deserialize)]
+pub struct TwoFactorSetupConfirmRequest {
+    pub pending_token: String,
+    pub code: String,
+}
+
+#[derive(Deserialize)]
+pub struct TwoFactorCodeRequest {
+    pub code: String,
+}
+
+#[derive(Serialize)]
+pub struct TwoFactorEnrollmentResponse {
+    pub secret: String,
+    pub otpauth_uri: String,
+}
+
+#[derive(Serialize)]
+pub struct TwoFactorStatusResponse {
+    pub enabled: bool,
+    pub mandatory: bool,
+    pub remaining_recovery_codes: u64,
+}
+
+#[derive(Serialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
+
+#[derive(Serialize)]
+pub struct TwoFactorSetupLoginResponse {
+    #[serde(flatten)]
+    pub auth: AuthResponse,
+    pub recovery_codes: Vec<String>,
+}
+
+const VERIFY_MAX_ATTEMPTS: i32 = 5;
+const VERIFY_WINDOW_MINUTES: i64 = 15;
+
+fn decode_pending_token(
+    state: &AppState,
+    token: &str,
+    expected_type: &str,
+) -> Result<(TempTokenClaims, Uuid), AppError> {
+    let claims = state.auth_service.verify_temp_token(token)?;
+    if claims.token_type != expected_type {
+        return Err(AppError::Validation("Invalid token type".to_string()));
+    }
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    Ok((claims, user_id))
+}
+
+async fn check_verify_rate_limit(state: &AppState, user_id: Uuid) -> Result<(), AppError> {
+    state
+        .rate_limit_service
+        .check_rate_limit(
+            user_id.to_string(),
+            "user_id".to_string(),
+            "2fa_verify".to_string(),
+            VERIFY_MAX_ATTEMPTS,
+            VERIFY_WINDOW_MINUTES,
+        )
+        .await?;
+    Ok(())
+}
+
+async fn audit_two_factor(
+    state: &AppState,
+    user_id: Uuid,
+    user_type: &str,
+    action: &str,
+    success: bool,
+    ip_address: Option<String>,
+    user_agent: Option<String>,
+) {
+    let _ = state
+        .audit_service
+        .log_action(
+            Some(user_id),
+            Some(user_type.to_string()),
+            None,
+            action.to_string(),
+            Some(user_type.to_string()),
+            Some(user_id),
+            ip_address,
+            user_agent,
+            success,
+            if success {
+                None
+            } else {
+                Some("Invalid authentication code".to_string())
+            },
+            None,
+            None,
+        )
+        .await;
+}
+
+fn claims_user_id(claims: &Claims) -> Result<Uuid, AppError> {
+    claims
+        .sub
+        .parse()
+        .map_err(|_| AppError::Validation("Invalid user ID".to_string()))
+}
+
+// ============================================================================
+// LOGIN SECOND STEP
+// ============================================================================
+
+/// Exchanges the pending token from `/auth/login` plus a TOTP or recovery code
+/// for a full session.
+pub async fn verify_two_factor_login(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorVerifyRequest>,
+) -> Result<(HeaderMap, Json<AuthResponse>), AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let (token, user_id) = decode_pending_token(&state, &payload.pending_token, "2fa_pending")?;
+
+    check_verify_rate_limit(&state, user_id).await?;
+
+    let account = LoginAccount::load(&state, &token.user_type, user_id).await?;
+    state
+        .suspension_service
+        .ensure_not_suspended(account.id)
+        .await?;
+
+    if !state
+        .two_factor_service
+        .verify(user_id, &payload.code)
+        .await?
+    {
+        audit_two_factor(
+            &state,
+            user_id,
+            &token.user_type,
+            "login_2fa_failed",
+            false,
+            ip_address,
+            user_agent,
+        )
+        .await;
+        return Err(AppError::Unauthorized);
+    }
+
+    complete_login(&state, account, ip_address, user_agent, "password+totp").await
+}
+
+/// Starts enrollment for an account that must set up 2FA before it can sign in.
+pub async fn setup_two_factor_login(
+    State(state): State<AppState>,
+    Json(payload): Json<TwoFactorSetupRequest>,
+) -> Result<Json<ApiResponse<TwoFactorEnrollmentResponse>>, AppError> {
+    let (token, user_id) = decode_pending_token(&state, &payload.pending_token, "2fa_setup")?;
+    let account = LoginAccount::load(&state, &token.user_type, user_id).await?;
+
+    let (secret, otpauth_uri) = state
+        .two_factor_service
+        .begin_enrollment(user_id, token.user_type.clone(), &account.user.email)
+        .await?;
+
+    Ok(Json(ApiResponse::success(TwoFactorEnrollmentResponse {
+        secret,
+        otpauth_uri,
+    })))
+}
+
+/// Confirms forced enrollment and signs the account in. The recovery codes are
+/// only ever returned here.
+pub async fn confirm_two_factor_setup_login(
+    State(state): State<AppState>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorSetupConfirmRequest>,
+) -> Result<(HeaderMap, Json<TwoFactorSetupLoginResponse>), AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let (token, user_id) = decode_pending_token(&state, &payload.pending_token, "2fa_setup")?;
+
+    check_verify_rate_limit(&state, user_id).await?;
+
+    let account = LoginAccount::load(&state, &token.user_type, user_id).await?;
+    state
+        .suspension_service
+        .ensure_not_suspended(account.id)
+        .await?;
+
+    let recovery_codes = state
+        .two_factor_service
+        .confirm_enrollment(user_id, &payload.code)
+        .await?;
+
+    audit_two_factor(
+        &state,
+        user_id,
+        &token.user_type,
+        "2fa_enabled",
+        true,
+        ip_address.clone(),
+        user_agent.clone(),
+    )
+    .await;
+
+    let (headers, Json(auth)) =
+        complete_login(&state, account, ip_address, user_agent, "password+totp").await?;
+
+    Ok((
+        headers,
+        Json(TwoFactorSetupLoginResponse {
+            auth,
+            recovery_codes,
+        }),
+    ))
+}
+
+// ============================================================================
+// AUTHENTICATED MANAGEMENT
+// ============================================================================
+
+pub async fn get_two_factor_status(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<TwoFactorStatusResponse>>, AppError> {
+    let user_id = claims_user_id(&claims)?;
+    let account = LoginAccount::load(&state, &claims.user_type, user_id).await?;
+
+    let enabled = state.two_factor_service.is_enabled(user_id).await?;
+    let remaining_recovery_codes = if enabled {
+        state
+            .two_factor_service
+            .remaining_recovery_codes(user_id)
+            .await?
+    } else {
+        0
+    };
+
+    Ok(Json(ApiResponse::success(TwoFactorStatusResponse {
+        enabled,
+        mandatory: account.two_factor_mandatory,
+        remaining_recovery_codes,
+    })))
+}
+
+pub async fn enroll_two_factor(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<TwoFactorEnrollmentResponse>>, AppError> {
+    let user_id = claims_user_id(&claims)?;
+    let account = LoginAccount::load(&state, &claims.user_type, user_id).await?;
+
+    let (secret, otpauth_uri) = state
+        .two_factor_service
+        .begin_enrollment(user_id, claims.user_type.clone(), &account.user.email)
+        .await?;
+
+    Ok(Json(ApiResponse::success(TwoFactorEnrollmentResponse {
+        secret,
+        otpauth_uri,
+    })))
+}
+
+pub async fn confirm_two_factor(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Json<ApiResponse<RecoveryCodesResponse>>, AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let user_id = claims_user_id(&claims)?;
+
+    check_verify_rate_limit(&state, user_id).await?;
+
+    let recovery_codes = state
+        .two_factor_service
+        .confirm_enrollment(user_id, &payload.code)
+        .await?;
+
+    audit_two_factor(
+        &state,
+        user_id,
+        &claims.user_type,
+        "2fa_enabled",
+        true,
+        ip_address,
+        user_agent,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(RecoveryCodesResponse {
+        recovery_codes,
+    })))
+}
+
+pub async fn disable_two_factor(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Json<ApiResponse<()>>, AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let user_id = claims_user_id(&claims)?;
+
+    let account = LoginAccount::load(&state, &claims.user_type, user_id).await?;
+    if account.two_factor_mandatory {
+        return Err(AppError::Forbidden);
+    }
+
+    check_verify_rate_limit(&state, user_id).await?;
+    let result = state
+        .two_factor_service
+        .disable(user_id, &payload.code)
+        .await;
+
+    audit_two_factor(
+        &state,
+        user_id,
+        &claims.user_type,
+        "2fa_disabled",
+        result.is_ok(),
+        ip_address,
+        user_agent,
+    )
+    .await;
+    result?;
+
+    Ok(Json(ApiResponse::success(())))
+}
+
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    ConnectInfo(addr): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
+    Json(payload): Json<TwoFactorCodeRequest>,
+) -> Result<Json<ApiResponse<RecoveryCodesResponse>>, AppError> {
+    let (ip_address, user_agent) = extract_client_info(&headers, Some(addr));
+    let user_id = claims_user_id(&claims)?;
+
+    check_verify_rate_limit(&state, user_id).await?;
+    let result = state
+        .two_factor_service
+        .regenerate_recovery_codes(user_id, &payload.code)
+        .await;
+
+    audit_two_factor(
+        &state,
+        user_id,
+        &claims.user_type,
+        "2fa_recovery_codes_regenerated",
+        result.is_ok(),
+        ip_address,
+        user_agent,
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(RecoveryCodesResponse {
+        recovery_codes: result?,
+    })))
+}
@@ -1,5 +1,7 @@
 use super::chat::ApiResponse;
+use crate::models::postgres::chat_attachment;
 use crate::services::auth_service::Claims;
+use crate::services::chat_service::AttachmentUpload;
 use crate::{utils::errors::AppError, AppState};
 use axum::extract::Extension;
 use axum::{
@@ -73,46 +75,56 @@ pub async fn upload_profile_picture(
 
 pub async fn upload_chat_attachment(
     State(state): State<AppState>,
-    Path(chat_id): Path<String>,
+    Path(chat_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
     mut multipart: Multipart,
-) -> Result<Json<ApiResponse<String>>, StatusCode> {
+) -> Result<Json<ApiResponse<chat_attachment::Model>>, AppError> {
     tracing::info!("📎 Processing chat attachment upload for chat: {}", chat_id);
 
+    let user_id = Uuid::parse_str(&claims.sub)?;
+    state.chat_service.require_member(chat_id, user_id).await?;
+
     while let Some(field) = multipart.next_field().await.map_err(|e| {
         tracing::error!("Multipart field error: {}", e);
-        StatusCode::BAD_REQUEST
+        AppError::Validation("Invalid multipart body".to_string())
     })? {
-        let name = field.name().unwrap_or("").to_string();
+        if field.name() != Some("file") {
+            continue;
+        }
 
-        if name == "file" {
-            let filename = field.file_name().unwrap_or("attachment").to_string();
-            let data = field.bytes().await.map_err(|e| {
-                tracing::error!("Failed to read file bytes: {}", e);
-                StatusCode::BAD_REQUEST
+        let filename = field.file_name().unwrap_or("attachment").to_string();
+        let data = field.bytes().await.map_err(|e| {
+            tracing::error!("Failed to read file bytes: {}", e);
+            AppError::Validation("Failed to read file".to_string())
+        })?;
+        let upload = AttachmentUpload::inspect(&filename, &data)?;
+
+        let (key, url) = state
+            .s3_service
+            .upload_chat_attachment(
+                &chat_id.to_string(),
+                &upload.filename,
+                &upload.content_type,
+                data.to_vec(),
+            )
+            .await
+            .map_err(|e| {
+                tracing::error!("❌ S3 upload failed: {}", e);
+                AppError::InternalServerError
             })?;
 
-            if data.is_empty() {
-                return Ok(Json(ApiResponse::error("File is empty".to_string())));
-            }
-
-            match state
-                .s3_service
-                .upload_chat_attachment(&chat_id, &filename, data.to_vec())
-                .await
-            {
-                Ok(url) => {
-                    tracing::info!("✅ Chat attachment uploaded successfully: {}", url);
-                    return Ok(Json(ApiResponse::success(url)));
-                }
-                Err(e) => {
-                    tracing::error!("❌ S3 upload failed: {}", e);
-                    return Ok(Json(ApiResponse::error(format!("Upload failed: {}", e))));
-                }
-            }
-        }
+        let attachment = state
+            .chat_service
+            .record_attachment(chat_id, user_id, upload, key, url)
+            .await?;
+        tracing::info!(
+            "✅ Chat attachment uploaded successfully: {}",
+            attachment.id
+        );
+        return Ok(Json(ApiResponse::success(attachment)));
     }
 
-    Ok(Json(ApiResponse::error("No file provided".to_string())))
+    Err(AppError::Validation("No file provided".to_string()))
 }
 
 pub async fn get_presigned_url(
@@ -0,0 +1,203 @@
+use super::chat::ApiResponse;
+use super::organizations::{audit_org_action, authorize};
+use crate::models::enums::OrgPermission;
+use crate::models::postgres::{webhook_delivery, webhook_endpoint};
+use crate::services::auth_service::Claims;
+use crate::{utils::errors::AppError, AppState};
+use axum::{
+    extract::{Extension, Path, Query, State},
+    response::Json,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+const DEFAULT_DELIVERY_LIMIT: u64 = 50;
+const MAX_DELIVERY_LIMIT: u64 = 200;
+
+#[derive(Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    pub description: Option<String>,
+    pub events: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub description: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Deserialize)]
+pub struct DeliveryListQuery {
+    pub limit: Option<u64>,
+}
+
+#[derive(Serialize)]
+pub struct CreatedWebhookResponse {
+    pub endpoint: webhook_endpoint::Model,
+    pub secret: String, // Only ever returned once
+}
+
+pub async fn list_org_webhooks(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<webhook_endpoint::Model>>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageWebhooks).await?;
+
+    let endpoints = state.webhook_service.list_endpoints(org_id).await?;
+    Ok(Json(ApiResponse::success(endpoints)))
+}
+
+pub async fn create_org_webhook(
+    State(state): State<AppState>,
+    Path(org_id): Path<Uuid>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> Result<Json<ApiResponse<CreatedWebhookResponse>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageWebhooks).await?;
+
+    let (endpoint, secret) = state
+        .webhook_service
+        .create_endpoint(org_id, payload.url, payload.description, payload.events)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_webhook_create",
+        "webhook_endpoint",
+        endpoint.id,
+        serde_json::json!({"organization_id": org_id, "url": endpoint.url, "events": endpoint.events}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(CreatedWebhookResponse {
+        endpoint,
+        secret,
+    })))
+}
+
+pub async fn update_org_webhook(
+    State(state): State<AppState>,
+    Path((org_id, endpoint_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> Result<Json<ApiResponse<webhook_endpoint::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageWebhooks).await?;
+
+    let endpoint = state
+        .webhook_service
+        .get_endpoint(org_id, endpoint_id)
+        .await?;
+    let endpoint = state
+        .webhook_service
+        .update_endpoint(
+            endpoint,
+            payload.url,
+            payload.description,
+            payload.events,
+            payload.is_active,
+        )
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_webhook_update",
+        "webhook_endpoint",
+        endpoint.id,
+        serde_json::json!({
+            "organization_id": org_id,
+            "url": endpoint.url,
+            "events": endpoint.events,
+            "is_active": endpoint.is_active,
+        }),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(endpoint)))
+}
+
+pub async fn delete_org_webhook(
+    State(state): State<AppState>,
+    Path((org_id, endpoint_id)): Path<(Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<String>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageWebhooks).await?;
+
+    let endpoint = state
+        .webhook_service
+        .get_endpoint(org_id, endpoint_id)
+        .await?;
+    state.webhook_service.delete_endpoint(endpoint).await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_webhook_delete",
+        "webhook_endpoint",
+        endpoint_id,
+        serde_json::json!({"organization_id": org_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(
+        "Webhook endpoint deleted".to_string(),
+    )))
+}
+
+pub async fn list_org_webhook_deliveries(
+    State(state): State<AppState>,
+    Path((org_id, endpoint_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<DeliveryListQuery>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<Vec<webhook_delivery::Model>>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageWebhooks).await?;
+
+    let endpoint = state
+        .webhook_service
+        .get_endpoint(org_id, endpoint_id)
+        .await?;
+    let limit = query
+        .limit
+        .unwrap_or(DEFAULT_DELIVERY_LIMIT)
+        .clamp(1, MAX_DELIVERY_LIMIT);
+    let deliveries = state
+        .webhook_service
+        .list_deliveries(endpoint.id, limit)
+        .await?;
+
+    Ok(Json(ApiResponse::success(deliveries)))
+}
+
+pub async fn replay_org_webhook_delivery(
+    State(state): State<AppState>,
+    Path((org_id, endpoint_id, delivery_id)): Path<(Uuid, Uuid, Uuid)>,
+    Extension(claims): Extension<Claims>,
+) -> Result<Json<ApiResponse<webhook_delivery::Model>>, AppError> {
+    authorize(&state, org_id, &claims, OrgPermission::ManageWebhooks).await?;
+
+    let endpoint = state
+        .webhook_service
+        .get_endpoint(org_id, endpoint_id)
+        .await?;
+    let delivery = state
+        .webhook_service
+        .replay(endpoint.id, delivery_id)
+        .await?;
+
+    audit_org_action(
+        &state,
+        &claims,
+        "org_webhook_replay",
+        "webhook_delivery",
+        delivery.id,
+        serde_json::json!({"organization_id": org_id, "replay_of": delivery_id}),
+    )
+    .await;
+
+    Ok(Json(ApiResponse::success(delivery)))
+}
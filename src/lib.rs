@@ -8,12 +8,21 @@ pub mod scripts;
 pub mod services;
 pub mod utils;
 
+use services::event_subscribers::{
+    ActivityLogSubscriber, ChatChannelSubscriber, DashboardCacheSubscriber, LeaderboardSubscriber,
+    NotificationSubscriber, VerificationEmailSubscriber, WebhookSubscriber,
+};
+use services::moderation_stages;
 use services::{
-    AdminService, ApiKeyService, AuditService, AuthService, BattleService, ChatService,
-    CommunityService, DashboardService, EmailService, OrganizationService, PlayerGameStatsService,
-    PlayerService, RateLimitService, RewardService, S3Service, SessionService, TeamService,
-    TournamentService, TournamentTeamInviteService, TournamentTeamService, TransactionService,
+    AdminService, AnnouncementService, ApiKeyService, AuditService, AuthService, BattleService,
+    ChatChannelService, ChatRealtimeService, ChatService, CommunityService, ConsumedTokenService,
+    DashboardService, DirectMessageService, EmailService, GeoIpService, LoginProtectionService,
+    ModerationService, OAuthService, OrganizationMemberService, OrganizationService, OutboxService,
+    PlayerGameStatsService, PlayerService, RateLimitService, RewardService, S3Service,
+    SessionService, SuspensionService, TeamService, TournamentService, TournamentTeamInviteService,
+    TournamentTeamService, TransactionService, TwoFactorService, WebhookService,
 };
+use std::sync::Arc;
 
 #[derive(Clone)]
 pub struct AppState {
@@ -26,6 +35,7 @@ pub struct AppState {
     pub player_service: PlayerService,
     pub admin_service: AdminService,
     pub organization_service: OrganizationService,
+    pub organization_member_service: OrganizationMemberService,
     pub team_service: TeamService,
     pub tournament_service: TournamentService,
     pub tournament_team_service: TournamentTeamService,
@@ -36,12 +46,25 @@ pub struct AppState {
     pub transaction_service: TransactionService,
     pub email_service: EmailService,
     pub chat_service: ChatService,
+    pub moderation_service: ModerationService,
+    pub chat_channel_service: ChatChannelService,
+    pub announcement_service: AnnouncementService,
+    pub direct_message_service: DirectMessageService,
+    pub chat_realtime_service: ChatRealtimeService,
     pub community_service: CommunityService,
     pub s3_service: S3Service,
     pub session_service: SessionService,
     pub audit_service: AuditService,
     pub rate_limit_service: RateLimitService,
     pub api_key_service: ApiKeyService,
+    pub suspension_service: SuspensionService,
+    pub two_factor_service: TwoFactorService,
+    pub oauth_service: OAuthService,
+    pub consumed_token_service: ConsumedTokenService,
+    pub geo_ip_service: GeoIpService,
+    pub login_protection_service: LoginProtectionService,
+    pub webhook_service: WebhookService,
+    pub outbox_service: OutboxService,
 }
 
 impl AppState {
@@ -50,6 +73,7 @@ impl AppState {
         sql_pool: sqlx::PgPool,
         aws: config::AwsClients,
         settings: config::Settings,
+        io: socketioxide::SocketIo,
     ) -> Self {
         let auth_service = AuthService::new(settings.jwt.secret.clone(), settings.jwt.expiration);
         let email_service =
@@ -59,11 +83,17 @@ impl AppState {
         let player_service = PlayerService::new(db.clone(), auth_service.clone());
         let admin_service = AdminService::new(db.clone(), auth_service.clone());
         let organization_service = OrganizationService::new(db.clone(), auth_service.clone());
+        let organization_member_service =
+            OrganizationMemberService::new(db.clone(), auth_service.clone());
 
         // Gaming services - ADD auth_service where needed
         let team_service = TeamService::new(db.clone());
         let tournament_service = TournamentService::new(db.clone());
-        let tournament_team_service = TournamentTeamService::new(db.clone());
+        let chat_realtime_service = ChatRealtimeService::new(io, settings.redis.url.clone());
+        let chat_channel_service =
+            ChatChannelService::new(db.clone(), chat_realtime_service.clone());
+        let tournament_team_service =
+            TournamentTeamService::new(db.clone(), chat_channel_service.clone());
         let tournament_team_invite_service = TournamentTeamInviteService::new(db.clone());
         let battle_service = BattleService::new(db.clone());
         let player_game_stats_service = PlayerGameStatsService::new(db.clone());
@@ -71,16 +101,61 @@ impl AppState {
         let transaction_service = TransactionService::new(db.clone());
 
         // Enterprise security services - ADD auth_service
-        let session_service = SessionService::new(db.clone());
+        let session_service = SessionService::new(db.clone(), settings.session.clone());
         let audit_service = AuditService::new(db.clone());
-        let rate_limit_service = RateLimitService::new(db.clone());
-        let api_key_service = ApiKeyService::new(db.clone(), auth_service.clone());
+        let rate_limit_service = RateLimitService::new(db.clone(), settings.redis.url.clone());
+        let api_key_service =
+            ApiKeyService::new(db.clone(), auth_service.clone(), settings.api_keys.clone());
+        let suspension_service = SuspensionService::new(db.clone());
+        let two_factor_service = TwoFactorService::new(db.clone());
+        let oauth_service = OAuthService::new(db.clone(), settings.oauth.clone());
+        let consumed_token_service = ConsumedTokenService::new(db.clone());
+        let geo_ip_service = GeoIpService::new(settings.geoip.lookup_url.clone());
+        let login_protection_service = LoginProtectionService::new(db.clone());
+        let webhook_service = WebhookService::new(db.clone());
 
-        let chat_service = ChatService::new(db.clone());
-        let community_service = CommunityService::new(db.clone());
+        let moderation_service = ModerationService::new(
+            db.clone(),
+            audit_service.clone(),
+            moderation_stages::standard_stages(&db, &settings.moderation),
+        );
+        let chat_service = ChatService::new(db.clone(), moderation_service.clone());
+        let direct_message_service = DirectMessageService::new(db.clone());
+        let announcement_service =
+            AnnouncementService::new(db.clone(), chat_realtime_service.clone());
+        let community_service = CommunityService::new(db.clone(), moderation_service.clone());
         let s3_service = S3Service::new(aws.s3.clone());
         let dashboard_service = DashboardService::new(sql_pool.clone(), settings.redis.url.clone());
 
+        // Side effects of domain events, run by the outbox dispatcher
+        let outbox_service = OutboxService::new(
+            db.clone(),
+            vec![
+                Arc::new(VerificationEmailSubscriber {
+                    auth_service: auth_service.clone(),
+                    email_service: email_service.clone(),
+                }),
+                Arc::new(DashboardCacheSubscriber {
+                    db: db.clone(),
+                    dashboard_service: dashboard_service.clone(),
+                }),
+                Arc::new(WebhookSubscriber {
+                    db: db.clone(),
+                    webhook_service: webhook_service.clone(),
+                }),
+                Arc::new(ChatChannelSubscriber {
+                    db: db.clone(),
+                    chat_channels: chat_channel_service.clone(),
+                }),
+                Arc::new(NotificationSubscriber {
+                    db: db.clone(),
+                    realtime: chat_realtime_service.clone(),
+                }),
+                Arc::new(LeaderboardSubscriber { db: db.clone() }),
+                Arc::new(ActivityLogSubscriber { db: db.clone() }),
+            ],
+        );
+
         Self {
             db,
             dashboard_service,
@@ -91,6 +166,7 @@ impl AppState {
             player_service,
             admin_service,
             organization_service,
+            organization_member_service,
             team_service,
             tournament_service,
             tournament_team_service,
@@ -100,6 +176,11 @@ impl AppState {
             reward_service,
             transaction_service,
             chat_service,
+            moderation_service,
+            chat_channel_service,
+            announcement_service,
+            direct_message_service,
+            chat_realtime_service,
             community_service,
             email_service,
             s3_service,
@@ -107,6 +188,14 @@ impl AppState {
             audit_service,
             rate_limit_service,
             api_key_service,
+            suspension_service,
+            two_factor_service,
+            oauth_service,
+            consumed_token_service,
+            geo_ip_service,
+            login_protection_service,
+            webhook_service,
+            outbox_service,
         }
     }
 }
@@ -1,14 +1,18 @@
 use axum::{extract::Request, middleware, response::Response, routing::get, Router};
 use std::env;
+use std::time::Duration;
 use tower_http::trace::TraceLayer;
 use tracing_subscriber;
 
 use aegis_backend::{
     config::{AwsClients, Settings},
+    middleware::permissions::{init_permissions, is_protected_route, spawn_permission_watcher},
+    middleware::rate_limit::RateLimitLayer,
     migration::Migrator,
     AppState,
 };
 use sea_orm_migration::prelude::*;
+use socketioxide::SocketIo;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
@@ -27,6 +31,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Load configuration
     let settings = Settings::new()?;
 
+    // Route permissions: fail fast on a broken file, then watch it for changes
+    let rule_count = init_permissions()?;
+    tracing::info!("🔐 Loaded {} route permission rules", rule_count);
+    spawn_permission_watcher(Duration::from_secs(
+        env::var("AEGIS_PERMISSIONS__RELOAD_SECS")
+            .ok()
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(30),
+    ));
+
     // Initialize databases - BOTH SeaORM and SQLx
     let database_url = &settings.database.url;
     let db = sea_orm::Database::connect(database_url).await?;
@@ -37,17 +51,138 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Setup S3 bucket
     setup_aws_resources(&aws_clients).await?;
 
+    // Socket.IO is served by a layer in front of the router; its namespaces
+    // need the app state, so they're registered once the state exists
+    let (socket_layer, io) = SocketIo::new_layer();
+
     // Create application state
-    let app_state = AppState::new(db, sql_pool, aws_clients, settings.clone()).await;
+    let app_state = AppState::new(db, sql_pool, aws_clients, settings.clone(), io).await;
+    aegis_backend::handlers::chat_socket::register_chat_namespace(app_state.clone());
+    app_state.chat_realtime_service.spawn_relay();
+
+    // Redeemed single-use tokens only need to be remembered until they expire
+    let consumed_tokens = app_state.consumed_token_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = consumed_tokens.purge_expired().await {
+                tracing::warn!("Failed to purge consumed tokens: {:?}", e);
+            }
+        }
+    });
+
+    // Login failures only feed the short sliding windows
+    let login_protection = app_state.login_protection_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = login_protection.purge_old_failures().await {
+                tracing::warn!("Failed to purge login failures: {:?}", e);
+            }
+        }
+    });
+
+    // API key last_used_at values are collected in memory and written in batches
+    let api_keys = app_state.api_key_service.clone();
+    let flush_every = settings.api_keys.last_used_flush_secs.max(1);
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(flush_every));
+        loop {
+            interval.tick().await;
+            if let Err(e) = api_keys.flush_last_used().await {
+                tracing::warn!("Failed to record API key usage: {:?}", e);
+            }
+        }
+    });
+
+    // Organization webhooks are queued in the database and sent from here
+    let webhooks = app_state.webhook_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(5));
+        loop {
+            interval.tick().await;
+            if let Err(e) = webhooks.process_due_deliveries().await {
+                tracing::warn!("Failed to process webhook deliveries: {:?}", e);
+            }
+        }
+    });
+
+    // Domain events from the outbox fan out to in-process subscribers
+    let outbox = app_state.outbox_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(2));
+        loop {
+            interval.tick().await;
+            if let Err(e) = outbox.dispatch_pending().await {
+                tracing::warn!("Failed to dispatch outbox events: {:?}", e);
+            }
+        }
+    });
+
+    let outbox = app_state.outbox_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(3600));
+        loop {
+            interval.tick().await;
+            if let Err(e) = outbox.purge_processed().await {
+                tracing::warn!("Failed to purge outbox events: {:?}", e);
+            }
+        }
+    });
+
+    // Team chats and lobbies follow roster and battle changes made anywhere
+    let chat_channels = app_state.chat_channel_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(300));
+        loop {
+            interval.tick().await;
+            if let Err(e) = chat_channels.reconcile().await {
+                tracing::warn!("Failed to reconcile provisioned chats: {:?}", e);
+            }
+        }
+    });
+
+    // Files of deleted chat messages are removed from storage
+    let chat_service = app_state.chat_service.clone();
+    let s3_service = app_state.s3_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(300));
+        loop {
+            interval.tick().await;
+            if let Err(e) = chat_service.purge_discarded_attachments(&s3_service).await {
+                tracing::warn!("Failed to purge discarded chat attachments: {:?}", e);
+            }
+        }
+    });
+
+    // Scheduled announcements are pushed once their publish time comes
+    let announcements = app_state.announcement_service.clone();
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(Duration::from_secs(15));
+        loop {
+            interval.tick().await;
+            if let Err(e) = announcements.publish_due().await {
+                tracing::warn!("Failed to publish announcements: {:?}", e);
+            }
+        }
+    });
 
     // Build routes
     let app = Router::new()
         .route("/health", get(health_check))
         .nest("", aegis_backend::routes::create_routes())
+        // Inside the auth layer so per-user policies can read the claims
+        .layer(RateLimitLayer::with_default_policies(
+            app_state.rate_limit_service.clone(),
+        ))
         .layer(middleware::from_fn_with_state(
             app_state.clone(),
             selective_auth_middleware,
         ))
+        // Outside the auth layer: the /chat namespace authenticates the handshake
+        .layer(socket_layer)
         .layer(TraceLayer::new_for_http())
         .layer(aegis_backend::middleware::cors::cors_layer())
         .with_state(app_state);
@@ -85,7 +220,7 @@ async fn selective_auth_middleware(
     let path = req.uri().path();
 
     println!("DEBUG: Middleware checking path: {}", path);
-    let is_protected = matches_protected_route(&path);
+    let is_protected = is_protected_route(req.method().as_str(), path);
 
     if is_protected {
         println!("DEBUG: Path is protected, checking JWT");
@@ -114,21 +249,6 @@ async fn selective_auth_middleware(
     }
 }
 
-fn matches_protected_route(path: &str) -> bool {
-    path.starts_with("/auth/logout")
-        // || path.starts_with("/auth/refresh")
-        || path.starts_with("/auth/revoke-sessions")
-        || path.starts_with("/auth/send-verification")
-        || path == "/me"
-        || path.starts_with("/players/me")
-        || path.starts_with("/players/profile")
-        || path == "/players"
-        || path.starts_with("/chats")
-        || path.starts_with("/communities")
-        || path.starts_with("/uploads")
-        || path.starts_with("/dashboard")
-}
-
 async fn run_migrations() -> Result<(), Box<dyn std::error::Error>> {
     let database_url = env::var("DATABASE_URL").expect("DATABASE_URL must be set");
 
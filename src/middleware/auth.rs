@@ -1,22 +1,34 @@
-use crate::middleware::permissions::check_permission;
+use crate::middleware::permissions::{check_permission, required_api_scope};
+use crate::middleware::rate_limit::insert_rate_limit_headers;
 use crate::models::enums::ApprovalStatus;
+use crate::models::postgres::api_key;
 use crate::services::auth_service::Claims;
 use crate::utils::errors::AppError;
 use crate::AppState;
 use axum::{
     extract::{Request, State},
+    http::HeaderMap,
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use chrono::Utc;
+
+const API_KEY_PREFIX: &str = "ApiKey ";
+const API_KEY_WINDOW_SECS: u64 = 3600;
 
 pub async fn jwt_auth_middleware(
     State(state): State<AppState>,
     mut request: Request,
     next: Next,
 ) -> Result<Response, AppError> {
+    if let Some(full_key) = extract_api_key(&request) {
+        return api_key_auth(state, full_key, request, next).await;
+    }
+
     println!("DEBUG: JWT middleware - extracting token");
 
     let path = request.uri().path();
+    let method = request.method().clone();
     let token = match extract_token_from_request(&request) {
         Ok(t) => {
             println!("DEBUG: Token extracted successfully (length: {})", t.len());
@@ -28,8 +40,35 @@ pub async fn jwt_auth_middleware(
         }
     };
 
+    let claims = authenticate_token(&state, &token).await?;
+
+    // Check path permissions using our permission system
+    println!(
+        "DEBUG: Checking permissions for path: {} with user_type: {}, verified: {}",
+        path, claims.user_type, claims.verified
+    );
+    // Check path permissions using our permission system
+    match check_permission(method.as_str(), path, &claims) {
+        Ok(()) => {
+            println!("DEBUG: Permission check passed");
+        }
+        Err(status) => {
+            println!("DEBUG: Permission check failed with status: {:?}", status);
+            return Err(AppError::Forbidden);
+        }
+    }
+
+    println!("DEBUG: JWT middleware - all checks passed");
+    request.extensions_mut().insert(claims);
+    Ok(next.run(request).await)
+}
+
+/// Verifies a JWT and the session behind it: the session must be live, belong
+/// to the token's subject, and the account must not be suspended. Shared by the
+/// HTTP middleware and the Socket.IO handshake.
+pub async fn authenticate_token(state: &AppState, token: &str) -> Result<Claims, AppError> {
     println!("DEBUG: JWT middleware - verifying JWT");
-    let claims = match state.auth_service.verify_jwt(&token) {
+    let claims = match state.auth_service.verify_jwt(token) {
         Ok(c) => {
             println!(
                 "DEBUG: JWT verification successful, session_id: {}",
@@ -78,50 +117,173 @@ pub async fn jwt_auth_middleware(
         return Err(AppError::Unauthorized);
     }
 
-    // Check path permissions using our permission system
-    println!(
-        "DEBUG: Checking permissions for path: {} with user_type: {}, verified: {}",
-        path, claims.user_type, claims.verified
-    );
-    // Check path permissions using our permission system
-    match check_permission(path, &claims) {
-        Ok(()) => {
-            println!("DEBUG: Permission check passed");
+    // Suspended or banned accounts keep their sessions revoked, but a token
+    // minted before the suspension may still be in flight
+    if let Some(suspension) = state.suspension_service.get_active(session.user_id).await? {
+        tracing::debug!(
+            "Account {} is under {} until {:?}",
+            session.user_id,
+            suspension.kind.as_str(),
+            suspension.expires_at
+        );
+        return Err(AppError::Forbidden);
+    }
+
+    // Keep last_activity fresh for the idle timeout and the session list.
+    // Throttled in the service, and off the request path
+    let session_service = state.session_service.clone();
+    tokio::spawn(async move {
+        if let Err(e) = session_service.touch_session(&session).await {
+            tracing::warn!("Failed to update session activity: {:?}", e);
         }
-        Err(status) => {
-            println!("DEBUG: Permission check failed with status: {:?}", status);
-            return Err(AppError::Forbidden);
+    });
+
+    Ok(claims)
+}
+
+fn extract_api_key(request: &Request) -> Option<String> {
+    request
+        .headers()
+        .get("authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix(API_KEY_PREFIX))
+        .map(|key| key.trim().to_string())
+}
+
+/// Claims standing in for the key's owner, so handlers treat an API key call
+/// like one from the owner limited to the key's scopes. There is no session.
+async fn api_key_owner_claims(state: &AppState, key: &api_key::Model) -> Result<Claims, AppError> {
+    let verified = match key.owner_type.as_str() {
+        "admin" => {
+            let admin = state
+                .admin_service
+                .get_by_id(key.owner_id)
+                .await?
+                .ok_or(AppError::Unauthorized)?;
+            if !admin.is_active {
+                return Err(AppError::Unauthorized);
+            }
+            true
+        }
+        "organization" => {
+            let org = state
+                .organization_service
+                .get_by_id(key.owner_id)
+                .await?
+                .ok_or(AppError::Unauthorized)?;
+            if org.approval_status != ApprovalStatus::Approved {
+                return Err(AppError::Forbidden);
+            }
+            org.email_verified
         }
+        _ => return Err(AppError::Unauthorized),
+    };
+
+    if state
+        .suspension_service
+        .get_active(key.owner_id)
+        .await?
+        .is_some()
+    {
+        return Err(AppError::Forbidden);
     }
 
-    println!("DEBUG: JWT middleware - all checks passed");
-    request.extensions_mut().insert(claims);
-    Ok(next.run(request).await)
+    let now = Utc::now();
+    Ok(Claims {
+        sub: key.owner_id.to_string(),
+        user_type: key.owner_type.clone(),
+        session_id: String::new(),
+        verified,
+        exp: key.expires_at.unwrap_or(now).timestamp() as usize,
+        iat: now.timestamp() as usize,
+        jti: key.key_id.clone(),
+    })
+}
+
+/// `Authorization: ApiKey ak_...` requests: the route must name an API scope
+/// the key holds, and each key gets `rate_limit_per_hour` requests per hour.
+async fn api_key_auth(
+    state: AppState,
+    full_key: String,
+    mut request: Request,
+    next: Next,
+) -> Result<Response, AppError> {
+    let key = state
+        .api_key_service
+        .validate_api_key(&full_key)
+        .await?
+        .ok_or(AppError::Unauthorized)?;
+    let claims = api_key_owner_claims(&state, &key).await?;
+
+    let method = request.method().as_str().to_string();
+    let path = request.uri().path().to_string();
+    check_permission(&method, &path, &claims).map_err(|_| AppError::Forbidden)?;
+
+    let scope = required_api_scope(&method, &path).ok_or(AppError::Forbidden)?;
+    if !state.api_key_service.check_scope(&key, &scope).await {
+        return Err(AppError::Forbidden);
+    }
+
+    let decision = match state
+        .rate_limit_service
+        .hit(
+            &key.key_id,
+            "api_key",
+            "api_key_requests",
+            key.rate_limit_per_hour.max(0) as u32,
+            API_KEY_WINDOW_SECS,
+        )
+        .await
+    {
+        Ok(decision) => Some(decision),
+        Err(e) => {
+            tracing::error!("API key rate limit check failed: {:?}", e);
+            None
+        }
+    };
+
+    let mut response = match decision {
+        Some(d) if !d.allowed => AppError::RateLimited.into_response(),
+        _ => {
+            request.extensions_mut().insert(claims);
+            request.extensions_mut().insert(key);
+            next.run(request).await
+        }
+    };
+    if let Some(decision) = decision {
+        insert_rate_limit_headers(response.headers_mut(), &decision);
+    }
+    Ok(response)
 }
 
 fn extract_token_from_request(request: &Request) -> Result<String, AppError> {
+    token_from_headers(request.headers()).ok_or(AppError::Unauthorized)
+}
+
+/// Bearer token from the Authorization header, falling back to the `token` cookie.
+pub fn token_from_headers(headers: &HeaderMap) -> Option<String> {
     // Try Authorization header first
-    if let Some(auth_header) = request.headers().get("authorization") {
+    if let Some(auth_header) = headers.get("authorization") {
         if let Ok(auth_str) = auth_header.to_str() {
             if let Some(token) = auth_str.strip_prefix("Bearer ") {
-                return Ok(token.to_string());
+                return Some(token.to_string());
             }
         }
     }
 
     // Try cookie as fallback
-    if let Some(cookie_header) = request.headers().get("cookie") {
+    if let Some(cookie_header) = headers.get("cookie") {
         if let Ok(cookies) = cookie_header.to_str() {
             for cookie in cookies.split(';') {
                 let cookie = cookie.trim();
                 if let Some(token) = cookie.strip_prefix("token=") {
-                    return Ok(token.to_string());
+                    return Some(token.to_string());
                 }
             }
         }
     }
 
-    Err(AppError::Unauthorized)
+    None
 }
 
 // Admin-only middleware
@@ -1,24 +1,78 @@
-use crate::config::permissions::{get_path_permissions, PathPermission};
+use crate::config::permissions::{
+    get_path_permissions, permissions_file_path, PermissionConfigError, PermissionTable,
+};
 use crate::services::auth_service::Claims;
 use axum::http::StatusCode;
-use std::sync::OnceLock;
+use std::sync::{Arc, OnceLock, RwLock};
+use std::time::{Duration, SystemTime};
 
-static PERMISSIONS: OnceLock<Vec<PathPermission>> = OnceLock::new();
+static PERMISSIONS: OnceLock<RwLock<Arc<PermissionTable>>> = OnceLock::new();
 
-fn get_permissions() -> &'static Vec<PathPermission> {
-    PERMISSIONS.get_or_init(|| get_path_permissions())
+/// Loads and validates the permission file. Call once at startup so a broken
+/// file stops the server instead of denying every request.
+pub fn init_permissions() -> Result<usize, PermissionConfigError> {
+    let table = get_path_permissions()?;
+    let count = table.len();
+
+    match PERMISSIONS.get() {
+        Some(lock) => *lock.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(table),
+        None => {
+            let _ = PERMISSIONS.set(RwLock::new(Arc::new(table)));
+        }
+    }
+
+    Ok(count)
 }
 
-pub fn check_permission(path: &str, claims: &Claims) -> Result<(), StatusCode> {
-    let permissions = get_permissions();
+/// Re-reads the permission file, keeping the current table if the new one is invalid.
+pub fn reload_permissions() -> Result<usize, PermissionConfigError> {
+    init_permissions()
+}
+
+fn get_permissions() -> Arc<PermissionTable> {
+    let lock = PERMISSIONS.get_or_init(|| {
+        RwLock::new(Arc::new(
+            get_path_permissions().expect("Failed to load route permission file"),
+        ))
+    });
+    lock.read().unwrap_or_else(|e| e.into_inner()).clone()
+}
+
+/// Polls the permission file and hot-reloads it when its modification time changes.
+pub fn spawn_permission_watcher(interval: Duration) {
+    tokio::spawn(async move {
+        let path = permissions_file_path();
+        let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let mut last_seen: Option<SystemTime> = modified(&path);
+
+        loop {
+            tokio::time::sleep(interval).await;
 
-    // Find matching permission (longest match wins)
-    let matching_permission = permissions
-        .iter()
-        .filter(|perm| path_matches(path, &perm.path))
-        .max_by_key(|perm| perm.path.len());
+            let current = modified(&path);
+            if current == last_seen {
+                continue;
+            }
+            last_seen = current;
 
-    if let Some(permission) = matching_permission {
+            match reload_permissions() {
+                Ok(count) => tracing::info!("🔐 Reloaded {} route permission rules", count),
+                Err(e) => tracing::error!(
+                    "❌ Invalid route permission file, keeping previous rules: {}",
+                    e
+                ),
+            }
+        }
+    });
+}
+
+pub fn is_protected_route(method: &str, path: &str) -> bool {
+    get_permissions().is_protected(method, path)
+}
+
+pub fn check_permission(method: &str, path: &str, claims: &Claims) -> Result<(), StatusCode> {
+    let permissions = get_permissions();
+
+    if let Some(permission) = permissions.find(method, path) {
         // Check if public route
         if permission.access.contains(&"public".to_string()) {
             return Ok(());
@@ -41,11 +95,10 @@ pub fn check_permission(path: &str, claims: &Claims) -> Result<(), StatusCode> {
     Ok(())
 }
 
-fn path_matches(request_path: &str, permission_path: &str) -> bool {
-    if permission_path.ends_with("*") {
-        let prefix = &permission_path[..permission_path.len() - 1];
-        request_path.starts_with(prefix)
-    } else {
-        request_path == permission_path
-    }
+/// The scope an API key needs for a route. `None` means the route doesn't
+/// accept API keys at all.
+pub fn required_api_scope(method: &str, path: &str) -> Option<String> {
+    get_permissions()
+        .find(method, path)
+        .and_then(|permission| permission.api_scope.clone())
 }
@@ -1,13 +1,23 @@
+use crate::services::auth_service::Claims;
+use crate::services::rate_limit_service::{RateLimitDecision, RateLimitService};
+use crate::utils::errors::AppError;
 use axum::{
     extract::{ConnectInfo, Request},
-    http::StatusCode,
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
+use chrono::Utc;
+use sha2::{Digest, Sha256};
 use std::collections::HashMap;
+use std::convert::Infallible;
+use std::future::Future;
 use std::net::SocketAddr;
+use std::pin::Pin;
 use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
 use std::time::{Duration, Instant};
+use tower::{Layer, Service};
 
 #[derive(Clone)]
 pub struct RateLimiter {
@@ -67,3 +77,258 @@ pub async fn login_rate_limit_middleware(
 
     Ok(next.run(request).await)
 }
+
+// ============================================================================
+// DISTRIBUTED PER-ROUTE RATE LIMITING
+// ============================================================================
+
+/// What a policy counts requests against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitKey {
+    Ip,
+    User,   // falls back to the client IP on unauthenticated requests
+    ApiKey, // falls back to the user, then the client IP
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitPolicy {
+    pub name: &'static str,
+    pub limit: u32,
+    pub window_secs: u64,
+    pub key: RateLimitKey,
+}
+
+pub mod policies {
+    use super::{RateLimitKey, RateLimitPolicy};
+
+    pub const LOGIN: RateLimitPolicy = RateLimitPolicy {
+        name: "login",
+        limit: 10,
+        window_secs: 60,
+        key: RateLimitKey::Ip,
+    };
+    pub const REGISTER: RateLimitPolicy = RateLimitPolicy {
+        name: "register",
+        limit: 5,
+        window_secs: 3600,
+        key: RateLimitKey::Ip,
+    };
+    pub const FORGOT_PASSWORD: RateLimitPolicy = RateLimitPolicy {
+        name: "forgot_password",
+        limit: 5,
+        window_secs: 900,
+        key: RateLimitKey::Ip,
+    };
+    pub const UPLOAD: RateLimitPolicy = RateLimitPolicy {
+        name: "upload",
+        limit: 30,
+        window_secs: 3600,
+        key: RateLimitKey::User,
+    };
+    pub const CHAT_SEND: RateLimitPolicy = RateLimitPolicy {
+        name: "chat_send",
+        limit: 30,
+        window_secs: 60,
+        key: RateLimitKey::User,
+    };
+}
+
+#[derive(Debug, Clone)]
+struct RoutePolicy {
+    method: Method,
+    segments: Vec<Option<String>>, // None matches any `:param` segment
+    policy: RateLimitPolicy,
+}
+
+impl RoutePolicy {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        if self.method != method {
+            return false;
+        }
+        let parts: Vec<&str> = path.split('/').filter(|p| !p.is_empty()).collect();
+        parts.len() == self.segments.len()
+            && self
+                .segments
+                .iter()
+                .zip(&parts)
+                .all(|(segment, part)| segment.as_deref().is_none_or(|s| s == *part))
+    }
+}
+
+/// Tower layer applying per-route rate limit policies through
+/// [`RateLimitService`]. Must sit inside the auth middleware so user-keyed
+/// policies can see the caller's claims.
+#[derive(Clone)]
+pub struct RateLimitLayer {
+    limiter: RateLimitService,
+    routes: Arc<Vec<RoutePolicy>>,
+}
+
+impl RateLimitLayer {
+    pub fn new(limiter: RateLimitService) -> Self {
+        Self {
+            limiter,
+            routes: Arc::new(Vec::new()),
+        }
+    }
+
+    /// The policies for the stock API routes.
+    pub fn with_default_policies(limiter: RateLimitService) -> Self {
+        Self::new(limiter)
+            .route(Method::POST, "/auth/login", policies::LOGIN)
+            .route(Method::POST, "/auth/register", policies::REGISTER)
+            .route(
+                Method::POST,
+                "/auth/forgot-password",
+                policies::FORGOT_PASSWORD,
+            )
+            .route(Method::POST, "/uploads/profile/:user_id", policies::UPLOAD)
+            .route(Method::POST, "/uploads/chat/:chat_id", policies::UPLOAD)
+            .route(
+                Method::POST,
+                "/chats/:chat_id/messages",
+                policies::CHAT_SEND,
+            )
+    }
+
+    /// Adds a policy for `method` on `path`, where `:name` segments match anything.
+    pub fn route(mut self, method: Method, path: &str, policy: RateLimitPolicy) -> Self {
+        let segments = path
+            .split('/')
+            .filter(|p| !p.is_empty())
+            .map(|p| (!p.starts_with(':')).then(|| p.to_string()))
+            .collect();
+
+        Arc::make_mut(&mut self.routes).push(RoutePolicy {
+            method,
+            segments,
+            policy,
+        });
+        self
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimitMiddleware<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimitMiddleware {
+            inner,
+            limiter: self.limiter.clone(),
+            routes: self.routes.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct RateLimitMiddleware<S> {
+    inner: S,
+    limiter: RateLimitService,
+    routes: Arc<Vec<RoutePolicy>>,
+}
+
+/// Picks the identifier a policy counts against, with the type stored by the
+/// Postgres fallback.
+fn rate_limit_identity(request: &Request, key: RateLimitKey) -> (String, &'static str) {
+    if key == RateLimitKey::ApiKey {
+        let api_key = request
+            .headers()
+            .get(header::AUTHORIZATION)
+            .and_then(|h| h.to_str().ok())
+            .and_then(|h| h.strip_prefix("ApiKey "));
+        if let Some(api_key) = api_key {
+            // Never keep the secret itself in Redis
+            let digest = hex::encode(Sha256::digest(api_key.trim().as_bytes()));
+            return (digest[..32].to_string(), "api_key");
+        }
+    }
+
+    if key != RateLimitKey::Ip {
+        if let Some(claims) = request.extensions().get::<Claims>() {
+            return (format!("{}:{}", claims.user_type, claims.sub), "user_id");
+        }
+    }
+
+    let ip = request
+        .extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unknown".to_string());
+    (ip, "ip")
+}
+
+pub(crate) fn insert_rate_limit_headers(headers: &mut HeaderMap, decision: &RateLimitDecision) {
+    let reset_at = Utc::now().timestamp() as u64 + decision.reset_after_secs;
+    headers.insert("x-ratelimit-limit", HeaderValue::from(decision.limit));
+    headers.insert(
+        "x-ratelimit-remaining",
+        HeaderValue::from(decision.remaining),
+    );
+    headers.insert("x-ratelimit-reset", HeaderValue::from(reset_at));
+    if !decision.allowed {
+        headers.insert(
+            header::RETRY_AFTER,
+            HeaderValue::from(decision.reset_after_secs.max(1)),
+        );
+    }
+}
+
+impl<S> Service<Request> for RateLimitMiddleware<S>
+where
+    S: Service<Request, Response = Response, Error = Infallible> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+{
+    type Response = Response;
+    type Error = Infallible;
+    type Future = Pin<Box<dyn Future<Output = Result<Response, Infallible>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request) -> Self::Future {
+        // The clone isn't guaranteed ready; keep the one poll_ready was called on
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+
+        let policy = self
+            .routes
+            .iter()
+            .find(|r| r.matches(request.method(), request.uri().path()))
+            .map(|r| r.policy);
+        let limiter = self.limiter.clone();
+
+        Box::pin(async move {
+            let Some(policy) = policy else {
+                return inner.call(request).await;
+            };
+
+            let (identifier, identifier_type) = rate_limit_identity(&request, policy.key);
+            let decision = match limiter
+                .hit(
+                    &identifier,
+                    identifier_type,
+                    policy.name,
+                    policy.limit,
+                    policy.window_secs,
+                )
+                .await
+            {
+                Ok(decision) => decision,
+                Err(e) => {
+                    // Fail open: an outage of both stores shouldn't take the API down
+                    tracing::error!("Rate limit check for {} failed: {:?}", policy.name, e);
+                    return inner.call(request).await;
+                }
+            };
+
+            let mut response = if decision.allowed {
+                inner.call(request).await?
+            } else {
+                AppError::RateLimited.into_response()
+            };
+            insert_rate_limit_headers(response.headers_mut(), &decision);
+            Ok(response)
+        })
+    }
+}
@@ -190,3 +190,356 @@ impl InviteStatus {
         }
     }
 }
+
+#[derive(Debug, Clone, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum OrgRole {
+    #[sea_orm(string_value = "owner")]
+    Owner,
+    #[sea_orm(string_value = "manager")]
+    Manager,
+    #[sea_orm(string_value = "tournament_admin")]
+    TournamentAdmin,
+    #[sea_orm(string_value = "viewer")]
+    Viewer,
+}
+
+// Actions an organization member may perform on org-owned resources
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrgPermission {
+    View,
+    ManageTeams,
+    ManageTournaments,
+    ManageApiKeys,
+    ManageWebhooks,
+    ManageMembers,
+    TransferOwnership,
+}
+
+impl OrgRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OrgRole::Owner => "owner",
+            OrgRole::Manager => "manager",
+            OrgRole::TournamentAdmin => "tournament_admin",
+            OrgRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn can(&self, permission: OrgPermission) -> bool {
+        match self {
+            OrgRole::Owner => true,
+            OrgRole::Manager => !matches!(permission, OrgPermission::TransferOwnership),
+            OrgRole::TournamentAdmin => matches!(
+                permission,
+                OrgPermission::View | OrgPermission::ManageTournaments
+            ),
+            OrgRole::Viewer => matches!(permission, OrgPermission::View),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum SuspensionKind {
+    #[sea_orm(string_value = "suspension")]
+    Suspension,
+    #[sea_orm(string_value = "ban")]
+    Ban,
+}
+
+impl SuspensionKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SuspensionKind::Suspension => "suspension",
+            SuspensionKind::Ban => "ban",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum OAuthProvider {
+    #[sea_orm(string_value = "discord")]
+    Discord,
+    #[sea_orm(string_value = "google")]
+    Google,
+    #[sea_orm(string_value = "twitch")]
+    Twitch,
+}
+
+impl OAuthProvider {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            OAuthProvider::Discord => "discord",
+            OAuthProvider::Google => "google",
+            OAuthProvider::Twitch => "twitch",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        use strum::IntoEnumIterator;
+        Self::iter().find(|p| p.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+pub enum WebhookEvent {
+    #[sea_orm(string_value = "tournament.status_changed")]
+    #[serde(rename = "tournament.status_changed")]
+    TournamentStatusChanged,
+    #[sea_orm(string_value = "team.registered")]
+    #[serde(rename = "team.registered")]
+    TeamRegistered,
+    #[sea_orm(string_value = "battle.result_verified")]
+    #[serde(rename = "battle.result_verified")]
+    BattleResultVerified,
+    #[sea_orm(string_value = "invite.accepted")]
+    #[serde(rename = "invite.accepted")]
+    InviteAccepted,
+}
+
+impl WebhookEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            WebhookEvent::TournamentStatusChanged => "tournament.status_changed",
+            WebhookEvent::TeamRegistered => "team.registered",
+            WebhookEvent::BattleResultVerified => "battle.result_verified",
+            WebhookEvent::InviteAccepted => "invite.accepted",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        use strum::IntoEnumIterator;
+        Self::iter().find(|e| e.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum WebhookDeliveryStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "succeeded")]
+    Succeeded,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "processed")]
+    Processed,
+    #[sea_orm(string_value = "failed")]
+    Failed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum ChatMemberRole {
+    #[sea_orm(string_value = "owner")]
+    Owner,
+    #[sea_orm(string_value = "admin")]
+    Admin,
+    #[sea_orm(string_value = "member")]
+    Member,
+}
+
+impl ChatMemberRole {
+    /// Owners and admins add, remove and mute members.
+    pub fn can_moderate(&self) -> bool {
+        matches!(self, ChatMemberRole::Owner | ChatMemberRole::Admin)
+    }
+
+    /// Whether a member with this role may moderate a member with `other`.
+    /// Admins only act on plain members; the owner on everyone else.
+    pub fn outranks(&self, other: ChatMemberRole) -> bool {
+        match self {
+            ChatMemberRole::Owner => other != ChatMemberRole::Owner,
+            ChatMemberRole::Admin => other == ChatMemberRole::Member,
+            ChatMemberRole::Member => false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum DmPrivacy {
+    #[sea_orm(string_value = "everyone")]
+    Everyone,
+    #[sea_orm(string_value = "connections")]
+    Connections,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementAudience {
+    #[sea_orm(string_value = "global")]
+    Global,
+    #[sea_orm(string_value = "game")]
+    Game,
+    #[sea_orm(string_value = "tournament")]
+    Tournament,
+    #[sea_orm(string_value = "team")]
+    Team,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum AnnouncementSeverity {
+    #[sea_orm(string_value = "info")]
+    Info,
+    #[sea_orm(string_value = "warning")]
+    Warning,
+    #[sea_orm(string_value = "critical")]
+    Critical,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum CommunityPostType {
+    #[sea_orm(string_value = "text")]
+    Text,
+    #[sea_orm(string_value = "image")]
+    Image,
+    #[sea_orm(string_value = "video")]
+    Video,
+    #[sea_orm(string_value = "poll")]
+    Poll,
+    #[sea_orm(string_value = "event")]
+    Event,
+    #[sea_orm(string_value = "announcement")]
+    Announcement,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum ModeratedContent {
+    #[sea_orm(string_value = "chat_message")]
+    ChatMessage,
+    #[sea_orm(string_value = "community_post")]
+    CommunityPost,
+    #[sea_orm(string_value = "post_comment")]
+    PostComment,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum ModerationStatus {
+    #[sea_orm(string_value = "pending")]
+    Pending,
+    #[sea_orm(string_value = "removed")]
+    Removed,
+    #[sea_orm(string_value = "dismissed")]
+    Dismissed,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, DeriveActiveEnum, Serialize, Deserialize, EnumIter)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[serde(rename_all = "snake_case")]
+pub enum ReportReason {
+    #[sea_orm(string_value = "spam")]
+    Spam,
+    #[sea_orm(string_value = "harassment")]
+    Harassment,
+    #[sea_orm(string_value = "hate_speech")]
+    HateSpeech,
+    #[sea_orm(string_value = "sexual_content")]
+    SexualContent,
+    #[sea_orm(string_value = "other")]
+    Other,
+}
+
+// Capabilities an admin route can require. Granted by role defaults, then
+// overridden per admin through the `admins.permissions` JSON object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, EnumIter)]
+pub enum AdminCapability {
+    #[serde(rename = "users.view")]
+    UsersView,
+    #[serde(rename = "users.suspend")]
+    UsersSuspend,
+    #[serde(rename = "users.ban")]
+    UsersBan,
+    #[serde(rename = "users.verify")]
+    UsersVerify,
+    #[serde(rename = "users.reset_password")]
+    UsersResetPassword,
+    #[serde(rename = "users.revoke_sessions")]
+    UsersRevokeSessions,
+    #[serde(rename = "users.unlock")]
+    UsersUnlock,
+    #[serde(rename = "audit.view")]
+    AuditView,
+    #[serde(rename = "orgs.approve")]
+    OrgsApprove,
+    #[serde(rename = "tournaments.approve")]
+    TournamentsApprove,
+    #[serde(rename = "api_keys.manage")]
+    ApiKeysManage,
+    #[serde(rename = "admins.manage")]
+    AdminsManage,
+    #[serde(rename = "announcements.manage")]
+    AnnouncementsManage,
+    #[serde(rename = "moderation.review")]
+    ModerationReview,
+}
+
+impl AdminCapability {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AdminCapability::UsersView => "users.view",
+            AdminCapability::UsersSuspend => "users.suspend",
+            AdminCapability::UsersBan => "users.ban",
+            AdminCapability::UsersVerify => "users.verify",
+            AdminCapability::UsersResetPassword => "users.reset_password",
+            AdminCapability::UsersRevokeSessions => "users.revoke_sessions",
+            AdminCapability::UsersUnlock => "users.unlock",
+            AdminCapability::AuditView => "audit.view",
+            AdminCapability::OrgsApprove => "orgs.approve",
+            AdminCapability::TournamentsApprove => "tournaments.approve",
+            AdminCapability::ApiKeysManage => "api_keys.manage",
+            AdminCapability::AdminsManage => "admins.manage",
+            AdminCapability::AnnouncementsManage => "announcements.manage",
+            AdminCapability::ModerationReview => "moderation.review",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Self> {
+        use strum::IntoEnumIterator;
+        Self::iter().find(|c| c.as_str() == value)
+    }
+}
+
+impl AdminRole {
+    /// Capabilities granted by the role before any per-admin overrides.
+    pub fn grants_by_default(&self, capability: AdminCapability) -> bool {
+        match self {
+            AdminRole::SuperAdmin => true,
+            AdminRole::Admin => !matches!(capability, AdminCapability::AdminsManage),
+            AdminRole::Moderator => matches!(
+                capability,
+                AdminCapability::UsersView
+                    | AdminCapability::UsersSuspend
+                    | AdminCapability::UsersUnlock
+                    | AdminCapability::AuditView
+                    | AdminCapability::ModerationReview
+            ),
+        }
+    }
+}
@@ -0,0 +1,40 @@
+use crate::models::enums::SuspensionKind;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "account_suspensions")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub user_type: String,
+    pub kind: SuspensionKind,
+    pub reason: String,
+    pub issued_by: Uuid,
+    pub expires_at: Option<ChronoDateTimeUtc>,
+    pub lifted_at: Option<ChronoDateTimeUtc>,
+    pub lifted_by: Option<Uuid>,
+    pub lift_reason: Option<String>,
+    pub created_at: ChronoDateTimeUtc,
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::admin::Entity",
+        from = "Column::IssuedBy",
+        to = "super::admin::Column::Id"
+    )]
+    IssuedBy,
+}
+
+impl Related<super::admin::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::IssuedBy.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
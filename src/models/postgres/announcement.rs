@@ -0,0 +1,53 @@
+use crate::models::enums::{AnnouncementAudience, AnnouncementSeverity, GameType};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "announcements")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub severity: AnnouncementSeverity,
+    pub audience: AnnouncementAudience,
+    pub game: Option<GameType>,
+    pub tournament_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    pub requires_ack: bool,
+    pub author_type: String,
+    pub author_id: Uuid,
+    pub publish_at: ChronoDateTimeUtc,
+    pub expires_at: Option<ChronoDateTimeUtc>,
+    pub published_at: Option<ChronoDateTimeUtc>,
+    pub withdrawn_at: Option<ChronoDateTimeUtc>,
+    pub created_at: ChronoDateTimeUtc,
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::tournament::Entity",
+        from = "Column::TournamentId",
+        to = "super::tournament::Column::Id"
+    )]
+    Tournament,
+    #[sea_orm(
+        belongs_to = "super::team::Entity",
+        from = "Column::TeamId",
+        to = "super::team::Column::Id"
+    )]
+    Team,
+    #[sea_orm(has_many = "super::announcement_acknowledgement::Entity")]
+    Acknowledgements,
+}
+
+impl Related<super::announcement_acknowledgement::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Acknowledgements.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -10,8 +10,7 @@ pub struct Model {
     pub chat_type: String,
     pub name: String,
     pub description: String,
-    pub participants: Json,
-    pub created_by: Uuid,
+    pub created_by: Option<Uuid>,
     pub team_id: Option<Uuid>,
     pub tournament_id: Option<Uuid>,
     pub community_id: Option<Uuid>,
@@ -19,6 +18,9 @@ pub struct Model {
     pub max_participants: i32,
     pub settings: Json,
     pub metadata: Json,
+    pub direct_key: Option<String>,
+    pub battle_id: Option<Uuid>,
+    pub archived_at: Option<ChronoDateTimeUtc>,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
 }
@@ -43,8 +45,16 @@ pub enum Relation {
         to = "super::tournament::Column::Id"
     )]
     Tournament,
+    #[sea_orm(
+        belongs_to = "super::battle::Entity",
+        from = "Column::BattleId",
+        to = "super::battle::Column::Id"
+    )]
+    Battle,
     #[sea_orm(has_many = "super::chat_message::Entity")]
     ChatMessages,
+    #[sea_orm(has_many = "super::chat_member::Entity")]
+    Members,
 }
 
 impl Related<super::player::Entity> for Entity {
@@ -59,4 +69,18 @@ impl Related<super::chat_message::Entity> for Entity {
     }
 }
 
+impl Related<super::chat_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Members.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
+
+impl Model {
+    /// Team chats and tournament and battle lobbies, whose members follow
+    /// the rosters instead of being added by hand.
+    pub fn is_provisioned(&self) -> bool {
+        self.team_id.is_some() || self.tournament_id.is_some() || self.battle_id.is_some()
+    }
+}
@@ -0,0 +1,59 @@
+use crate::models::enums::ChatMemberRole;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "chat_members")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub chat_id: Uuid,
+    pub player_id: Uuid,
+    pub role: ChatMemberRole,
+    pub is_muted: bool,
+    pub muted_until: Option<ChronoDateTimeUtc>,
+    pub joined_at: ChronoDateTimeUtc,
+    pub left_at: Option<ChronoDateTimeUtc>,
+    pub last_read_message_id: Option<Uuid>,
+    pub last_read_at: Option<ChronoDateTimeUtc>,
+    pub created_at: ChronoDateTimeUtc,
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+impl Model {
+    /// Whether the member is currently barred from sending messages.
+    pub fn is_silenced(&self) -> bool {
+        self.is_muted && !matches!(self.muted_until, Some(until) if until <= chrono::Utc::now())
+    }
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::chat::Entity",
+        from = "Column::ChatId",
+        to = "super::chat::Column::Id"
+    )]
+    Chat,
+    #[sea_orm(
+        belongs_to = "super::player::Entity",
+        from = "Column::PlayerId",
+        to = "super::player::Column::Id"
+    )]
+    Player,
+}
+
+impl Related<super::chat::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Chat.def()
+    }
+}
+
+impl Related<super::player::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Player.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
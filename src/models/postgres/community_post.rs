@@ -1,3 +1,4 @@
+use crate::models::enums::CommunityPostType;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
@@ -11,7 +12,7 @@ pub struct Model {
     pub author_id: Uuid,
     pub title: Option<String>,
     pub content: String,
-    pub post_type: String,
+    pub post_type: CommunityPostType,
     pub attachments: Json,
     pub poll_data: Json,
     pub event_data: Json,
@@ -20,6 +21,7 @@ pub struct Model {
     pub downvotes: i32,
     pub comment_count: i32,
     pub view_count: i32,
+    pub hot_score: f64, // maintained by the database from votes and age
     pub pinned: bool,
     pub locked: bool,
     pub deleted_at: Option<ChronoDateTimeUtc>,
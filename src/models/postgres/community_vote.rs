@@ -0,0 +1,40 @@
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "community_votes")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub player_id: Uuid,
+    pub post_id: Option<Uuid>, // exactly one of post_id and comment_id is set
+    pub comment_id: Option<Uuid>,
+    pub value: i16, // 1 up, -1 down
+    pub created_at: ChronoDateTimeUtc,
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::player::Entity",
+        from = "Column::PlayerId",
+        to = "super::player::Column::Id"
+    )]
+    Player,
+    #[sea_orm(
+        belongs_to = "super::community_post::Entity",
+        from = "Column::PostId",
+        to = "super::community_post::Column::Id"
+    )]
+    Post,
+    #[sea_orm(
+        belongs_to = "super::post_comment::Entity",
+        from = "Column::CommentId",
+        to = "super::post_comment::Column::Id"
+    )]
+    Comment,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,42 @@
+use crate::models::enums::{ModeratedContent, ReportReason};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "content_reports")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub content_type: ModeratedContent,
+    pub content_id: Uuid,
+    pub reporter_id: Uuid,
+    pub reason: ReportReason,
+    pub details: Option<String>,
+    pub queue_item_id: Uuid,
+    pub created_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::moderation_queue_item::Entity",
+        from = "Column::QueueItemId",
+        to = "super::moderation_queue_item::Column::Id"
+    )]
+    QueueItem,
+    #[sea_orm(
+        belongs_to = "super::player::Entity",
+        from = "Column::ReporterId",
+        to = "super::player::Column::Id"
+    )]
+    Reporter,
+}
+
+impl Related<super::moderation_queue_item::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QueueItem.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
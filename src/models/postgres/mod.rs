@@ -1,17 +1,35 @@
+pub mod account_lockout;
+pub mod account_suspension;
 pub mod activity_log;
 pub mod admin;
+pub mod announcement;
+pub mod announcement_acknowledgement;
 pub mod api_key;
 pub mod audit_log;
 pub mod battle;
 pub mod chat;
+pub mod chat_attachment;
+pub mod chat_member;
 pub mod chat_message;
+pub mod chat_message_edit;
+pub mod chat_message_reaction;
 pub mod community;
 pub mod community_member;
 pub mod community_post;
+pub mod community_post_view;
+pub mod community_vote;
+pub mod consumed_token;
+pub mod content_report;
+pub mod login_failure;
+pub mod moderation_queue_item;
+pub mod oauth_state;
 pub mod organization;
+pub mod organization_member;
+pub mod outbox_event;
 pub mod player;
 pub mod player_connection;
 pub mod player_game_stats;
+pub mod player_identity;
 pub mod post_comment;
 pub mod rate_limit;
 pub mod recruitment_approach;
@@ -22,22 +40,44 @@ pub mod tournament;
 pub mod tournament_team;
 pub mod tournament_team_invite;
 pub mod transaction;
+pub mod two_factor_recovery_code;
 pub mod user_session;
+pub mod user_two_factor;
+pub mod webhook_delivery;
+pub mod webhook_endpoint;
 
+pub use account_lockout::Entity as AccountLockout;
+pub use account_suspension::Entity as AccountSuspension;
 pub use activity_log::Entity as ActivityLog;
 pub use admin::Entity as Admin;
+pub use announcement::Entity as Announcement;
+pub use announcement_acknowledgement::Entity as AnnouncementAcknowledgement;
 pub use api_key::Entity as ApiKey;
 pub use audit_log::Entity as AuditLog;
 pub use battle::Entity as Battle;
 pub use chat::Entity as Chat;
+pub use chat_attachment::Entity as ChatAttachment;
+pub use chat_member::Entity as ChatMember;
 pub use chat_message::Entity as ChatMessage;
+pub use chat_message_edit::Entity as ChatMessageEdit;
+pub use chat_message_reaction::Entity as ChatMessageReaction;
 pub use community::Entity as Community;
 pub use community_member::Entity as CommunityMember;
 pub use community_post::Entity as CommunityPost;
+pub use community_post_view::Entity as CommunityPostView;
+pub use community_vote::Entity as CommunityVote;
+pub use consumed_token::Entity as ConsumedToken;
+pub use content_report::Entity as ContentReport;
+pub use login_failure::Entity as LoginFailure;
+pub use moderation_queue_item::Entity as ModerationQueueItem;
+pub use oauth_state::Entity as OAuthState;
 pub use organization::Entity as Organization;
+pub use organization_member::Entity as OrganizationMember;
+pub use outbox_event::Entity as OutboxEvent;
 pub use player::Entity as Player;
 pub use player_connection::Entity as PlayerConnection;
 pub use player_game_stats::Entity as PlayerGameStats;
+pub use player_identity::Entity as PlayerIdentity;
 pub use post_comment::Entity as PostComment;
 pub use rate_limit::Entity as RateLimit;
 pub use recruitment_approach::Entity as RecruitmentApproach;
@@ -47,4 +87,8 @@ pub use tournament::Entity as Tournament;
 pub use tournament_team::Entity as TournamentTeam;
 pub use tournament_team_invite::Entity as TournamentTeamInvite;
 pub use transaction::Entity as Transaction;
+pub use two_factor_recovery_code::Entity as TwoFactorRecoveryCode;
 pub use user_session::Entity as UserSession;
+pub use user_two_factor::Entity as UserTwoFactor;
+pub use webhook_delivery::Entity as WebhookDelivery;
+pub use webhook_endpoint::Entity as WebhookEndpoint;
@@ -0,0 +1,43 @@
+use crate::models::enums::{ModeratedContent, ModerationStatus};
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "moderation_queue")]
+pub struct Model {
+    #[sea_orm(primary_key)]
+    pub id: Uuid,
+    pub content_type: ModeratedContent,
+    pub content_id: Uuid,
+    pub author_id: Option<Uuid>,
+    pub excerpt: String,
+    pub reasons: Json,
+    pub report_count: i32,
+    pub status: ModerationStatus,
+    pub reviewed_by: Option<Uuid>,
+    pub reviewed_at: Option<ChronoDateTimeUtc>,
+    pub resolution_note: Option<String>,
+    pub created_at: ChronoDateTimeUtc,
+    pub updated_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::player::Entity",
+        from = "Column::AuthorId",
+        to = "super::player::Column::Id"
+    )]
+    Author,
+    #[sea_orm(has_many = "super::content_report::Entity")]
+    ContentReports,
+}
+
+impl Related<super::content_report::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ContentReports.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,37 @@
+use crate::models::enums::OAuthProvider;
+use sea_orm::entity::prelude::*;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Serialize, Deserialize)]
+#[sea_orm(table_name = "oauth_states")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub state: String,
+    pub provider: OAuthProvider,
+    #[serde(skip_serializing)]
+    pub code_verifier: String,
+    pub nonce: String,
+    pub link_player_id: Option<Uuid>,
+    pub redirect_to: Option<String>,
+    pub expires_at: ChronoDateTimeUtc,
+    pub created_at: ChronoDateTimeUtc,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "super::player::Entity",
+        from = "Column::LinkPlayerId",
+        to = "super::player::Column::Id"
+    )]
+    LinkPlayer,
+}
+
+impl Related<super::player::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::LinkPlayer.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
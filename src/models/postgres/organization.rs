@@ -42,6 +42,8 @@ pub enum Relation {
     Teams,
     #[sea_orm(has_many = "super::tournament_team_invite::Entity")]
     TournamentTeamInvites,
+    #[sea_orm(has_many = "super::organization_member::Entity")]
+    Members,
 }
 
 impl Related<super::team::Entity> for Entity {
@@ -56,4 +58,10 @@ impl Related<super::tournament_team_invite::Entity> for Entity {
     }
 }
 
+impl Related<super::organization_member::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Members.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
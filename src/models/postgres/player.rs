@@ -1,4 +1,4 @@
-use crate::models::enums::GameType;
+use crate::models::enums::{DmPrivacy, GameType};
 use rust_decimal::Decimal;
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
@@ -14,6 +14,7 @@ pub struct Model {
     pub real_name: Option<String>,
     pub email: String,
     pub password: String,
+    pub has_password: bool, // false for accounts created through social login
     pub verified: bool,
     pub country: Option<String>,
     pub bio: String,
@@ -37,6 +38,7 @@ pub struct Model {
     pub youtube: String,
     pub twitter: String,
     pub profile_visibility: String,
+    pub dm_privacy: DmPrivacy,
     pub card_theme: String,
     pub coins: i64,
     pub last_check_in: Option<ChronoDateTimeUtc>,
@@ -58,6 +60,8 @@ pub enum Relation {
     PlayerGameStats,
     #[sea_orm(has_many = "super::transaction::Entity")]
     Transactions,
+    #[sea_orm(has_many = "super::player_identity::Entity")]
+    Identities,
 }
 
 impl Related<super::team::Entity> for Entity {
@@ -77,4 +81,10 @@ impl Related<super::transaction::Entity> for Entity {
         Relation::Transactions.def()
     }
 }
+
+impl Related<super::player_identity::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Identities.def()
+    }
+}
 impl ActiveModelBehavior for ActiveModel {}
@@ -14,6 +14,7 @@ pub struct Model {
     pub upvotes: i32,
     pub downvotes: i32,
     pub reply_count: i32,
+    pub depth: i16,
     pub deleted_at: Option<ChronoDateTimeUtc>,
     pub created_at: ChronoDateTimeUtc,
     pub updated_at: ChronoDateTimeUtc,
@@ -33,6 +34,8 @@ pub enum Relation {
         to = "super::player::Column::Id"
     )]
     Author,
+    #[sea_orm(belongs_to = "Entity", from = "Column::ParentId", to = "Column::Id")]
+    Parent,
 }
 
 impl Related<super::community_post::Entity> for Entity {
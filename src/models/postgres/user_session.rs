@@ -15,6 +15,8 @@ pub struct Model {
     pub user_agent: Option<String>,
     pub device_fingerprint: Option<String>,
     pub expires_at: ChronoDateTimeUtc,
+    pub family_id: Uuid, // shared by every session rotated out of the same login
+    pub family_expires_at: ChronoDateTimeUtc, // absolute cap, not extended by rotation
     pub revoked: bool,
     pub revoked_at: Option<ChronoDateTimeUtc>,
     pub revoked_reason: Option<String>,
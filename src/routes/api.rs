@@ -1,6 +1,8 @@
+use crate::services::chat_service::MAX_ATTACHMENT_BYTES;
 use crate::{handlers, AppState};
 use axum::{
-    routing::{get, post, put},
+    extract::DefaultBodyLimit,
+    routing::{delete, get, post, put},
     Router,
 };
 
@@ -17,16 +19,52 @@ pub fn create_routes() -> Router<AppState> {
             post(handlers::reset_password),
         )
         .route("/auth/verify-email/:token", post(handlers::verify_email))
+        .route("/auth/2fa/verify", post(handlers::verify_two_factor_login))
+        .route("/auth/2fa/setup", post(handlers::setup_two_factor_login))
+        .route(
+            "/auth/2fa/setup/confirm",
+            post(handlers::confirm_two_factor_setup_login),
+        )
+        .route("/auth/magic-link", post(handlers::request_magic_link))
+        .route("/auth/magic-link/verify", post(handlers::verify_magic_link))
+        .route("/auth/oauth/providers", get(handlers::list_oauth_providers))
+        .route(
+            "/auth/oauth/:provider/authorize",
+            get(handlers::oauth_authorize),
+        )
+        .route(
+            "/auth/oauth/:provider/callback",
+            get(handlers::oauth_callback),
+        )
         // ========================================
         // PROTECTED AUTH ENDPOINTS (JWT Required)
         // ========================================
         .route("/auth/logout", post(handlers::auth_logout))
         .route("/auth/refresh", post(handlers::refresh_token))
         .route("/auth/revoke-sessions", post(handlers::revoke_all_sessions))
+        .route("/auth/sessions", get(handlers::list_sessions))
+        .route("/auth/sessions/:id", delete(handlers::revoke_session))
         .route(
             "/auth/send-verification",
             post(handlers::send_verification_email),
         )
+        .route("/auth/2fa/status", get(handlers::get_two_factor_status))
+        .route("/auth/2fa/enroll", post(handlers::enroll_two_factor))
+        .route("/auth/2fa/confirm", post(handlers::confirm_two_factor))
+        .route("/auth/2fa/disable", post(handlers::disable_two_factor))
+        .route(
+            "/auth/2fa/recovery-codes",
+            post(handlers::regenerate_recovery_codes),
+        )
+        .route(
+            "/auth/oauth/:provider/link",
+            post(handlers::start_identity_link),
+        )
+        .route("/auth/identities", get(handlers::list_linked_identities))
+        .route(
+            "/auth/identities/:provider",
+            delete(handlers::unlink_identity),
+        )
         // ========================================
         // PROTECTED PLAYER ENDPOINTS (JWT Required)
         // ========================================
@@ -46,19 +84,91 @@ pub fn create_routes() -> Router<AppState> {
         // PROTECTED SOCIAL ENDPOINTS (JWT Required)
         // ========================================
         .route("/chats", post(handlers::create_chat))
+        .route("/chats/search", get(handlers::search_messages))
         .route("/chats/:chat_id", get(handlers::get_chat))
         .route("/chats/:chat_id/messages", post(handlers::send_message))
         .route("/chats/:chat_id/messages", get(handlers::get_messages))
-        .route("/chats/:chat_id/join/:user_id", post(handlers::join_chat))
+        .route(
+            "/chats/:chat_id/messages/:message_id",
+            put(handlers::edit_message).delete(handlers::delete_message),
+        )
+        .route(
+            "/chats/:chat_id/messages/:message_id/context",
+            get(handlers::get_message_context),
+        )
+        .route(
+            "/chats/:chat_id/messages/:message_id/edits",
+            get(handlers::get_message_edits),
+        )
+        .route(
+            "/chats/:chat_id/messages/:message_id/report",
+            post(handlers::report_chat_message),
+        )
+        .route(
+            "/chats/:chat_id/messages/:message_id/reactions/:emoji",
+            put(handlers::add_reaction).delete(handlers::remove_reaction),
+        )
+        .route("/chats/:chat_id/join", post(handlers::join_chat))
+        .route("/chats/:chat_id/leave", post(handlers::leave_chat))
+        .route("/chats/:chat_id/read", put(handlers::mark_chat_read))
+        .route(
+            "/chats/:chat_id/members",
+            get(handlers::list_chat_members).post(handlers::add_chat_member),
+        )
+        .route(
+            "/chats/:chat_id/members/:player_id",
+            put(handlers::update_chat_member).delete(handlers::remove_chat_member),
+        )
+        .route(
+            "/chats/:chat_id/members/:player_id/mute",
+            put(handlers::mute_chat_member),
+        )
+        .route("/dm/inbox", get(handlers::get_dm_inbox))
+        .route("/dm/groups", post(handlers::create_group_dm))
+        .route("/dm/:player_id", post(handlers::open_direct_message))
+        .route("/announcements", get(handlers::list_announcements))
+        .route(
+            "/announcements/:announcement_id/acknowledge",
+            post(handlers::acknowledge_announcement),
+        )
         .route("/communities", post(handlers::create_community))
+        .route("/communities/feed", get(handlers::get_home_feed))
         .route("/communities/:community_id", get(handlers::get_community))
         .route(
             "/communities/:community_id/posts",
-            post(handlers::add_post_to_community),
+            post(handlers::create_post),
         )
         .route(
             "/communities/:community_id/posts",
-            get(handlers::get_community_posts),
+            get(handlers::get_community_feed),
+        )
+        .route(
+            "/communities/:community_id/posts/:post_id",
+            get(handlers::get_post).delete(handlers::delete_post),
+        )
+        .route(
+            "/communities/:community_id/posts/:post_id/vote",
+            put(handlers::vote_post),
+        )
+        .route(
+            "/communities/:community_id/posts/:post_id/comments",
+            get(handlers::list_post_comments).post(handlers::create_post_comment),
+        )
+        .route(
+            "/communities/:community_id/posts/:post_id/comments/:comment_id",
+            delete(handlers::delete_post_comment),
+        )
+        .route(
+            "/communities/:community_id/posts/:post_id/comments/:comment_id/vote",
+            put(handlers::vote_post_comment),
+        )
+        .route(
+            "/communities/:community_id/posts/:post_id/comments/:comment_id/report",
+            post(handlers::report_post_comment),
+        )
+        .route(
+            "/communities/:community_id/posts/:post_id/report",
+            post(handlers::report_community_post),
         )
         .route(
             "/communities/:community_id/join/:user_id",
@@ -73,6 +183,192 @@ pub fn create_routes() -> Router<AppState> {
             get(handlers::get_community_members),
         )
         // ========================================
+        // PROTECTED ORGANIZATION ENDPOINTS (JWT + Org Role Required)
+        // ========================================
+        .route(
+            "/organizations/memberships",
+            get(handlers::get_my_org_memberships),
+        )
+        .route(
+            "/organizations/invites/:token/accept",
+            post(handlers::accept_org_invite),
+        )
+        .route(
+            "/organizations/:org_id/members",
+            get(handlers::list_org_members).post(handlers::invite_org_member),
+        )
+        .route(
+            "/organizations/:org_id/members/:member_id",
+            put(handlers::update_org_member_role).delete(handlers::remove_org_member),
+        )
+        .route(
+            "/organizations/:org_id/transfer-ownership",
+            post(handlers::transfer_org_ownership),
+        )
+        .route(
+            "/organizations/:org_id/teams",
+            get(handlers::list_org_teams).post(handlers::create_org_team),
+        )
+        .route(
+            "/organizations/:org_id/tournaments",
+            get(handlers::list_org_tournaments).post(handlers::create_org_tournament),
+        )
+        .route(
+            "/organizations/:org_id/tournaments/:tournament_id/teams",
+            post(handlers::register_org_tournament_team),
+        )
+        .route(
+            "/organizations/:org_id/tournaments/:tournament_id/battles/:battle_id/results",
+            post(handlers::verify_org_battle_results),
+        )
+        .route(
+            "/organizations/:org_id/api-keys",
+            get(handlers::list_org_api_keys).post(handlers::create_org_api_key),
+        )
+        .route(
+            "/organizations/:org_id/api-keys/:key_id",
+            delete(handlers::revoke_org_api_key),
+        )
+        .route(
+            "/organizations/:org_id/api-keys/:key_id/rotate",
+            post(handlers::rotate_org_api_key),
+        )
+        .route(
+            "/organizations/:org_id/webhooks",
+            get(handlers::list_org_webhooks).post(handlers::create_org_webhook),
+        )
+        .route(
+            "/organizations/:org_id/webhooks/:endpoint_id",
+            put(handlers::update_org_webhook).delete(handlers::delete_org_webhook),
+        )
+        .route(
+            "/organizations/:org_id/webhooks/:endpoint_id/deliveries",
+            get(handlers::list_org_webhook_deliveries),
+        )
+        .route(
+            "/organizations/:org_id/webhooks/:endpoint_id/deliveries/:delivery_id/replay",
+            post(handlers::replay_org_webhook_delivery),
+        )
+        .route(
+            "/organizations/:org_id/announcements",
+            get(handlers::list_org_announcements).post(handlers::create_org_announcement),
+        )
+        .route(
+            "/organizations/:org_id/announcements/:announcement_id",
+            delete(handlers::withdraw_org_announcement),
+        )
+        .route(
+            "/organizations/:org_id/announcements/:announcement_id/acknowledgements",
+            get(handlers::org_announcement_acknowledgements),
+        )
+        // ========================================
+        // PROTECTED ADMIN CONSOLE ENDPOINTS (JWT + Admin Required)
+        // ========================================
+        .route("/admin/players", get(handlers::admin_search_players))
+        .route(
+            "/admin/announcements",
+            get(handlers::admin_list_announcements).post(handlers::admin_create_announcement),
+        )
+        .route(
+            "/admin/announcements/:announcement_id",
+            delete(handlers::admin_withdraw_announcement),
+        )
+        .route(
+            "/admin/announcements/:announcement_id/acknowledgements",
+            get(handlers::admin_announcement_acknowledgements),
+        )
+        .route(
+            "/admin/moderation/queue",
+            get(handlers::admin_list_moderation_queue),
+        )
+        .route(
+            "/admin/moderation/queue/:item_id",
+            get(handlers::admin_get_moderation_item),
+        )
+        .route(
+            "/admin/moderation/queue/:item_id/resolve",
+            post(handlers::admin_resolve_moderation_item),
+        )
+        .route("/admin/players/:id", get(handlers::admin_get_player))
+        .route(
+            "/admin/organizations",
+            get(handlers::admin_search_organizations),
+        )
+        .route(
+            "/admin/organizations/:id",
+            get(handlers::admin_get_organization),
+        )
+        .route(
+            "/admin/organizations/:id/approve",
+            post(handlers::admin_approve_organization),
+        )
+        .route(
+            "/admin/organizations/:id/reject",
+            post(handlers::admin_reject_organization),
+        )
+        .route(
+            "/admin/tournaments/:id/approve",
+            post(handlers::admin_approve_tournament),
+        )
+        .route(
+            "/admin/tournaments/:id/reject",
+            post(handlers::admin_reject_tournament),
+        )
+        .route("/admin/admins", get(handlers::admin_search_admins))
+        .route("/admin/admins/:id", get(handlers::admin_get_admin))
+        .route(
+            "/admin/admins/:id/permissions",
+            get(handlers::admin_get_permissions).put(handlers::admin_update_permissions),
+        )
+        .route(
+            "/admin/route-permissions/reload",
+            post(handlers::admin_reload_route_permissions),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/suspend",
+            post(handlers::admin_suspend_account),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/unsuspend",
+            post(handlers::admin_lift_suspension),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/suspensions",
+            get(handlers::admin_list_suspensions),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/revoke-sessions",
+            post(handlers::admin_revoke_sessions),
+        )
+        .route(
+            "/admin/api-keys",
+            get(handlers::list_admin_api_keys).post(handlers::create_admin_api_key),
+        )
+        .route(
+            "/admin/api-keys/:key_id",
+            delete(handlers::revoke_admin_api_key),
+        )
+        .route(
+            "/admin/api-keys/:key_id/rotate",
+            post(handlers::rotate_admin_api_key),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/unlock",
+            post(handlers::admin_unlock_account),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/verify-email",
+            post(handlers::admin_force_verify_email),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/reset-password",
+            post(handlers::admin_reset_password),
+        )
+        .route(
+            "/admin/accounts/:user_type/:user_id/audit",
+            get(handlers::admin_get_audit_trail),
+        )
+        // ========================================
         // PROTECTED UPLOAD ENDPOINTS (JWT Required)
         // ========================================
         .route(
@@ -81,7 +377,9 @@ pub fn create_routes() -> Router<AppState> {
         )
         .route(
             "/uploads/chat/:chat_id",
-            post(handlers::upload_chat_attachment),
+            // Room for the multipart framing around a maximum-size file
+            post(handlers::upload_chat_attachment)
+                .layer(DefaultBodyLimit::max(MAX_ATTACHMENT_BYTES + 64 * 1024)),
         )
         .route("/uploads/presigned/:key", get(handlers::get_presigned_url))
         // ========================================
@@ -115,7 +413,7 @@ pub fn create_routes() -> Router<AppState> {
 //         "/chats",                                    // Create chat
 //         "/chats/:chat_id",                           // View chat
 //         "/chats/:chat_id/messages",                  // Chat messages
-//         "/chats/:chat_id/join/:user_id",             // Join chat
+//         "/chats/:chat_id/join",                      // Join chat
 //         "/communities",                              // Create community
 //         "/communities/:community_id",                // View community
 //         "/communities/:community_id/posts",          // Community posts
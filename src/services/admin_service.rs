@@ -1,9 +1,11 @@
-use crate::models::enums::AdminRole;
-use crate::models::postgres::{admin, Admin};
+use crate::models::enums::{AdminCapability, AdminRole};
+use crate::models::postgres::{admin, organization, player, Admin, Organization, Player};
 use crate::services::auth_service::AuthService;
 use crate::utils::errors::AppError;
 use anyhow::Result;
+use sea_orm::sea_query::{extension::postgres::PgExpr, Expr};
 use sea_orm::*;
+use strum::IntoEnumIterator;
 use uuid::Uuid;
 
 #[derive(Clone)]
@@ -102,18 +104,71 @@ impl AdminService {
         Ok(new_admin.insert(&self.db).await?)
     }
 
-    pub async fn has_permission(&self, admin_id: Uuid, permission: &str) -> Result<bool, AppError> {
-        let admin = self.get_by_id(admin_id).await?;
-        match admin {
-            Some(a) => {
-                if let Some(perm_value) = a.permissions.get(permission) {
-                    Ok(perm_value.as_bool().unwrap_or(false))
-                } else {
-                    Ok(false)
-                }
+    /// Resolves a capability from the admin's role defaults, letting explicit
+    /// `true`/`false` entries in `admins.permissions` override them. Super admins
+    /// always hold every capability so they cannot be locked out.
+    pub fn capability_granted(admin: &admin::Model, capability: AdminCapability) -> bool {
+        if !admin.is_active {
+            return false;
+        }
+        if admin.role == AdminRole::SuperAdmin {
+            return true;
+        }
+
+        admin
+            .permissions
+            .get(capability.as_str())
+            .and_then(|v| v.as_bool())
+            .unwrap_or_else(|| admin.role.grants_by_default(capability))
+    }
+
+    pub fn effective_capabilities(admin: &admin::Model) -> Vec<AdminCapability> {
+        AdminCapability::iter()
+            .filter(|c| Self::capability_granted(admin, *c))
+            .collect()
+    }
+
+    pub async fn has_permission(
+        &self,
+        admin_id: Uuid,
+        capability: AdminCapability,
+    ) -> Result<bool, AppError> {
+        Ok(self
+            .get_by_id(admin_id)
+            .await?
+            .map(|a| Self::capability_granted(&a, capability))
+            .unwrap_or(false))
+    }
+
+    /// Replaces an admin's per-capability overrides. Keys must be known
+    /// capabilities and values booleans.
+    pub async fn update_permissions(
+        &self,
+        admin_id: Uuid,
+        overrides: serde_json::Map<String, serde_json::Value>,
+    ) -> Result<admin::Model, AppError> {
+        for (key, value) in &overrides {
+            if AdminCapability::parse(key).is_none() {
+                return Err(AppError::Validation(format!("Unknown capability: {}", key)));
+            }
+            if !value.is_boolean() {
+                return Err(AppError::Validation(format!(
+                    "Capability {} must be true or false",
+                    key
+                )));
             }
-            None => Ok(false),
         }
+
+        let admin = Admin::find_by_id(admin_id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        let mut admin_update: admin::ActiveModel = admin.into();
+        admin_update.permissions = Set(serde_json::Value::Object(overrides));
+        admin_update.updated_at = Set(chrono::Utc::now());
+
+        Ok(admin_update.update(&self.db).await?)
     }
 
     async fn increment_login_attempts(&self, admin_id: Uuid) -> Result<(), AppError> {
@@ -132,6 +187,18 @@ impl AdminService {
         }
         Ok(())
     }
+
+    /// Clears the legacy per-admin lock alongside the shared login lockout.
+    pub async fn clear_login_lock(&self, admin_id: Uuid) -> Result<(), AppError> {
+        if let Some(a) = Admin::find_by_id(admin_id).one(&self.db).await? {
+            let mut admin_update: admin::ActiveModel = a.into();
+            admin_update.login_attempts = Set(0);
+            admin_update.lock_until = Set(None);
+            Admin::update(admin_update).exec(&self.db).await?;
+        }
+        Ok(())
+    }
+
     pub async fn get_by_email(&self, email: String) -> Result<Option<admin::Model>, AppError> {
         Ok(Admin::find()
             .filter(admin::Column::Email.eq(email))
@@ -154,4 +221,82 @@ impl AdminService {
             Ok(false)
         }
     }
+
+    // ========================================
+    // ADMIN CONSOLE SEARCH
+    // ========================================
+
+    pub async fn search_players(
+        &self,
+        query: Option<String>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<player::Model>, AppError> {
+        let mut select = Player::find();
+        if let Some(q) = query.filter(|q| !q.trim().is_empty()) {
+            let pattern = format!("%{}%", q.trim());
+            select = select.filter(
+                Condition::any()
+                    .add(Expr::col(player::Column::Username).ilike(&pattern))
+                    .add(Expr::col(player::Column::Email).ilike(&pattern))
+                    .add(Expr::col(player::Column::InGameName).ilike(&pattern)),
+            );
+        }
+
+        Ok(select
+            .order_by_desc(player::Column::CreatedAt)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn search_organizations(
+        &self,
+        query: Option<String>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<organization::Model>, AppError> {
+        let mut select = Organization::find();
+        if let Some(q) = query.filter(|q| !q.trim().is_empty()) {
+            let pattern = format!("%{}%", q.trim());
+            select = select.filter(
+                Condition::any()
+                    .add(Expr::col(organization::Column::OrgName).ilike(&pattern))
+                    .add(Expr::col(organization::Column::Email).ilike(&pattern))
+                    .add(Expr::col(organization::Column::OwnerName).ilike(&pattern)),
+            );
+        }
+
+        Ok(select
+            .order_by_desc(organization::Column::CreatedAt)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn search_admins(
+        &self,
+        query: Option<String>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<admin::Model>, AppError> {
+        let mut select = Admin::find();
+        if let Some(q) = query.filter(|q| !q.trim().is_empty()) {
+            let pattern = format!("%{}%", q.trim());
+            select = select.filter(
+                Condition::any()
+                    .add(Expr::col(admin::Column::Username).ilike(&pattern))
+                    .add(Expr::col(admin::Column::Email).ilike(&pattern)),
+            );
+        }
+
+        Ok(select
+            .order_by_desc(admin::Column::CreatedAt)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.db)
+            .await?)
+    }
 }
@@ -0,0 +1,458 @@
+use crate::models::enums::{AnnouncementAudience, AnnouncementSeverity, GameType};
+use crate::models::postgres::{
+    announcement, announcement_acknowledgement, Announcement, AnnouncementAcknowledgement, Team,
+    Tournament,
+};
+use crate::services::chat_realtime_service::{ChatRealtimeService, ANNOUNCEMENT};
+use crate::utils::errors::AppError;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+use serde::Serialize;
+use uuid::Uuid;
+
+const MAX_TITLE_CHARS: usize = 200;
+const MAX_BODY_CHARS: usize = 5000;
+
+// Whether player `p` is in the audience of announcement `a`. Teams include
+// their captain; tournaments the players of every registered team.
+pub(crate) const IN_AUDIENCE: &str = r#"(
+    a.audience = 'global'
+    OR (a.audience = 'game' AND p.primary_game = a.game)
+    OR (a.audience = 'team' AND (
+        p.team_id = a.team_id
+        OR EXISTS (SELECT 1 FROM teams t WHERE t.id = a.team_id AND t.captain = p.id)))
+    OR (a.audience = 'tournament' AND EXISTS (
+        SELECT 1 FROM tournament_teams tt JOIN teams t ON t.id = tt.team_id
+        WHERE tt.tournament_id = a.tournament_id AND (p.team_id = t.id OR t.captain = p.id)))
+)"#;
+
+pub(crate) const IS_LIVE: &str = r#"(
+    a.withdrawn_at IS NULL AND a.publish_at <= NOW()
+    AND (a.expires_at IS NULL OR a.expires_at > NOW())
+)"#;
+
+/// Who is posting. Organizations only reach their own tournaments and teams.
+#[derive(Debug, Clone, Copy)]
+pub enum AnnouncementAuthor {
+    Admin(Uuid),
+    Organization(Uuid),
+}
+
+impl AnnouncementAuthor {
+    fn kind(&self) -> &'static str {
+        match self {
+            AnnouncementAuthor::Admin(_) => "admin",
+            AnnouncementAuthor::Organization(_) => "organization",
+        }
+    }
+
+    fn id(&self) -> Uuid {
+        match self {
+            AnnouncementAuthor::Admin(id) | AnnouncementAuthor::Organization(id) => *id,
+        }
+    }
+
+    /// Admins manage every announcement, organizations their own.
+    fn manages(&self, announcement: &announcement::Model) -> bool {
+        match self {
+            AnnouncementAuthor::Admin(_) => true,
+            AnnouncementAuthor::Organization(id) => {
+                announcement.author_type == self.kind() && announcement.author_id == *id
+            }
+        }
+    }
+}
+
+pub struct NewAnnouncement {
+    pub title: String,
+    pub body: String,
+    pub severity: AnnouncementSeverity,
+    pub audience: AnnouncementAudience,
+    pub game: Option<GameType>,
+    pub tournament_id: Option<Uuid>,
+    pub team_id: Option<Uuid>,
+    pub requires_ack: bool,
+    pub publish_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+/// An announcement as its audience sees it.
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct PlayerAnnouncement {
+    pub id: Uuid,
+    pub title: String,
+    pub body: String,
+    pub severity: String,
+    pub audience: String,
+    pub requires_ack: bool,
+    pub publish_at: DateTime<Utc>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub acknowledged_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct AcknowledgementSummary {
+    pub announcement_id: Uuid,
+    pub audience_size: i64,
+    pub acknowledged: i64,
+}
+
+/// Platform announcements: targeted globally, per game, tournament or team,
+/// optionally scheduled and expiring. Each is pushed over the `/chat`
+/// namespace once it goes live and listed in the audience's system channel.
+#[derive(Clone)]
+pub struct AnnouncementService {
+    db: DatabaseConnection,
+    realtime: ChatRealtimeService,
+}
+
+impl AnnouncementService {
+    pub fn new(db: DatabaseConnection, realtime: ChatRealtimeService) -> Self {
+        Self { db, realtime }
+    }
+
+    pub async fn create(
+        &self,
+        author: AnnouncementAuthor,
+        new: NewAnnouncement,
+    ) -> Result<announcement::Model, AppError> {
+        let title = new.title.trim().to_string();
+        let body = new.body.trim().to_string();
+        if title.is_empty() || title.chars().count() > MAX_TITLE_CHARS {
+            return Err(AppError::Validation(format!(
+                "Title must be 1 to {} characters",
+                MAX_TITLE_CHARS
+            )));
+        }
+        if body.is_empty() || body.chars().count() > MAX_BODY_CHARS {
+            return Err(AppError::Validation(format!(
+                "Body must be 1 to {} characters",
+                MAX_BODY_CHARS
+            )));
+        }
+
+        let now = Utc::now();
+        let publish_at = new.publish_at.map_or(now, |at| at.max(now));
+        if new.expires_at.is_some_and(|at| at <= publish_at) {
+            return Err(AppError::Validation(
+                "Expiry must be after the announcement is published".to_string(),
+            ));
+        }
+        self.check_target(author, &new).await?;
+
+        let created = announcement::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            title: Set(title),
+            body: Set(body),
+            severity: Set(new.severity),
+            audience: Set(new.audience),
+            game: Set(new.game),
+            tournament_id: Set(new.tournament_id),
+            team_id: Set(new.team_id),
+            requires_ack: Set(new.requires_ack),
+            author_type: Set(author.kind().to_string()),
+            author_id: Set(author.id()),
+            publish_at: Set(publish_at),
+            expires_at: Set(new.expires_at),
+            published_at: Set(None),
+            withdrawn_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&self.db)
+        .await?;
+
+        // Immediate announcements go out now rather than on the next tick. The
+        // announcement is saved either way, so a failed push is left to the tick.
+        if created.publish_at <= now {
+            if let Err(e) = self.publish_due().await {
+                tracing::warn!("Failed to publish due announcements: {:?}", e);
+            }
+        }
+        Ok(created)
+    }
+
+    /// Checks the audience has exactly its target, that the target exists and
+    /// that the author may reach it.
+    async fn check_target(
+        &self,
+        author: AnnouncementAuthor,
+        new: &NewAnnouncement,
+    ) -> Result<(), AppError> {
+        let targets = (
+            new.game.is_some(),
+            new.tournament_id.is_some(),
+            new.team_id.is_some(),
+        );
+        let expected = match new.audience {
+            AnnouncementAudience::Global => (false, false, false),
+            AnnouncementAudience::Game => (true, false, false),
+            AnnouncementAudience::Tournament => (false, true, false),
+            AnnouncementAudience::Team => (false, false, true),
+        };
+        if targets != expected {
+            return Err(AppError::Validation(
+                "Set only the game, tournament_id or team_id the audience targets".to_string(),
+            ));
+        }
+
+        let owner_org = if let Some(tournament_id) = new.tournament_id {
+            Tournament::find_by_id(tournament_id)
+                .one(&self.db)
+                .await?
+                .ok_or(AppError::NotFound)?
+                .submitted_by
+        } else if let Some(team_id) = new.team_id {
+            Team::find_by_id(team_id)
+                .one(&self.db)
+                .await?
+                .ok_or(AppError::NotFound)?
+                .organization_id
+        } else {
+            None
+        };
+
+        match author {
+            AnnouncementAuthor::Admin(_) => Ok(()),
+            AnnouncementAuthor::Organization(org_id) if owner_org == Some(org_id) => Ok(()),
+            AnnouncementAuthor::Organization(_) => Err(AppError::Forbidden),
+        }
+    }
+
+    /// Pushes announcements whose publish time has come. Claiming and marking
+    /// them is one statement, so each goes out once across nodes.
+    pub async fn publish_due(&self) -> Result<usize, AppError> {
+        let due = Announcement::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"UPDATE announcements a SET published_at = NOW()
+                       WHERE a.published_at IS NULL AND {IS_LIVE}
+                       RETURNING *"#
+                ),
+                [],
+            ))
+            .all(&self.db)
+            .await?;
+
+        // One failed push shouldn't hold back the rest; they are already claimed
+        for announcement in &due {
+            if let Err(e) = self.push(announcement).await {
+                tracing::warn!("Failed to push announcement {}: {:?}", announcement.id, e);
+            }
+        }
+        Ok(due.len())
+    }
+
+    async fn push(&self, announcement: &announcement::Model) -> Result<(), AppError> {
+        let data = serde_json::json!({
+            "id": announcement.id,
+            "title": announcement.title,
+            "body": announcement.body,
+            "severity": announcement.severity,
+            "audience": announcement.audience,
+            "requires_ack": announcement.requires_ack,
+            "publish_at": announcement.publish_at,
+            "expires_at": announcement.expires_at,
+        });
+
+        if announcement.audience == AnnouncementAudience::Global {
+            self.realtime.notify_all(ANNOUNCEMENT, data).await;
+        } else {
+            let players = self.audience(announcement.id).await?;
+            self.realtime
+                .notify_players(&players, ANNOUNCEMENT, data)
+                .await;
+        }
+        Ok(())
+    }
+
+    async fn audience(&self, announcement_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let rows = self
+            .db
+            .query_all(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"SELECT p.id FROM announcements a JOIN players p ON {IN_AUDIENCE}
+                       WHERE a.id = $1"#
+                ),
+                [announcement_id.into()],
+            ))
+            .await?;
+        let mut players = Vec::with_capacity(rows.len());
+        for row in rows {
+            players.push(row.try_get::<Uuid>("", "id")?);
+        }
+        Ok(players)
+    }
+
+    /// Live announcements for the player, newest first. `pending` narrows them
+    /// to mandatory notices the player hasn't acknowledged.
+    pub async fn list_for_player(
+        &self,
+        player_id: Uuid,
+        pending: bool,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<PlayerAnnouncement>, AppError> {
+        Ok(
+            PlayerAnnouncement::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"SELECT a.id, a.title, a.body, a.severity, a.audience, a.requires_ack,
+                          a.publish_at, a.expires_at, ack.acknowledged_at
+                   FROM announcements a
+                   JOIN players p ON p.id = $1
+                   LEFT JOIN announcement_acknowledgements ack
+                     ON ack.announcement_id = a.id AND ack.player_id = p.id
+                   WHERE {IS_LIVE} AND {IN_AUDIENCE}
+                     AND (NOT $2 OR (a.requires_ack AND ack.id IS NULL))
+                   ORDER BY a.publish_at DESC
+                   LIMIT $3 OFFSET $4"#
+                ),
+                [
+                    player_id.into(),
+                    pending.into(),
+                    (limit as i64).into(),
+                    (offset as i64).into(),
+                ],
+            ))
+            .all(&self.db)
+            .await?,
+        )
+    }
+
+    /// Records that the player has read a live announcement addressed to them.
+    /// Acknowledging again keeps the first time.
+    pub async fn acknowledge(
+        &self,
+        player_id: Uuid,
+        announcement_id: Uuid,
+    ) -> Result<announcement_acknowledgement::Model, AppError> {
+        let visible = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"SELECT 1 AS visible FROM announcements a JOIN players p ON p.id = $2
+                       WHERE a.id = $1 AND {IS_LIVE} AND {IN_AUDIENCE}"#
+                ),
+                [announcement_id.into(), player_id.into()],
+            ))
+            .await?;
+        if visible.is_none() {
+            return Err(AppError::NotFound);
+        }
+
+        AnnouncementAcknowledgement::insert(announcement_acknowledgement::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            announcement_id: Set(announcement_id),
+            player_id: Set(player_id),
+            acknowledged_at: Set(Utc::now()),
+        })
+        .on_conflict(
+            OnConflict::columns([
+                announcement_acknowledgement::Column::AnnouncementId,
+                announcement_acknowledgement::Column::PlayerId,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .exec_without_returning(&self.db)
+        .await?;
+
+        AnnouncementAcknowledgement::find()
+            .filter(announcement_acknowledgement::Column::AnnouncementId.eq(announcement_id))
+            .filter(announcement_acknowledgement::Column::PlayerId.eq(player_id))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Announcements the author manages, including scheduled, expired and
+    /// withdrawn ones, newest first.
+    pub async fn list_for_author(
+        &self,
+        author: AnnouncementAuthor,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<announcement::Model>, AppError> {
+        let mut query = Announcement::find();
+        if let AnnouncementAuthor::Organization(org_id) = author {
+            query = query
+                .filter(announcement::Column::AuthorType.eq(author.kind()))
+                .filter(announcement::Column::AuthorId.eq(org_id));
+        }
+        Ok(query
+            .order_by_desc(announcement::Column::CreatedAt)
+            .limit(limit)
+            .offset(offset)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn get_for_author(
+        &self,
+        author: AnnouncementAuthor,
+        announcement_id: Uuid,
+    ) -> Result<announcement::Model, AppError> {
+        Announcement::find_by_id(announcement_id)
+            .one(&self.db)
+            .await?
+            .filter(|a| author.manages(a))
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Takes an announcement down; withdrawn ones stay listed for the author.
+    pub async fn withdraw(
+        &self,
+        author: AnnouncementAuthor,
+        announcement_id: Uuid,
+    ) -> Result<announcement::Model, AppError> {
+        let existing = self.get_for_author(author, announcement_id).await?;
+        if existing.withdrawn_at.is_some() {
+            return Ok(existing);
+        }
+
+        let now = Utc::now();
+        let mut update: announcement::ActiveModel = existing.into();
+        update.withdrawn_at = Set(Some(now));
+        update.updated_at = Set(now);
+        Ok(update.update(&self.db).await?)
+    }
+
+    /// How many of the current audience have acknowledged the announcement.
+    pub async fn acknowledgement_summary(
+        &self,
+        author: AnnouncementAuthor,
+        announcement_id: Uuid,
+    ) -> Result<AcknowledgementSummary, AppError> {
+        self.get_for_author(author, announcement_id).await?;
+
+        Ok(
+            AcknowledgementSummary::find_by_statement(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"SELECT a.id AS announcement_id,
+                          COUNT(p.id) AS audience_size,
+                          COUNT(ack.id) AS acknowledged
+                   FROM announcements a
+                   JOIN players p ON {IN_AUDIENCE}
+                   LEFT JOIN announcement_acknowledgements ack
+                     ON ack.announcement_id = a.id AND ack.player_id = p.id
+                   WHERE a.id = $1
+                   GROUP BY a.id"#
+                ),
+                [announcement_id.into()],
+            ))
+            .one(&self.db)
+            .await?
+            .unwrap_or(AcknowledgementSummary {
+                announcement_id,
+                audience_size: 0,
+                acknowledged: 0,
+            }),
+        )
+    }
+}
@@ -1,19 +1,166 @@
+use crate::config::ApiKeySettings;
 use crate::models::postgres::{api_key, ApiKey};
 use crate::services::auth_service::AuthService;
 use crate::utils::errors::AppError;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use hmac::{Hmac, Mac};
 use sea_orm::{sea_query::Expr, *};
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
+// Marks key_hash values holding an HMAC digest; anything else is a legacy Argon2 hash
+const HMAC_HASH_PREFIX: &str = "hmac-sha256:";
+// Past this size, expired entries are swept out of the cache on insert
+const CACHE_SWEEP_SIZE: usize = 10_000;
+
+/// Scopes a key can be granted; `*` grants all of them. Routes name the scope
+/// they need in the permission table (`api_scope`).
+pub const API_KEY_SCOPES: [&str; 5] = [
+    "members:read",
+    "teams:read",
+    "teams:write",
+    "tournaments:read",
+    "tournaments:write",
+];
+
+struct CachedKey {
+    key: api_key::Model,
+    cached_at: Instant,
+}
+
 #[derive(Clone)]
 pub struct ApiKeyService {
     db: DatabaseConnection,
-    auth_service: AuthService,
+    auth_service: AuthService, // only for verifying legacy Argon2 hashes
+    hmac_secret: Arc<Vec<u8>>,
+    cache_ttl: Duration,
+    // Verified keys by digest of the full key. Other nodes only see a revoke or
+    // rotation once their entry expires, so the TTL is kept short
+    cache: Arc<RwLock<HashMap<String, CachedKey>>>,
+    pending_last_used: Arc<Mutex<HashMap<Uuid, DateTime<Utc>>>>,
 }
 
 impl ApiKeyService {
-    pub fn new(db: DatabaseConnection, auth_service: AuthService) -> Self {
-        Self { db, auth_service }
+    pub fn new(
+        db: DatabaseConnection,
+        auth_service: AuthService,
+        settings: ApiKeySettings,
+    ) -> Self {
+        Self {
+            db,
+            auth_service,
+            hmac_secret: Arc::new(settings.hmac_secret.into_bytes()),
+            cache_ttl: Duration::from_secs(settings.cache_ttl_secs),
+            cache: Arc::new(RwLock::new(HashMap::new())),
+            pending_last_used: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn mac(&self, full_key: &str) -> Hmac<Sha256> {
+        let mut mac = Hmac::<Sha256>::new_from_slice(&self.hmac_secret)
+            .expect("HMAC accepts keys of any length");
+        mac.update(full_key.as_bytes());
+        mac
+    }
+
+    fn digest(&self, full_key: &str) -> String {
+        hex::encode(self.mac(full_key).finalize().into_bytes())
+    }
+
+    fn hash_key(&self, full_key: &str) -> String {
+        format!("{}{}", HMAC_HASH_PREFIX, self.digest(full_key))
+    }
+
+    /// Constant-time check of a key against a stored HMAC digest.
+    fn digest_matches(&self, full_key: &str, stored_hex: &str) -> bool {
+        match hex::decode(stored_hex) {
+            Ok(stored) => self.mac(full_key).verify_slice(&stored).is_ok(),
+            Err(_) => false,
+        }
+    }
+
+    fn cached(&self, digest: &str) -> Option<api_key::Model> {
+        let cache = self.cache.read().unwrap();
+        cache
+            .get(digest)
+            .filter(|entry| entry.cached_at.elapsed() < self.cache_ttl)
+            .map(|entry| entry.key.clone())
+    }
+
+    fn cache_key(&self, digest: String, key: api_key::Model) {
+        let mut cache = self.cache.write().unwrap();
+        if cache.len() >= CACHE_SWEEP_SIZE {
+            let ttl = self.cache_ttl;
+            cache.retain(|_, entry| entry.cached_at.elapsed() < ttl);
+        }
+        cache.insert(
+            digest,
+            CachedKey {
+                key,
+                cached_at: Instant::now(),
+            },
+        );
+    }
+
+    fn evict(&self, key_id: &str) {
+        self.cache
+            .write()
+            .unwrap()
+            .retain(|_, entry| entry.key.key_id != key_id);
+    }
+
+    fn mark_used(&self, key_id: Uuid) {
+        self.pending_last_used
+            .lock()
+            .unwrap()
+            .insert(key_id, Utc::now());
+    }
+
+    /// Writes the `last_used_at` values collected since the last flush. On a
+    /// database error the unwritten values go back for the next flush, unless
+    /// the key was used again in the meantime.
+    pub async fn flush_last_used(&self) -> Result<usize, AppError> {
+        let pending = std::mem::take(&mut *self.pending_last_used.lock().unwrap());
+        let total = pending.len();
+
+        let mut remaining = pending.into_iter();
+        while let Some((id, used_at)) = remaining.next() {
+            let written = ApiKey::update_many()
+                .col_expr(api_key::Column::LastUsedAt, Expr::value(used_at))
+                .filter(api_key::Column::Id.eq(id))
+                .exec(&self.db)
+                .await;
+            if let Err(e) = written {
+                let mut requeue = self.pending_last_used.lock().unwrap();
+                for (id, used_at) in std::iter::once((id, used_at)).chain(remaining) {
+                    requeue.entry(id).or_insert(used_at);
+                }
+                return Err(e.into());
+            }
+        }
+        Ok(total)
+    }
+
+    fn generate_secret() -> String {
+        Uuid::new_v4().to_string().replace("-", "")
+    }
+
+    pub fn validate_scopes(scopes: &[String]) -> Result<(), AppError> {
+        if scopes.is_empty() {
+            return Err(AppError::Validation(
+                "An API key needs at least one scope".to_string(),
+            ));
+        }
+        if let Some(unknown) = scopes
+            .iter()
+            .find(|s| *s != "*" && !API_KEY_SCOPES.contains(&s.as_str()))
+        {
+            return Err(AppError::Validation(format!("Unknown scope: {}", unknown)));
+        }
+        Ok(())
     }
 
     pub async fn create_api_key(
@@ -25,15 +172,21 @@ impl ApiKeyService {
         rate_limit_per_hour: Option<i32>,
         expires_at: Option<chrono::DateTime<Utc>>,
     ) -> Result<(api_key::Model, String), AppError> {
+        Self::validate_scopes(&scopes)?;
+        if rate_limit_per_hour.is_some_and(|limit| limit < 1) {
+            return Err(AppError::Validation(
+                "rate_limit_per_hour must be positive".to_string(),
+            ));
+        }
+
         let key_id = format!(
             "ak_{}",
             Uuid::new_v4().to_string().replace("-", "")[..16].to_lowercase()
         );
-        let secret_key = Uuid::new_v4().to_string().replace("-", "");
+        let secret_key = Self::generate_secret();
         let full_key = format!("{}_{}", key_id, secret_key);
 
-        // ✅ ENTERPRISE: Use AuthService for consistent hashing
-        let key_hash = self.auth_service.hash_password(&secret_key)?;
+        let key_hash = self.hash_key(&full_key);
 
         let new_key = api_key::ActiveModel {
             id: Set(Uuid::new_v4()),
@@ -55,10 +208,25 @@ impl ApiKeyService {
         Ok((api_key_model, full_key))
     }
 
+    /// Resolves a full `ak_<id>_<secret>` key to its record. Cached keys skip
+    /// the database; keys still on a legacy Argon2 hash are moved to HMAC on
+    /// their first successful use.
     pub async fn validate_api_key(
         &self,
         full_key: &str,
     ) -> Result<Option<api_key::Model>, AppError> {
+        let digest = self.digest(full_key);
+        if let Some(key) = self.cached(&digest) {
+            if key
+                .expires_at
+                .is_some_and(|expires_at| expires_at < Utc::now())
+            {
+                return Ok(None);
+            }
+            self.mark_used(key.id);
+            return Ok(Some(key));
+        }
+
         let parts: Vec<&str> = full_key.split('_').collect();
         if parts.len() != 3 || parts[0] != "ak" {
             return Ok(None);
@@ -67,44 +235,92 @@ impl ApiKeyService {
         let key_id = format!("{}_{}", parts[0], parts[1]);
         let secret = parts[2];
 
-        let api_key = ApiKey::find()
+        let Some(key) = ApiKey::find()
             .filter(api_key::Column::KeyId.eq(key_id))
             .filter(api_key::Column::IsActive.eq(true))
             .one(&self.db)
-            .await?;
+            .await?
+        else {
+            return Ok(None);
+        };
+
+        // Check expiration
+        if key
+            .expires_at
+            .is_some_and(|expires_at| expires_at < Utc::now())
+        {
+            return Ok(None);
+        }
 
-        if let Some(key) = api_key {
-            // Check expiration
-            if let Some(expires_at) = key.expires_at {
-                if expires_at < Utc::now() {
+        let key = match key.key_hash.strip_prefix(HMAC_HASH_PREFIX) {
+            Some(stored) => {
+                if !self.digest_matches(full_key, stored) {
                     return Ok(None);
                 }
+                key
             }
-
-            // ✅ ENTERPRISE: Use AuthService for consistent verification
-            if self.auth_service.verify_password(secret, &key.key_hash)? {
-                // Update last used
-                let mut update: api_key::ActiveModel = key.clone().into();
-                update.last_used_at = Set(Some(Utc::now()));
-                ApiKey::update(update).exec(&self.db).await?;
-
-                return Ok(Some(key));
+            None => {
+                if !self.auth_service.verify_password(secret, &key.key_hash)? {
+                    return Ok(None);
+                }
+                let mut update: api_key::ActiveModel = key.into();
+                update.key_hash = Set(self.hash_key(full_key));
+                update.updated_at = Set(Utc::now());
+                update.update(&self.db).await?
             }
-        }
+        };
 
-        Ok(None)
+        self.cache_key(digest, key.clone());
+        self.mark_used(key.id);
+        Ok(Some(key))
     }
 
+    /// Deactivates the key. The cache is cleared after the write, so a lookup
+    /// racing it can't cache the key again.
     pub async fn revoke_api_key(&self, key_id: String) -> Result<(), AppError> {
         ApiKey::update_many()
             .col_expr(api_key::Column::IsActive, Expr::value(false))
             .col_expr(api_key::Column::UpdatedAt, Expr::value(Utc::now()))
-            .filter(api_key::Column::KeyId.eq(key_id))
+            .filter(api_key::Column::KeyId.eq(&key_id))
             .exec(&self.db)
             .await?;
+        self.evict(&key_id);
         Ok(())
     }
 
+    /// Replaces the key's secret; the old one stops working immediately. The
+    /// key keeps its id, scopes and limits.
+    pub async fn rotate_api_key(
+        &self,
+        key: api_key::Model,
+    ) -> Result<(api_key::Model, String), AppError> {
+        let secret_key = Self::generate_secret();
+        let full_key = format!("{}_{}", key.key_id, secret_key);
+
+        let mut update: api_key::ActiveModel = key.into();
+        update.key_hash = Set(self.hash_key(&full_key));
+        update.updated_at = Set(Utc::now());
+        let key = update.update(&self.db).await?;
+
+        self.evict(&key.key_id);
+        Ok((key, full_key))
+    }
+
+    pub async fn get_owner_key(
+        &self,
+        owner_id: Uuid,
+        owner_type: &str,
+        key_id: &str,
+    ) -> Result<Option<api_key::Model>, AppError> {
+        Ok(ApiKey::find()
+            .filter(api_key::Column::OwnerId.eq(owner_id))
+            .filter(api_key::Column::OwnerType.eq(owner_type))
+            .filter(api_key::Column::KeyId.eq(key_id))
+            .filter(api_key::Column::IsActive.eq(true))
+            .one(&self.db)
+            .await?)
+    }
+
     pub async fn list_owner_keys(
         &self,
         owner_id: Uuid,
@@ -30,7 +30,11 @@ pub enum UserType {
 pub struct TempTokenClaims {
     pub sub: String,
     pub user_type: String,
-    pub token_type: String, // "reset_password" or "verify_email"
+    pub token_type: String, // "reset_password", "verify_email", "2fa_pending", "magic_login", ...
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub jti: Option<String>, // only on single-use tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub fingerprint: Option<String>, // only on tokens bound to the requesting client
     pub exp: usize,
     pub iat: usize,
 }
@@ -73,6 +77,20 @@ impl AuthService {
         Ok(password_hash.to_string())
     }
 
+    // Hash of a random secret, for accounts that sign in without a password
+    pub fn hash_unusable_password(&self) -> Result<String, AppError> {
+        let mut secret = [0u8; 32];
+        rand::RngCore::fill_bytes(&mut rand::rngs::OsRng, &mut secret);
+
+        let salt = SaltString::generate(&mut OsRng);
+        let password_hash = self
+            .argon2
+            .hash_password(hex::encode(secret).as_bytes(), &salt)
+            .map_err(|_| AppError::InternalServerError)?;
+
+        Ok(password_hash.to_string())
+    }
+
     pub fn verify_password(&self, password: &str, hash: &str) -> Result<bool, AppError> {
         let parsed_hash = PasswordHash::new(hash).map_err(|_| AppError::InternalServerError)?;
         Ok(self
@@ -149,26 +167,83 @@ impl AuthService {
         user_type: UserType,
         token_type: &str,
         expiry_hours: i64,
+    ) -> Result<String, AppError> {
+        self.generate_temp_token_with_ttl(
+            user_id,
+            user_type,
+            token_type,
+            Duration::hours(expiry_hours),
+        )
+    }
+
+    // Same as generate_temp_token, for tokens that should live minutes rather than hours
+    pub fn generate_temp_token_with_ttl(
+        &self,
+        user_id: Uuid,
+        user_type: UserType,
+        token_type: &str,
+        ttl: Duration,
     ) -> Result<String, AppError> {
         let now = Utc::now();
-        let exp = (now + Duration::hours(expiry_hours)).timestamp() as usize;
+        let claims = TempTokenClaims {
+            sub: user_id.to_string(),
+            user_type: user_type.as_str().to_string(),
+            token_type: token_type.to_string(),
+            jti: None,
+            fingerprint: None,
+            exp: (now + ttl).timestamp() as usize,
+            iat: now.timestamp() as usize,
+        };
+
+        self.encode_temp_token(&claims)
+    }
 
+    // Single-use token tied to the client that asked for it. Returns the token
+    // and its jti, which the caller records when the token is redeemed.
+    pub fn generate_bound_temp_token(
+        &self,
+        user_id: Uuid,
+        user_type: UserType,
+        token_type: &str,
+        ttl: Duration,
+        fingerprint: String,
+    ) -> Result<(String, Uuid), AppError> {
+        let now = Utc::now();
+        let jti = Uuid::new_v4();
         let claims = TempTokenClaims {
             sub: user_id.to_string(),
             user_type: user_type.as_str().to_string(),
             token_type: token_type.to_string(),
-            exp,
+            jti: Some(jti.to_string()),
+            fingerprint: Some(fingerprint),
+            exp: (now + ttl).timestamp() as usize,
             iat: now.timestamp() as usize,
         };
 
+        Ok((self.encode_temp_token(&claims)?, jti))
+    }
+
+    fn encode_temp_token(&self, claims: &TempTokenClaims) -> Result<String, AppError> {
         encode(
             &Header::default(),
-            &claims,
+            claims,
             &EncodingKey::from_secret(self.jwt_secret.as_ref()),
         )
         .map_err(|_| AppError::InternalServerError)
     }
 
+    // Hash of the client's IP and user agent, so bound tokens don't carry them in the clear
+    pub fn client_fingerprint(ip_address: Option<&str>, user_agent: Option<&str>) -> String {
+        use sha2::{Digest, Sha256};
+
+        let input = format!(
+            "{}|{}",
+            ip_address.unwrap_or_default(),
+            user_agent.unwrap_or_default()
+        );
+        hex::encode(Sha256::digest(input.as_bytes()))
+    }
+
     // Verify temporary token
     pub fn verify_temp_token(&self, token: &str) -> Result<TempTokenClaims, AppError> {
         let mut validation = Validation::default();
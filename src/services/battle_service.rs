@@ -1,8 +1,23 @@
-use crate::models::postgres::{battle, Battle};
+use crate::models::enums::BattleStatus;
+use crate::models::postgres::{battle, tournament_team, Battle, TournamentTeam};
+use crate::services::outbox_service::{DomainEvent, OutboxService};
 use crate::utils::errors::AppError;
+use chrono::Utc;
 use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use uuid::Uuid;
 
+/// One team's line in a battle's verified results, stored under
+/// `battle_stats.results`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BattleResult {
+    pub team_id: Uuid,
+    pub placement: i32,
+    pub kills: i32,
+    pub points: i32,
+}
+
 #[derive(Clone)]
 pub struct BattleService {
     db: DatabaseConnection,
@@ -27,4 +42,74 @@ impl BattleService {
     pub async fn get_by_id(&self, id: Uuid) -> Result<Option<battle::Model>, AppError> {
         Ok(Battle::find_by_id(id).one(&self.db).await?)
     }
+
+    /// Stores the organizer's verified results and completes the battle.
+    /// Verifying again replaces the earlier results.
+    pub async fn verify_results(
+        &self,
+        battle: battle::Model,
+        results: Vec<BattleResult>,
+    ) -> Result<battle::Model, AppError> {
+        if battle.status == BattleStatus::Cancelled {
+            return Err(AppError::Validation(
+                "Results can't be verified for a cancelled battle".to_string(),
+            ));
+        }
+        if results.is_empty() {
+            return Err(AppError::Validation(
+                "Results must include at least one team".to_string(),
+            ));
+        }
+        if results
+            .iter()
+            .any(|r| r.placement < 1 || r.kills < 0 || r.points < 0)
+        {
+            return Err(AppError::Validation(
+                "Placements start at 1 and kills and points can't be negative".to_string(),
+            ));
+        }
+        let team_ids: HashSet<Uuid> = results.iter().map(|r| r.team_id).collect();
+        if team_ids.len() != results.len() {
+            return Err(AppError::Validation(
+                "Each team can only appear once in the results".to_string(),
+            ));
+        }
+
+        let registered = TournamentTeam::find()
+            .filter(tournament_team::Column::TournamentId.eq(battle.tournament))
+            .filter(tournament_team::Column::TeamId.is_in(team_ids.iter().copied()))
+            .count(&self.db)
+            .await?;
+        if registered as usize != team_ids.len() {
+            return Err(AppError::Validation(
+                "Every team in the results must be registered for the tournament".to_string(),
+            ));
+        }
+
+        let mut stats = match battle.battle_stats.clone() {
+            serde_json::Value::Object(stats) => stats,
+            _ => serde_json::Map::new(),
+        };
+        stats.insert("results".to_string(), serde_json::to_value(&results)?);
+        stats.insert("verified_at".to_string(), serde_json::json!(Utc::now()));
+
+        let txn = self.db.begin().await?;
+        let tournament_id = battle.tournament;
+        let mut update: battle::ActiveModel = battle.into();
+        update.battle_stats = Set(serde_json::Value::Object(stats));
+        update.status = Set(BattleStatus::Completed);
+        update.updated_at = Set(Utc::now());
+        let battle = update.update(&txn).await?;
+        OutboxService::record(
+            &txn,
+            &DomainEvent::ResultVerified {
+                battle_id: battle.id,
+                tournament_id,
+            },
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(battle)
+    }
 }
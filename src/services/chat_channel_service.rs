@@ -0,0 +1,294 @@
+use crate::models::postgres::{chat, Chat};
+use crate::services::chat_realtime_service::ChatRealtimeService;
+use crate::utils::errors::AppError;
+use sea_orm::*;
+use uuid::Uuid;
+
+pub const TEAM_CHAT: &str = "team";
+pub const TOURNAMENT_CHAT: &str = "tournament";
+pub const BATTLE_CHAT: &str = "battle";
+
+// Battle lobbies open this long before the scheduled start
+const BATTLE_LOBBY_LEAD_HOURS: i32 = 24;
+
+// Who belongs in each provisioned chat: a team's players and captain, who
+// owns the team chat. Lobbies take the players of the registered (tournament)
+// or participating (battle) teams. `$1` narrows it to one chat.
+const ROSTER: &str = r#"roster AS (
+    SELECT DISTINCT ON (c.id, tp.player_id)
+           c.id AS chat_id, tp.player_id,
+           CASE WHEN c.chat_type = 'team' AND tp.player_id = t.captain
+                THEN 'owner' ELSE 'member' END AS role
+    FROM chats c
+    JOIN teams t
+      ON (c.chat_type = 'team' AND t.id = c.team_id)
+      OR (c.chat_type = 'tournament' AND t.id IN (
+              SELECT tt.team_id FROM tournament_teams tt WHERE tt.tournament_id = c.tournament_id))
+      OR (c.chat_type = 'battle' AND EXISTS (
+              SELECT 1 FROM battles b
+              WHERE b.id = c.battle_id AND b.participating_teams @> jsonb_build_array(t.id::text)))
+    JOIN LATERAL (
+        SELECT p.id AS player_id FROM players p WHERE p.team_id = t.id
+        UNION
+        SELECT t.captain WHERE t.captain IS NOT NULL
+    ) tp ON TRUE
+    WHERE c.archived_at IS NULL AND ($1::uuid IS NULL OR c.id = $1)
+    ORDER BY c.id, tp.player_id
+)"#;
+
+/// A membership a roster sync ended. Its sockets are evicted from the chat's
+/// room once the sync is committed.
+#[derive(Debug, Clone, Copy, FromQueryResult)]
+pub struct EndedMembership {
+    pub chat_id: Uuid,
+    pub player_id: Uuid,
+}
+
+/// Chats the server provisions and keeps in step with rosters: one per team,
+/// a lobby per tournament and one per battle, archived once the battle is over.
+/// Every step is idempotent; `reconcile` runs them all periodically to catch
+/// roster changes made anywhere.
+#[derive(Clone)]
+pub struct ChatChannelService {
+    db: DatabaseConnection,
+    realtime: ChatRealtimeService,
+}
+
+impl ChatChannelService {
+    pub fn new(db: DatabaseConnection, realtime: ChatRealtimeService) -> Self {
+        Self { db, realtime }
+    }
+
+    /// Stops ended members' sockets receiving the chat's events. Call it after
+    /// the transaction that ended them commits.
+    pub async fn evict(&self, ended: Vec<EndedMembership>) {
+        for membership in ended {
+            self.realtime
+                .evict(membership.chat_id, membership.player_id)
+                .await;
+        }
+    }
+
+    /// Creates the team's chat if it is missing and syncs its members,
+    /// returning the memberships that ended.
+    pub async fn provision_team_chat<C: ConnectionTrait>(
+        conn: &C,
+        team_id: Uuid,
+    ) -> Result<Vec<EndedMembership>, AppError> {
+        Self::create_team_chats(conn, Some(team_id)).await?;
+        Self::sync_channel(conn, chat::Column::TeamId, TEAM_CHAT, team_id).await
+    }
+
+    /// Creates the tournament's lobby if it is missing and syncs its members,
+    /// returning the memberships that ended.
+    pub async fn provision_tournament_lobby<C: ConnectionTrait>(
+        conn: &C,
+        tournament_id: Uuid,
+    ) -> Result<Vec<EndedMembership>, AppError> {
+        Self::create_tournament_lobbies(conn, Some(tournament_id)).await?;
+        Self::sync_channel(
+            conn,
+            chat::Column::TournamentId,
+            TOURNAMENT_CHAT,
+            tournament_id,
+        )
+        .await
+    }
+
+    /// Opens the battle's lobby once the battle is near, or archives it once
+    /// the battle is over. Returns the memberships that ended.
+    pub async fn provision_battle_lobby<C: ConnectionTrait>(
+        conn: &C,
+        battle_id: Uuid,
+    ) -> Result<Vec<EndedMembership>, AppError> {
+        Self::create_battle_lobbies(conn, Some(battle_id)).await?;
+        Self::archive_battle_lobbies(conn, Some(battle_id)).await?;
+        Self::sync_channel(conn, chat::Column::BattleId, BATTLE_CHAT, battle_id).await
+    }
+
+    /// Provisions every missing chat, archives finished battle lobbies and
+    /// brings all memberships in line with the rosters.
+    pub async fn reconcile(&self) -> Result<(), AppError> {
+        let created = Self::create_team_chats(&self.db, None).await?
+            + Self::create_tournament_lobbies(&self.db, None).await?
+            + Self::create_battle_lobbies(&self.db, None).await?;
+        let archived = Self::archive_battle_lobbies(&self.db, None).await?;
+        let (joined, ended) = Self::sync_members(&self.db, None).await?;
+        let left = ended.len() as u64;
+        self.evict(ended).await;
+
+        if created + archived + joined + left > 0 {
+            tracing::info!(
+                "Provisioned chats: {} created, {} archived, {} memberships added, {} ended",
+                created,
+                archived,
+                joined,
+                left
+            );
+        }
+        Ok(())
+    }
+
+    async fn sync_channel<C: ConnectionTrait>(
+        conn: &C,
+        column: chat::Column,
+        chat_type: &str,
+        owner_id: Uuid,
+    ) -> Result<Vec<EndedMembership>, AppError> {
+        let chat = Chat::find()
+            .filter(chat::Column::ChatType.eq(chat_type))
+            .filter(column.eq(owner_id))
+            .one(conn)
+            .await?;
+        match chat {
+            Some(chat) => Ok(Self::sync_members(conn, Some(chat.id)).await?.1),
+            None => Ok(Vec::new()),
+        }
+    }
+
+    async fn create_team_chats<C: ConnectionTrait>(
+        conn: &C,
+        team_id: Option<Uuid>,
+    ) -> Result<u64, AppError> {
+        Self::execute(
+            conn,
+            r#"INSERT INTO chats (id, chat_type, name, team_id, is_private)
+               SELECT gen_random_uuid(), 'team', LEFT(t.team_name, 100), t.id, TRUE
+               FROM teams t
+               WHERE t.status <> 'disbanded' AND ($1::uuid IS NULL OR t.id = $1)
+               ON CONFLICT DO NOTHING"#,
+            team_id,
+        )
+        .await
+    }
+
+    async fn create_tournament_lobbies<C: ConnectionTrait>(
+        conn: &C,
+        tournament_id: Option<Uuid>,
+    ) -> Result<u64, AppError> {
+        Self::execute(
+            conn,
+            r#"INSERT INTO chats (id, chat_type, name, tournament_id, is_private)
+               SELECT gen_random_uuid(), 'tournament', LEFT(t.tournament_name || ' Lobby', 100), t.id, TRUE
+               FROM tournaments t
+               WHERE t.status NOT IN ('completed', 'cancelled')
+                 AND ($1::uuid IS NULL OR t.id = $1)
+               ON CONFLICT DO NOTHING"#,
+            tournament_id,
+        )
+        .await
+    }
+
+    async fn create_battle_lobbies<C: ConnectionTrait>(
+        conn: &C,
+        battle_id: Option<Uuid>,
+    ) -> Result<u64, AppError> {
+        let result = conn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO chats (id, chat_type, name, tournament_id, battle_id, is_private)
+                   SELECT gen_random_uuid(), 'battle', 'Battle ' || b.battle_number, b.tournament, b.id, TRUE
+                   FROM battles b
+                   WHERE b.status IN ('scheduled', 'in_progress')
+                     AND b.scheduled_start_time <= NOW() + make_interval(hours => $2)
+                     AND ($1::uuid IS NULL OR b.id = $1)
+                   ON CONFLICT DO NOTHING"#,
+                [battle_id.into(), BATTLE_LOBBY_LEAD_HOURS.into()],
+            ))
+            .await?;
+        Ok(result.rows_affected())
+    }
+
+    async fn archive_battle_lobbies<C: ConnectionTrait>(
+        conn: &C,
+        battle_id: Option<Uuid>,
+    ) -> Result<u64, AppError> {
+        Self::execute(
+            conn,
+            r#"UPDATE chats c SET archived_at = NOW(), updated_at = NOW()
+               FROM battles b
+               WHERE c.battle_id = b.id AND c.archived_at IS NULL
+                 AND b.status IN ('completed', 'cancelled')
+                 AND ($1::uuid IS NULL OR b.id = $1)"#,
+            battle_id,
+        )
+        .await
+    }
+
+    /// Adds the roster's players that are missing from provisioned chats and
+    /// ends the memberships of those no longer on it. Ownership of a team chat
+    /// follows the captain; other roles members were given are kept. Returns
+    /// the number added and the memberships ended.
+    async fn sync_members<C: ConnectionTrait>(
+        conn: &C,
+        chat_id: Option<Uuid>,
+    ) -> Result<(u64, Vec<EndedMembership>), AppError> {
+        let joined = Self::execute(
+            conn,
+            &format!(
+                r#"WITH {ROSTER}
+                   INSERT INTO chat_members (id, chat_id, player_id, role)
+                   SELECT gen_random_uuid(), chat_id, player_id, role FROM roster
+                   ON CONFLICT (chat_id, player_id) DO UPDATE SET
+                       role = CASE
+                           WHEN chat_members.left_at IS NULL
+                                AND chat_members.role <> 'owner' AND EXCLUDED.role <> 'owner'
+                           THEN chat_members.role
+                           ELSE EXCLUDED.role
+                       END,
+                       is_muted = chat_members.is_muted
+                           AND chat_members.left_at IS NULL AND EXCLUDED.role <> 'owner',
+                       muted_until = CASE
+                           WHEN chat_members.left_at IS NULL AND EXCLUDED.role <> 'owner'
+                           THEN chat_members.muted_until
+                       END,
+                       joined_at = COALESCE(
+                           CASE WHEN chat_members.left_at IS NULL THEN chat_members.joined_at END,
+                           NOW()
+                       ),
+                       left_at = NULL,
+                       updated_at = NOW()
+                   WHERE chat_members.left_at IS NOT NULL
+                      OR (chat_members.role = 'owner') <> (EXCLUDED.role = 'owner')"#
+            ),
+            chat_id,
+        )
+        .await?;
+
+        let ended = EndedMembership::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(
+                r#"WITH {ROSTER}
+                   UPDATE chat_members m SET left_at = NOW(), updated_at = NOW()
+                   FROM chats c
+                   WHERE c.id = m.chat_id AND m.left_at IS NULL AND c.archived_at IS NULL
+                     AND (c.team_id IS NOT NULL OR c.tournament_id IS NOT NULL OR c.battle_id IS NOT NULL)
+                     AND ($1::uuid IS NULL OR c.id = $1)
+                     AND NOT EXISTS (
+                         SELECT 1 FROM roster r WHERE r.chat_id = m.chat_id AND r.player_id = m.player_id
+                     )
+                   RETURNING m.chat_id, m.player_id"#
+            ),
+            [chat_id.into()],
+        ))
+        .all(conn)
+        .await?;
+
+        Ok((joined, ended))
+    }
+
+    async fn execute<C: ConnectionTrait>(
+        conn: &C,
+        sql: &str,
+        scope: Option<Uuid>,
+    ) -> Result<u64, AppError> {
+        let result = conn
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                sql,
+                [scope.into()],
+            ))
+            .await?;
+        Ok(result.rows_affected())
+    }
+}
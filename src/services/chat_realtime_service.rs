@@ -0,0 +1,235 @@
+use futures_util::StreamExt;
+use redis::aio::ConnectionManager;
+use redis::{AsyncCommands, Client as RedisClient};
+use serde::{Deserialize, Serialize};
+use socketioxide::SocketIo;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::OnceCell;
+use uuid::Uuid;
+
+pub const CHAT_NAMESPACE: &str = "/chat";
+
+// Events the /chat namespace sends to the room of a chat
+pub const MESSAGE_NEW: &str = "message:new";
+pub const MESSAGE_EDITED: &str = "message:edited";
+pub const MESSAGE_DELETED: &str = "message:deleted";
+pub const MESSAGE_REACTIONS: &str = "message:reactions";
+pub const TYPING: &str = "typing";
+pub const READ: &str = "read";
+
+// Events sent to the players an announcement targets
+pub const ANNOUNCEMENT: &str = "announcement";
+// Events sent to the players a domain event concerns
+pub const NOTIFICATION: &str = "notification";
+
+const RELAY_CHANNEL: &str = "aegis:chat:events";
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+const RELAY_RECONNECT_AFTER: Duration = Duration::from_secs(5);
+
+/// What one node publishes for the others to repeat on their own sockets.
+#[derive(Serialize, Deserialize)]
+struct Relayed {
+    node_id: Uuid,
+    #[serde(flatten)]
+    action: RelayedAction,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "action", rename_all = "snake_case")]
+enum RelayedAction {
+    // No rooms means every socket
+    Emit {
+        rooms: Vec<String>,
+        event: String,
+        data: serde_json::Value,
+    },
+    Evict {
+        chat_id: Uuid,
+        player_id: Uuid,
+    },
+}
+
+/// Pushes chat events, and announcements to players, to the Socket.IO rooms
+/// of every node. Each node emits to its own sockets and publishes to Redis;
+/// the other nodes re-emit what they receive. Without Redis only local
+/// sockets are reached.
+#[derive(Clone)]
+pub struct ChatRealtimeService {
+    io: SocketIo,
+    redis: Option<RedisClient>,
+    publisher: Arc<OnceCell<ConnectionManager>>,
+    node_id: Uuid,
+}
+
+impl ChatRealtimeService {
+    pub fn new(io: SocketIo, redis_url: Option<String>) -> Self {
+        let redis = redis_url.and_then(|url| match RedisClient::open(url) {
+            Ok(client) => Some(client),
+            Err(e) => {
+                tracing::warn!("Chat events stay on this node, no Redis: {}", e);
+                None
+            }
+        });
+
+        Self {
+            io,
+            redis,
+            publisher: Arc::new(OnceCell::new()),
+            node_id: Uuid::new_v4(),
+        }
+    }
+
+    pub fn io(&self) -> &SocketIo {
+        &self.io
+    }
+
+    pub fn room(chat_id: Uuid) -> String {
+        format!("chat:{}", chat_id)
+    }
+
+    /// Every socket of a player joins this room on connect.
+    pub fn player_room(player_id: Uuid) -> String {
+        format!("player:{}", player_id)
+    }
+
+    /// Sends `event` to everyone in the chat's room, on all nodes.
+    pub async fn broadcast(&self, chat_id: Uuid, event: &str, data: serde_json::Value) {
+        self.publish(vec![Self::room(chat_id)], event, data).await;
+    }
+
+    /// Sends `event` to the connected sockets of the given players.
+    pub async fn notify_players(&self, player_ids: &[Uuid], event: &str, data: serde_json::Value) {
+        if player_ids.is_empty() {
+            return;
+        }
+        let rooms = player_ids.iter().map(|id| Self::player_room(*id)).collect();
+        self.publish(rooms, event, data).await;
+    }
+
+    /// Sends `event` to every connected socket.
+    pub async fn notify_all(&self, event: &str, data: serde_json::Value) {
+        self.publish(Vec::new(), event, data).await;
+    }
+
+    /// Takes the player's sockets out of the chat's room, on all nodes, once
+    /// their membership has ended.
+    pub async fn evict(&self, chat_id: Uuid, player_id: Uuid) {
+        self.leave_local(chat_id, player_id);
+        self.relay_to_nodes(RelayedAction::Evict { chat_id, player_id })
+            .await;
+    }
+
+    async fn publish(&self, rooms: Vec<String>, event: &str, data: serde_json::Value) {
+        self.emit_local(&rooms, event, &data);
+        self.relay_to_nodes(RelayedAction::Emit {
+            rooms,
+            event: event.to_string(),
+            data,
+        })
+        .await;
+    }
+
+    async fn relay_to_nodes(&self, action: RelayedAction) {
+        let Some(client) = &self.redis else {
+            return;
+        };
+        let relayed = Relayed {
+            node_id: self.node_id,
+            action,
+        };
+        let Ok(payload) = serde_json::to_string(&relayed) else {
+            return;
+        };
+
+        let publisher = self
+            .publisher
+            .get_or_try_init(|| async {
+                tokio::time::timeout(REDIS_TIMEOUT, ConnectionManager::new(client.clone()))
+                    .await
+                    .map_err(|_| {
+                        redis::RedisError::from((redis::ErrorKind::IoError, "connect timed out"))
+                    })?
+            })
+            .await;
+        match publisher {
+            Ok(publisher) => {
+                let mut conn = publisher.clone();
+                if let Err(e) = conn.publish::<_, _, ()>(RELAY_CHANNEL, payload).await {
+                    tracing::warn!("Failed to relay chat event: {}", e);
+                }
+            }
+            Err(e) => tracing::warn!("Failed to relay chat event: {}", e),
+        }
+    }
+
+    fn emit_local(&self, rooms: &[String], event: &str, data: &serde_json::Value) {
+        let Some(ns) = self.io.of(CHAT_NAMESPACE) else {
+            return;
+        };
+        let sent = if rooms.is_empty() {
+            ns.emit(event.to_string(), data)
+        } else {
+            ns.to(rooms.to_vec()).emit(event.to_string(), data)
+        };
+        if let Err(e) = sent {
+            tracing::warn!("Failed to emit {} to {:?}: {:?}", event, rooms, e);
+        }
+    }
+
+    fn leave_local(&self, chat_id: Uuid, player_id: Uuid) {
+        let Some(ns) = self.io.of(CHAT_NAMESPACE) else {
+            return;
+        };
+        if let Err(e) = ns
+            .to(Self::player_room(player_id))
+            .leave(Self::room(chat_id))
+        {
+            tracing::warn!(
+                "Failed to evict {} from chat {}: {:?}",
+                player_id,
+                chat_id,
+                e
+            );
+        }
+    }
+
+    /// Re-emits events published by other nodes, reconnecting when Redis drops.
+    pub fn spawn_relay(&self) {
+        let Some(client) = self.redis.clone() else {
+            return;
+        };
+        let service = self.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = service.relay(&client).await {
+                    tracing::warn!("Chat relay disconnected from Redis: {}", e);
+                }
+                tokio::time::sleep(RELAY_RECONNECT_AFTER).await;
+            }
+        });
+    }
+
+    async fn relay(&self, client: &RedisClient) -> redis::RedisResult<()> {
+        let mut pubsub = client.get_async_connection().await?.into_pubsub();
+        pubsub.subscribe(RELAY_CHANNEL).await?;
+
+        let mut messages = pubsub.on_message();
+        while let Some(message) = messages.next().await {
+            let payload: String = message.get_payload()?;
+            match serde_json::from_str::<Relayed>(&payload) {
+                Ok(relayed) if relayed.node_id != self.node_id => match relayed.action {
+                    RelayedAction::Emit { rooms, event, data } => {
+                        self.emit_local(&rooms, &event, &data);
+                    }
+                    RelayedAction::Evict { chat_id, player_id } => {
+                        self.leave_local(chat_id, player_id);
+                    }
+                },
+                Ok(_) => {}
+                Err(e) => tracing::warn!("Dropping malformed chat event: {}", e),
+            }
+        }
+        Ok(())
+    }
+}
@@ -1,164 +1,1424 @@
-use crate::models::postgres::{chat, chat_message};
-use anyhow::Result;
-use sea_orm::{
-    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder,
-    QuerySelect, Set,
+use crate::models::enums::{ChatMemberRole, ModeratedContent};
+use crate::models::postgres::{
+    chat, chat_attachment, chat_member, chat_message, chat_message_edit, chat_message_reaction,
+    ChatAttachment, ChatMember, ChatMessage, ChatMessageEdit, ChatMessageReaction, Player,
 };
+use crate::services::direct_message_service::{DirectMessageService, DIRECT_CHAT, GROUP_DM_CHAT};
+use crate::services::moderation_service::{
+    ModerationService, QueuedContent, Screening, Submission,
+};
+use crate::services::outbox_service::{DomainEvent, OutboxService};
+use crate::services::s3_service::S3Service;
+use crate::utils::errors::AppError;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+pub const MAX_MESSAGE_LENGTH: usize = 4000;
+pub const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+const MAX_ATTACHMENTS_PER_MESSAGE: usize = 10;
+// Discarded attachments removed from storage per sweep
+const ATTACHMENT_PURGE_BATCH: u64 = 100;
+const REPLY_EXCERPT_CHARS: usize = 120;
+pub const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 100;
+const MAX_SEARCH_QUERY_CHARS: usize = 200;
+// Emoji sequences with skin tones and joiners run to a few dozen bytes
+const MAX_EMOJI_BYTES: usize = 64;
+
+// `system` messages are only written by the server
+const USER_MESSAGE_TYPES: [&str; 4] = ["text", "image", "file", "emoji"];
+
+pub struct NewChatMessage {
+    pub message: String,
+    pub message_type: String,
+    pub reply_to: Option<Uuid>,
+    pub attachment_ids: Vec<Uuid>,
+}
+
+/// The start of the message a reply quotes.
+#[derive(Debug, Serialize)]
+pub struct ReplyPreview {
+    pub message_id: Uuid,
+    pub sender_id: Uuid,
+    pub excerpt: String,
+    pub deleted: bool,
+}
+
+impl ReplyPreview {
+    fn of(parent: &chat_message::Model) -> Self {
+        Self {
+            message_id: parent.id,
+            sender_id: parent.sender_id,
+            excerpt: parent.message.chars().take(REPLY_EXCERPT_CHARS).collect(),
+            deleted: parent.deleted_at.is_some(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChatMessageView {
+    #[serde(flatten)]
+    pub message: chat_message::Model,
+    pub reply_preview: Option<ReplyPreview>,
+}
+
+/// A slice of chat history, newest first.
+#[derive(Debug, Serialize)]
+pub struct MessagePage {
+    pub messages: Vec<ChatMessageView>,
+    pub has_older: bool,
+    pub has_newer: bool,
+}
+
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct MessageSearchHit {
+    pub message_id: Uuid,
+    pub chat_id: Uuid,
+    pub sender_id: Uuid,
+    pub created_at: DateTime<Utc>,
+    pub snippet: String,
+    pub rank: f32,
+}
+
+/// A position in history. Messages sharing a timestamp are ordered by id, so
+/// a message cursor never skips or repeats its neighbours.
+struct CursorKey {
+    at: DateTime<Utc>,
+    id: Option<Uuid>,
+}
+
+impl CursorKey {
+    fn before(&self) -> Condition {
+        match self.id {
+            Some(id) => Condition::any()
+                .add(chat_message::Column::CreatedAt.lt(self.at))
+                .add(
+                    Condition::all()
+                        .add(chat_message::Column::CreatedAt.eq(self.at))
+                        .add(chat_message::Column::Id.lt(id)),
+                ),
+            None => Condition::all().add(chat_message::Column::CreatedAt.lt(self.at)),
+        }
+    }
+
+    fn after(&self) -> Condition {
+        match self.id {
+            Some(id) => Condition::any()
+                .add(chat_message::Column::CreatedAt.gt(self.at))
+                .add(
+                    Condition::all()
+                        .add(chat_message::Column::CreatedAt.eq(self.at))
+                        .add(chat_message::Column::Id.gt(id)),
+                ),
+            None => Condition::all().add(chat_message::Column::CreatedAt.gt(self.at)),
+        }
+    }
+}
+
+/// What the server found out about an uploaded file. The client's filename is
+/// only kept for display; the type comes from the file's leading bytes.
+pub struct AttachmentUpload {
+    pub filename: String,
+    pub content_type: String,
+    pub size_bytes: i64,
+    pub sha256: String,
+}
+
+impl AttachmentUpload {
+    pub fn inspect(filename: &str, data: &[u8]) -> Result<Self, AppError> {
+        if data.is_empty() {
+            return Err(AppError::Validation("File is empty".to_string()));
+        }
+        if data.len() > MAX_ATTACHMENT_BYTES {
+            return Err(AppError::Validation(format!(
+                "Attachments are limited to {} MB",
+                MAX_ATTACHMENT_BYTES / (1024 * 1024)
+            )));
+        }
+
+        Ok(Self {
+            filename: sanitize_filename(filename),
+            content_type: sniff_content_type(filename, data).to_string(),
+            size_bytes: data.len() as i64,
+            sha256: hex::encode(Sha256::digest(data)),
+        })
+    }
+}
+
+fn sanitize_filename(filename: &str) -> String {
+    let base = filename.rsplit(['/', '\\']).next().unwrap_or_default();
+    let clean: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .take(100)
+        .collect();
+    if clean.trim_matches(['.', '_']).is_empty() {
+        "attachment".to_string()
+    } else {
+        clean
+    }
+}
+
+fn sniff_content_type(filename: &str, data: &[u8]) -> &'static str {
+    match data {
+        [0xFF, 0xD8, 0xFF, ..] => "image/jpeg",
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => "image/png",
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => "image/gif",
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => "image/webp",
+        [b'%', b'P', b'D', b'F', b'-', ..] => "application/pdf",
+        [_, _, _, _, b'f', b't', b'y', b'p', ..] => "video/mp4",
+        [b'I', b'D', b'3', ..] | [0xFF, 0xFB | 0xF3 | 0xF2, ..] => "audio/mpeg",
+        [b'O', b'g', b'g', b'S', ..] => "audio/ogg",
+        [b'P', b'K', 0x03, 0x04, ..] => "application/zip",
+        // Plain text has no signature, so the extension decides among text types
+        _ if std::str::from_utf8(data).is_ok() => {
+            if filename.to_lowercase().ends_with(".json") {
+                "application/json"
+            } else {
+                "text/plain"
+            }
+        }
+        _ => "application/octet-stream",
+    }
+}
+
+fn validate_message_text(text: &str, has_attachments: bool) -> Result<(), AppError> {
+    if text.trim().is_empty() && !has_attachments {
+        return Err(AppError::Validation("Message can't be empty".to_string()));
+    }
+    if text.chars().count() > MAX_MESSAGE_LENGTH {
+        return Err(AppError::Validation(format!(
+            "Messages are limited to {} characters",
+            MAX_MESSAGE_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+fn validate_emoji(emoji: &str) -> Result<(), AppError> {
+    if emoji.is_empty()
+        || emoji.len() > MAX_EMOJI_BYTES
+        || emoji.chars().any(|c| c.is_whitespace() || c.is_control())
+    {
+        return Err(AppError::Validation("Invalid reaction".to_string()));
+    }
+    Ok(())
+}
+
+fn attachment_summary(attachment: &chat_attachment::Model) -> serde_json::Value {
+    serde_json::json!({
+        "id": attachment.id,
+        "url": attachment.url,
+        "filename": attachment.filename,
+        "content_type": attachment.content_type,
+        "size_bytes": attachment.size_bytes,
+    })
+}
+
 #[derive(Clone)]
 pub struct ChatService {
     db: DatabaseConnection,
+    moderation: ModerationService,
 }
 
 impl ChatService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, moderation: ModerationService) -> Self {
+        Self { db, moderation }
     }
 
+    /// Creates a chat with its creator as owner.
     pub async fn create_chat(
         &self,
         name: String,
         chat_type: String,
-        created_by: String,
-    ) -> Result<String> {
-        let chat_id = Uuid::new_v4();
-        let created_by_uuid = Uuid::parse_str(&created_by)?;
+        created_by: Uuid,
+    ) -> Result<chat::Model, AppError> {
+        if chat_type == DIRECT_CHAT || chat_type == GROUP_DM_CHAT {
+            return Err(AppError::Validation(
+                "Direct conversations are started through /dm".to_string(),
+            ));
+        }
+
+        let txn = self.db.begin().await?;
 
         let chat = chat::ActiveModel {
-            id: Set(chat_id),
+            id: Set(Uuid::new_v4()),
             name: Set(name),
             chat_type: Set(chat_type),
-            created_by: Set(created_by_uuid),
-            participants: Set(serde_json::json!([created_by])),
+            created_by: Set(Some(created_by)),
             ..Default::default()
-        };
+        }
+        .insert(&txn)
+        .await?;
+        Self::add_membership(&txn, chat.id, created_by, ChatMemberRole::Owner).await?;
 
-        chat.insert(&self.db).await?;
-        Ok(chat_id.to_string())
+        txn.commit().await?;
+        Ok(chat)
+    }
+
+    pub async fn get_chat(&self, chat_id: Uuid) -> Result<Option<chat::Model>, AppError> {
+        Ok(chat::Entity::find_by_id(chat_id).one(&self.db).await?)
+    }
+
+    pub async fn get_chats_by_type(&self, chat_type: &str) -> Result<Vec<chat::Model>, AppError> {
+        Ok(chat::Entity::find()
+            .filter(chat::Column::ChatType.eq(chat_type))
+            .all(&self.db)
+            .await?)
     }
 
-    pub async fn get_chat(&self, chat_id: &str) -> Result<Option<chat::Model>> {
-        let chat_uuid = Uuid::parse_str(chat_id)?;
-        Ok(chat::Entity::find_by_id(chat_uuid).one(&self.db).await?)
+    /// The player's current membership, if they haven't left.
+    pub async fn get_membership(
+        &self,
+        chat_id: Uuid,
+        player_id: Uuid,
+    ) -> Result<Option<chat_member::Model>, AppError> {
+        Ok(ChatMember::find()
+            .filter(chat_member::Column::ChatId.eq(chat_id))
+            .filter(chat_member::Column::PlayerId.eq(player_id))
+            .filter(chat_member::Column::LeftAt.is_null())
+            .one(&self.db)
+            .await?)
+    }
+
+    /// Chats are only visible to their members, so a missing chat is
+    /// indistinguishable from one the player isn't in.
+    pub async fn require_member(
+        &self,
+        chat_id: Uuid,
+        player_id: Uuid,
+    ) -> Result<chat_member::Model, AppError> {
+        self.get_membership(chat_id, player_id)
+            .await?
+            .ok_or(AppError::Forbidden)
+    }
+
+    pub async fn is_member(&self, chat_id: Uuid, player_id: Uuid) -> Result<bool, AppError> {
+        Ok(self.get_membership(chat_id, player_id).await?.is_some())
     }
 
     pub async fn send_message(
         &self,
-        chat_id: String,
-        sender: String,
-        message: String,
-        message_type: String,
-    ) -> Result<String> {
-        let message_id = Uuid::new_v4();
-        let chat_uuid = Uuid::parse_str(&chat_id)?;
-        let sender_uuid = Uuid::parse_str(&sender)?;
-
-        let chat_message = chat_message::ActiveModel {
-            id: Set(message_id),
-            chat_id: Set(chat_uuid),
-            sender_id: Set(sender_uuid),
+        chat_id: Uuid,
+        sender_id: Uuid,
+        new_message: NewChatMessage,
+    ) -> Result<ChatMessageView, AppError> {
+        let member = self.require_member(chat_id, sender_id).await?;
+        if member.is_silenced() {
+            return Err(AppError::Forbidden);
+        }
+        self.ensure_not_archived(chat_id).await?;
+        self.ensure_not_blocked(chat_id, sender_id).await?;
+
+        let NewChatMessage {
+            message,
+            message_type,
+            reply_to,
+            attachment_ids,
+        } = new_message;
+        if !USER_MESSAGE_TYPES.contains(&message_type.as_str()) {
+            return Err(AppError::Validation(format!(
+                "Unsupported message type '{}'",
+                message_type
+            )));
+        }
+        if attachment_ids.len() > MAX_ATTACHMENTS_PER_MESSAGE {
+            return Err(AppError::Validation(format!(
+                "At most {} attachments per message",
+                MAX_ATTACHMENTS_PER_MESSAGE
+            )));
+        }
+        validate_message_text(&message, !attachment_ids.is_empty())?;
+        let screening = self
+            .moderation
+            .screen(&Submission {
+                content_type: ModeratedContent::ChatMessage,
+                author_id: sender_id,
+                text: &message,
+                edit_of: None,
+            })
+            .await?;
+
+        if let Some(reply_to) = reply_to {
+            let parent = self.get_message_in_chat(chat_id, reply_to).await?;
+            if parent.deleted_at.is_some() {
+                return Err(AppError::Validation(
+                    "Can't reply to a deleted message".to_string(),
+                ));
+            }
+        }
+
+        let txn = self.db.begin().await?;
+
+        let attachments = if attachment_ids.is_empty() {
+            Vec::new()
+        } else {
+            ChatAttachment::find()
+                .filter(chat_attachment::Column::Id.is_in(attachment_ids.clone()))
+                .filter(chat_attachment::Column::ChatId.eq(chat_id))
+                .filter(chat_attachment::Column::UploadedBy.eq(sender_id))
+                .filter(chat_attachment::Column::MessageId.is_null())
+                .filter(chat_attachment::Column::DiscardedAt.is_null())
+                .lock_exclusive()
+                .all(&txn)
+                .await?
+        };
+        let unique_ids: HashSet<Uuid> = attachment_ids.iter().copied().collect();
+        if attachments.len() != unique_ids.len() {
+            return Err(AppError::Validation(
+                "Attachments must be your own unused uploads to this chat".to_string(),
+            ));
+        }
+
+        let message = chat_message::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            chat_id: Set(chat_id),
+            sender_id: Set(sender_id),
             message: Set(message),
             message_type: Set(message_type),
+            reply_to: Set(reply_to),
+            attachments: Set(serde_json::json!(attachments
+                .iter()
+                .map(attachment_summary)
+                .collect::<Vec<_>>())),
             ..Default::default()
-        };
+        }
+        .insert(&txn)
+        .await?;
 
-        chat_message.insert(&self.db).await?;
-        Ok(message_id.to_string())
+        if !attachments.is_empty() {
+            ChatAttachment::update_many()
+                .col_expr(
+                    chat_attachment::Column::MessageId,
+                    Expr::value(Some(message.id)),
+                )
+                .filter(chat_attachment::Column::Id.is_in(unique_ids))
+                .exec(&txn)
+                .await?;
+        }
+        txn.commit().await?;
+
+        self.queue_flags(screening, &message).await;
+        self.view_one(message).await
     }
 
+    /// A page of history, newest first. `before` and `after` take a message id
+    /// or an RFC 3339 timestamp; without either the page ends at the latest
+    /// message.
     pub async fn get_messages(
         &self,
-        chat_id: &str,
-        limit: Option<i32>,
-    ) -> Result<Vec<chat_message::Model>> {
-        let chat_uuid = Uuid::parse_str(chat_id)?;
-        let mut query = chat_message::Entity::find()
-            .filter(chat_message::Column::ChatId.eq(chat_uuid))
-            .order_by_desc(chat_message::Column::CreatedAt);
+        chat_id: Uuid,
+        viewer_id: Uuid,
+        before: Option<&str>,
+        after: Option<&str>,
+        limit: u64,
+    ) -> Result<MessagePage, AppError> {
+        self.require_member(chat_id, viewer_id).await?;
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+
+        let (messages, has_older, has_newer) = match (before, after) {
+            (Some(_), Some(_)) => {
+                return Err(AppError::Validation(
+                    "Use either before or after, not both".to_string(),
+                ))
+            }
+            (Some(cursor), None) => {
+                let key = self.resolve_cursor(chat_id, cursor).await?;
+                let (older, has_older) = self.older_than(chat_id, Some(&key), limit).await?;
+                let has_newer = !self.newer_than(chat_id, &key, 1).await?.0.is_empty();
+                (older, has_older, has_newer)
+            }
+            (None, Some(cursor)) => {
+                let key = self.resolve_cursor(chat_id, cursor).await?;
+                let (newer, has_newer) = self.newer_than(chat_id, &key, limit).await?;
+                let has_older = !self.older_than(chat_id, Some(&key), 1).await?.0.is_empty();
+                (newer, has_older, has_newer)
+            }
+            (None, None) => {
+                let (latest, has_older) = self.older_than(chat_id, None, limit).await?;
+                (latest, has_older, false)
+            }
+        };
 
-        if let Some(limit) = limit {
-            query = query.limit(limit as u64);
+        Ok(MessagePage {
+            messages: self.with_reply_previews(messages).await?,
+            has_older,
+            has_newer,
+        })
+    }
+
+    /// The history around one message, for jumping to it from a search hit or
+    /// a reply. Roughly half the window is older than the message.
+    pub async fn message_context(
+        &self,
+        chat_id: Uuid,
+        viewer_id: Uuid,
+        message_id: Uuid,
+        limit: u64,
+    ) -> Result<MessagePage, AppError> {
+        self.require_member(chat_id, viewer_id).await?;
+        let limit = limit.clamp(1, MAX_PAGE_SIZE);
+
+        let target = self.get_message_in_chat(chat_id, message_id).await?;
+        let key = CursorKey {
+            at: target.created_at,
+            id: Some(target.id),
+        };
+        let older_count = (limit - 1) / 2;
+        let (older, has_older) = self.older_than(chat_id, Some(&key), older_count).await?;
+        let (newer, has_newer) = self
+            .newer_than(chat_id, &key, limit - 1 - older_count)
+            .await?;
+
+        let messages = newer
+            .into_iter()
+            .chain(std::iter::once(target))
+            .chain(older)
+            .collect();
+        Ok(MessagePage {
+            messages: self.with_reply_previews(messages).await?,
+            has_older,
+            has_newer,
+        })
+    }
+
+    async fn resolve_cursor(&self, chat_id: Uuid, cursor: &str) -> Result<CursorKey, AppError> {
+        if let Ok(message_id) = Uuid::parse_str(cursor) {
+            let message = self.get_message_in_chat(chat_id, message_id).await?;
+            return Ok(CursorKey {
+                at: message.created_at,
+                id: Some(message.id),
+            });
+        }
+        DateTime::parse_from_rfc3339(cursor)
+            .map(|at| CursorKey {
+                at: at.with_timezone(&Utc),
+                id: None,
+            })
+            .map_err(|_| {
+                AppError::Validation("Cursor must be a message id or an RFC 3339 time".to_string())
+            })
+    }
+
+    /// Up to `limit` messages older than `key` (or the latest ones), newest
+    /// first, and whether more remain.
+    async fn older_than(
+        &self,
+        chat_id: Uuid,
+        key: Option<&CursorKey>,
+        limit: u64,
+    ) -> Result<(Vec<chat_message::Model>, bool), AppError> {
+        if limit == 0 {
+            return Ok((Vec::new(), false));
         }
+        let mut query = ChatMessage::find().filter(chat_message::Column::ChatId.eq(chat_id));
+        if let Some(key) = key {
+            query = query.filter(key.before());
+        }
+        let mut messages = query
+            .order_by_desc(chat_message::Column::CreatedAt)
+            .order_by_desc(chat_message::Column::Id)
+            .limit(limit + 1)
+            .all(&self.db)
+            .await?;
 
-        Ok(query.all(&self.db).await?)
+        let has_more = messages.len() as u64 > limit;
+        messages.truncate(limit as usize);
+        Ok((messages, has_more))
     }
 
-    pub async fn get_chats_by_type(&self, chat_type: &str) -> Result<Vec<chat::Model>> {
-        Ok(chat::Entity::find()
-            .filter(chat::Column::ChatType.eq(chat_type))
+    /// Up to `limit` messages right after `key`, newest first, and whether
+    /// more remain.
+    async fn newer_than(
+        &self,
+        chat_id: Uuid,
+        key: &CursorKey,
+        limit: u64,
+    ) -> Result<(Vec<chat_message::Model>, bool), AppError> {
+        if limit == 0 {
+            return Ok((Vec::new(), false));
+        }
+        let mut messages = ChatMessage::find()
+            .filter(chat_message::Column::ChatId.eq(chat_id))
+            .filter(key.after())
+            .order_by_asc(chat_message::Column::CreatedAt)
+            .order_by_asc(chat_message::Column::Id)
+            .limit(limit + 1)
             .all(&self.db)
-            .await?)
+            .await?;
+
+        let has_more = messages.len() as u64 > limit;
+        messages.truncate(limit as usize);
+        messages.reverse();
+        Ok((messages, has_more))
     }
 
-    pub async fn join_chat(&self, chat_id: &str, user_id: &str) -> Result<()> {
-        use sea_orm::{ActiveModelTrait, TransactionTrait};
+    /// Full-text search over the player's chats, or one of them, best matches
+    /// first. Snippets are HTML-escaped with matches wrapped in `<mark>`.
+    pub async fn search_messages(
+        &self,
+        player_id: Uuid,
+        query: &str,
+        chat_id: Option<Uuid>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<MessageSearchHit>, AppError> {
+        let query = query.trim();
+        if query.is_empty() || query.chars().count() > MAX_SEARCH_QUERY_CHARS {
+            return Err(AppError::Validation(format!(
+                "Search terms must be 1 to {} characters",
+                MAX_SEARCH_QUERY_CHARS
+            )));
+        }
+        if let Some(chat_id) = chat_id {
+            self.require_member(chat_id, player_id).await?;
+        }
+
+        Ok(MessageSearchHit::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT cm.id AS message_id, cm.chat_id, cm.sender_id, cm.created_at,
+                      ts_headline(
+                          'simple',
+                          replace(replace(replace(cm.message, '&', '&amp;'), '<', '&lt;'), '>', '&gt;'),
+                          q.query,
+                          'StartSel=<mark>, StopSel=</mark>, MaxWords=24, MinWords=8, MaxFragments=2'
+                      ) AS snippet,
+                      ts_rank(cm.search_vector, q.query) AS rank
+               FROM chat_messages cm
+               JOIN chat_members m
+                 ON m.chat_id = cm.chat_id AND m.player_id = $1 AND m.left_at IS NULL
+               CROSS JOIN (SELECT websearch_to_tsquery('simple', $2) AS query) q
+               WHERE cm.deleted_at IS NULL
+                 AND cm.search_vector @@ q.query
+                 AND ($3::uuid IS NULL OR cm.chat_id = $3)
+               ORDER BY rank DESC, cm.created_at DESC
+               LIMIT $4 OFFSET $5"#,
+            [
+                player_id.into(),
+                query.into(),
+                chat_id.into(),
+                (limit.clamp(1, MAX_PAGE_SIZE) as i64).into(),
+                (offset as i64).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?)
+    }
+
+    async fn get_message_in_chat(
+        &self,
+        chat_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<chat_message::Model, AppError> {
+        ChatMessage::find_by_id(message_id)
+            .filter(chat_message::Column::ChatId.eq(chat_id))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
 
-        let chat_uuid = Uuid::parse_str(chat_id)?;
-        let user_uuid = Uuid::parse_str(user_id)?;
+    /// Senders edit their own messages; the previous text goes to the history.
+    pub async fn edit_message(
+        &self,
+        chat_id: Uuid,
+        editor_id: Uuid,
+        message_id: Uuid,
+        text: String,
+    ) -> Result<ChatMessageView, AppError> {
+        let member = self.require_member(chat_id, editor_id).await?;
+        if member.is_silenced() {
+            return Err(AppError::Forbidden);
+        }
+        self.ensure_not_archived(chat_id).await?;
+        let message = self.get_message_in_chat(chat_id, message_id).await?;
+        if message.sender_id != editor_id || message.message_type == "system" {
+            return Err(AppError::Forbidden);
+        }
+        if message.deleted_at.is_some() {
+            return Err(AppError::Validation(
+                "Deleted messages can't be edited".to_string(),
+            ));
+        }
+        let has_attachments = message
+            .attachments
+            .as_array()
+            .is_some_and(|a| !a.is_empty());
+        validate_message_text(&text, has_attachments)?;
+        if text == message.message {
+            return self.view_one(message).await;
+        }
+        let screening = self
+            .moderation
+            .screen(&Submission {
+                content_type: ModeratedContent::ChatMessage,
+                author_id: editor_id,
+                text: &text,
+                edit_of: Some(message.id),
+            })
+            .await?;
 
-        // Transaction: Ensure data consistency
+        let now = Utc::now();
         let txn = self.db.begin().await?;
 
-        //  Validate chat exists and user has permission
-        let chat = chat::Entity::find_by_id(chat_uuid)
-            .one(&txn)
-            .await?
-            .ok_or_else(|| anyhow::anyhow!("Chat not found"))?;
+        chat_message_edit::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            message_id: Set(message.id),
+            previous_message: Set(message.message.clone()),
+            edited_by: Set(editor_id),
+            edited_at: Set(now),
+        }
+        .insert(&txn)
+        .await?;
+
+        let mut update: chat_message::ActiveModel = message.into();
+        update.message = Set(text);
+        update.edited_at = Set(Some(now));
+        let updated = update.update(&txn).await?;
+
+        txn.commit().await?;
+        self.queue_flags(screening, &updated).await;
+        self.view_one(updated).await
+    }
+
+    /// Queues a stored message its screening flagged. The message is already
+    /// sent, so a failure here is only logged.
+    async fn queue_flags(&self, screening: Screening, message: &chat_message::Model) {
+        if let Err(e) = self
+            .moderation
+            .queue_flags(
+                screening,
+                QueuedContent {
+                    content_type: ModeratedContent::ChatMessage,
+                    content_id: message.id,
+                    author_id: message.sender_id,
+                    text: &message.message,
+                },
+            )
+            .await
+        {
+            tracing::warn!("Failed to queue flagged message {}: {}", message.id, e);
+        }
+    }
+
+    /// Earlier versions of a message, oldest first.
+    pub async fn message_edits(
+        &self,
+        chat_id: Uuid,
+        viewer_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<Vec<chat_message_edit::Model>, AppError> {
+        self.require_member(chat_id, viewer_id).await?;
+        self.get_message_in_chat(chat_id, message_id).await?;
+
+        Ok(ChatMessageEdit::find()
+            .filter(chat_message_edit::Column::MessageId.eq(message_id))
+            .order_by_asc(chat_message_edit::Column::EditedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    /// A message a member can report: one that is neither deleted nor a
+    /// system notice.
+    pub async fn reportable_message(
+        &self,
+        chat_id: Uuid,
+        reporter_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<chat_message::Model, AppError> {
+        self.require_member(chat_id, reporter_id).await?;
+        let message = self.get_message_in_chat(chat_id, message_id).await?;
+        if message.deleted_at.is_some() || message.message_type == "system" {
+            return Err(AppError::NotFound);
+        }
+        Ok(message)
+    }
+
+    /// Tombstones a message: it stays in the history with its content, edits,
+    /// reactions and attachment links removed. Senders delete their own
+    /// messages; owners and admins anyone's.
+    pub async fn delete_message(
+        &self,
+        chat_id: Uuid,
+        actor_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<chat_message::Model, AppError> {
+        let member = self.require_member(chat_id, actor_id).await?;
+        let message = self.get_message_in_chat(chat_id, message_id).await?;
+        if message.sender_id != actor_id && !member.role.can_moderate() {
+            return Err(AppError::Forbidden);
+        }
+        if message.deleted_at.is_some() {
+            return Ok(message);
+        }
 
-        // Security: Check max participants limit
-        let current_participants: Vec<String> =
-            serde_json::from_value(chat.participants.clone()).unwrap_or_default();
+        let txn = self.db.begin().await?;
+        let deleted = Self::tombstone(&txn, message, actor_id).await?;
+        txn.commit().await?;
+        Ok(deleted)
+    }
 
-        if current_participants.len() >= chat.max_participants as usize {
-            return Err(anyhow::anyhow!("Chat is at maximum capacity"));
+    /// Clears a message's content, edits and reactions and discards its
+    /// attachments, recording who deleted it. Moderators removing content use
+    /// this too.
+    pub async fn tombstone<C: ConnectionTrait>(
+        conn: &C,
+        message: chat_message::Model,
+        actor_id: Uuid,
+    ) -> Result<chat_message::Model, AppError> {
+        let message_id = message.id;
+        ChatMessageEdit::delete_many()
+            .filter(chat_message_edit::Column::MessageId.eq(message_id))
+            .exec(conn)
+            .await?;
+        ChatMessageReaction::delete_many()
+            .filter(chat_message_reaction::Column::MessageId.eq(message_id))
+            .exec(conn)
+            .await?;
+        // Discarded files stay linked so they can't be attached again
+        ChatAttachment::update_many()
+            .col_expr(
+                chat_attachment::Column::DiscardedAt,
+                Expr::value(Some(Utc::now())),
+            )
+            .filter(chat_attachment::Column::MessageId.eq(message_id))
+            .filter(chat_attachment::Column::DiscardedAt.is_null())
+            .exec(conn)
+            .await?;
+
+        let mut metadata = message.metadata.clone();
+        if let Some(map) = metadata.as_object_mut() {
+            map.insert("deleted_by".to_string(), serde_json::json!(actor_id));
         }
+        let mut update: chat_message::ActiveModel = message.into();
+        update.message = Set(String::new());
+        update.attachments = Set(serde_json::json!([]));
+        update.reactions = Set(serde_json::json!({}));
+        update.metadata = Set(metadata);
+        update.deleted_at = Set(Some(Utc::now()));
+        Ok(update.update(conn).await?)
+    }
 
-        //  Prevent duplicate joins
-        if current_participants.contains(&user_id.to_string()) {
-            return Ok(()); // Already a participant
+    /// Adds the player's `emoji` reaction and returns the message's counts.
+    /// Reacting twice with the same emoji is a no-op.
+    pub async fn add_reaction(
+        &self,
+        chat_id: Uuid,
+        player_id: Uuid,
+        message_id: Uuid,
+        emoji: &str,
+    ) -> Result<serde_json::Value, AppError> {
+        validate_emoji(emoji)?;
+        let member = self.require_member(chat_id, player_id).await?;
+        if member.is_silenced() {
+            return Err(AppError::Forbidden);
+        }
+        self.ensure_not_archived(chat_id).await?;
+        let message = self.get_message_in_chat(chat_id, message_id).await?;
+        if message.deleted_at.is_some() {
+            return Err(AppError::Validation(
+                "Can't react to a deleted message".to_string(),
+            ));
         }
 
-        // Update: Add user to participants JSONB array
-        let mut updated_participants = current_participants;
-        updated_participants.push(user_id.to_string());
+        let txn = self.db.begin().await?;
+        ChatMessageReaction::insert(chat_message_reaction::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            message_id: Set(message_id),
+            player_id: Set(player_id),
+            emoji: Set(emoji.to_string()),
+            created_at: Set(Utc::now()),
+        })
+        .on_conflict(
+            sea_query::OnConflict::columns([
+                chat_message_reaction::Column::MessageId,
+                chat_message_reaction::Column::PlayerId,
+                chat_message_reaction::Column::Emoji,
+            ])
+            .do_nothing()
+            .to_owned(),
+        )
+        .do_nothing()
+        .exec(&txn)
+        .await?;
+        let reactions = Self::refresh_reaction_counts(&txn, message_id).await?;
+        txn.commit().await?;
+        Ok(reactions)
+    }
 
-        let mut chat_active: chat::ActiveModel = chat.into();
-        chat_active.participants = Set(serde_json::json!(updated_participants));
-        chat_active.updated_at = Set(chrono::Utc::now().into());
+    pub async fn remove_reaction(
+        &self,
+        chat_id: Uuid,
+        player_id: Uuid,
+        message_id: Uuid,
+        emoji: &str,
+    ) -> Result<serde_json::Value, AppError> {
+        self.require_member(chat_id, player_id).await?;
+        self.get_message_in_chat(chat_id, message_id).await?;
 
-        chat_active.update(&txn).await?;
+        let txn = self.db.begin().await?;
+        ChatMessageReaction::delete_many()
+            .filter(chat_message_reaction::Column::MessageId.eq(message_id))
+            .filter(chat_message_reaction::Column::PlayerId.eq(player_id))
+            .filter(chat_message_reaction::Column::Emoji.eq(emoji))
+            .exec(&txn)
+            .await?;
+        let reactions = Self::refresh_reaction_counts(&txn, message_id).await?;
+        txn.commit().await?;
+        Ok(reactions)
+    }
 
-        //  Audit: Log the join action
-        use crate::models::postgres::activity_log;
-        let audit_log = activity_log::ActiveModel {
+    /// Rewrites `chat_messages.reactions` as `{emoji: count}` from the
+    /// reaction rows, and returns it.
+    async fn refresh_reaction_counts<C: ConnectionTrait>(
+        conn: &C,
+        message_id: Uuid,
+    ) -> Result<serde_json::Value, AppError> {
+        let row = conn
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"UPDATE chat_messages
+                   SET reactions = COALESCE((
+                       SELECT jsonb_object_agg(emoji, total)
+                       FROM (
+                           SELECT emoji, COUNT(*) AS total FROM chat_message_reactions
+                           WHERE message_id = $1
+                           GROUP BY emoji
+                       ) counts
+                   ), '{}'::jsonb)
+                   WHERE id = $1
+                   RETURNING reactions"#,
+                [message_id.into()],
+            ))
+            .await?
+            .ok_or(AppError::NotFound)?;
+        Ok(row.try_get("", "reactions")?)
+    }
+
+    /// Records a file already stored for the chat so a message can link it.
+    pub async fn record_attachment(
+        &self,
+        chat_id: Uuid,
+        uploader_id: Uuid,
+        upload: AttachmentUpload,
+        storage_key: String,
+        url: String,
+    ) -> Result<chat_attachment::Model, AppError> {
+        Ok(chat_attachment::ActiveModel {
             id: Set(Uuid::new_v4()),
-            entity_type: Set("chat".to_string()),
-            entity_id: Set(chat_uuid),
-            actor_id: Set(Some(user_uuid)),
-            action: Set("join_chat".to_string()),
-            details: Set(serde_json::json!({
-                "chat_id": chat_id,
-                "user_id": user_id,
-                "participant_count": updated_participants.len()
-            })),
-            ip_address: Set(None), // TODO: Pass from request context
-            user_agent: Set(None), // TODO: Pass from request context
-            created_at: Set(chrono::Utc::now().into()),
+            chat_id: Set(chat_id),
+            uploaded_by: Set(uploader_id),
+            message_id: Set(None),
+            storage_key: Set(storage_key),
+            url: Set(url),
+            filename: Set(upload.filename),
+            content_type: Set(upload.content_type),
+            size_bytes: Set(upload.size_bytes),
+            sha256: Set(upload.sha256),
+            discarded_at: Set(None),
+            created_at: Set(Utc::now()),
+        }
+        .insert(&self.db)
+        .await?)
+    }
+
+    /// Removes the files of deleted messages from storage, then their rows.
+    /// Files storage refuses to delete are left for the next sweep.
+    pub async fn purge_discarded_attachments(&self, s3: &S3Service) -> Result<usize, AppError> {
+        let discarded = ChatAttachment::find()
+            .filter(chat_attachment::Column::DiscardedAt.is_not_null())
+            .order_by_asc(chat_attachment::Column::DiscardedAt)
+            .limit(ATTACHMENT_PURGE_BATCH)
+            .all(&self.db)
+            .await?;
+
+        let mut removed = Vec::with_capacity(discarded.len());
+        for attachment in discarded {
+            match s3.delete_file(&attachment.storage_key).await {
+                Ok(()) => removed.push(attachment.id),
+                Err(e) => tracing::warn!(
+                    "Failed to delete attachment {} from storage: {}",
+                    attachment.id,
+                    e
+                ),
+            }
+        }
+        if removed.is_empty() {
+            return Ok(0);
+        }
+
+        ChatAttachment::delete_many()
+            .filter(chat_attachment::Column::Id.is_in(removed.iter().copied()))
+            .exec(&self.db)
+            .await?;
+        Ok(removed.len())
+    }
+
+    async fn view_one(&self, message: chat_message::Model) -> Result<ChatMessageView, AppError> {
+        let mut views = self.with_reply_previews(vec![message]).await?;
+        views.pop().ok_or(AppError::NotFound)
+    }
+
+    /// Attaches a quoted preview of the message each one replies to, as it
+    /// reads now, so edits and deletions of the original show up.
+    async fn with_reply_previews(
+        &self,
+        messages: Vec<chat_message::Model>,
+    ) -> Result<Vec<ChatMessageView>, AppError> {
+        let parent_ids: Vec<Uuid> = messages.iter().filter_map(|m| m.reply_to).collect();
+        let parents: HashMap<Uuid, chat_message::Model> = if parent_ids.is_empty() {
+            HashMap::new()
+        } else {
+            ChatMessage::find()
+                .filter(chat_message::Column::Id.is_in(parent_ids))
+                .all(&self.db)
+                .await?
+                .into_iter()
+                .map(|m| (m.id, m))
+                .collect()
         };
 
-        audit_log.insert(&txn).await?;
+        Ok(messages
+            .into_iter()
+            .map(|message| {
+                let reply_preview = message
+                    .reply_to
+                    .and_then(|id| parents.get(&id))
+                    .map(ReplyPreview::of);
+                ChatMessageView {
+                    message,
+                    reply_preview,
+                }
+            })
+            .collect())
+    }
+
+    pub async fn list_members(
+        &self,
+        chat_id: Uuid,
+        viewer_id: Uuid,
+    ) -> Result<Vec<chat_member::Model>, AppError> {
+        self.require_member(chat_id, viewer_id).await?;
+
+        Ok(ChatMember::find()
+            .filter(chat_member::Column::ChatId.eq(chat_id))
+            .filter(chat_member::Column::LeftAt.is_null())
+            .order_by_asc(chat_member::Column::JoinedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Joins a public chat. Private chats are joined by being added.
+    pub async fn join_chat(&self, chat_id: Uuid, player_id: Uuid) -> Result<(), AppError> {
+        let txn = self.db.begin().await?;
+
+        let chat = chat::Entity::find_by_id(chat_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        if chat.is_private {
+            return Err(AppError::Forbidden);
+        }
+
+        let member_count =
+            Self::add_membership(&txn, chat_id, player_id, ChatMemberRole::Member).await?;
+        if member_count > chat.max_participants as u64 {
+            return Err(AppError::Validation(
+                "Chat is at maximum capacity".to_string(),
+            ));
+        }
+
+        Self::log_activity(
+            &txn,
+            chat_id,
+            player_id,
+            "join_chat",
+            serde_json::json!({ "member_count": member_count }),
+        )
+        .await?;
 
-        //  Commit: Atomic operation
         txn.commit().await?;
 
         tracing::info!(
-            "User {} successfully joined chat {} (participants: {})",
-            user_id,
+            "User {} joined chat {} (members: {})",
+            player_id,
             chat_id,
-            updated_participants.len()
+            member_count
         );
+        Ok(())
+    }
 
+    /// Owners hand over ownership before leaving.
+    pub async fn leave_chat(&self, chat_id: Uuid, player_id: Uuid) -> Result<(), AppError> {
+        let member = self.require_member(chat_id, player_id).await?;
+        self.ensure_manual_membership(chat_id).await?;
+        if self.chat_type(chat_id).await?.as_deref() == Some(DIRECT_CHAT) {
+            return Err(AppError::Validation(
+                "Direct conversations can't be left".to_string(),
+            ));
+        }
+        if member.role == ChatMemberRole::Owner {
+            return Err(AppError::Validation(
+                "Transfer ownership before leaving the chat".to_string(),
+            ));
+        }
+
+        let txn = self.db.begin().await?;
+        Self::end_membership(&txn, member).await?;
+        Self::log_activity(
+            &txn,
+            chat_id,
+            player_id,
+            "leave_chat",
+            serde_json::json!({}),
+        )
+        .await?;
+        txn.commit().await?;
         Ok(())
     }
+
+    /// Adds a player to the chat on behalf of an owner or admin.
+    pub async fn add_member(
+        &self,
+        chat_id: Uuid,
+        actor_id: Uuid,
+        player_id: Uuid,
+    ) -> Result<chat_member::Model, AppError> {
+        let actor = self.require_member(chat_id, actor_id).await?;
+        if !actor.role.can_moderate() {
+            return Err(AppError::Forbidden);
+        }
+        self.ensure_manual_membership(chat_id).await?;
+        if Player::find_by_id(player_id).one(&self.db).await?.is_none() {
+            return Err(AppError::NotFound);
+        }
+        if self.chat_type(chat_id).await?.as_deref() == Some(GROUP_DM_CHAT) {
+            DirectMessageService::ensure_can_message(&self.db, actor_id, player_id).await?;
+        }
+
+        let txn = self.db.begin().await?;
+        let chat = chat::Entity::find_by_id(chat_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        let member_count =
+            Self::add_membership(&txn, chat_id, player_id, ChatMemberRole::Member).await?;
+        if member_count > chat.max_participants as u64 {
+            return Err(AppError::Validation(
+                "Chat is at maximum capacity".to_string(),
+            ));
+        }
+
+        Self::log_activity(
+            &txn,
+            chat_id,
+            actor_id,
+            "add_chat_member",
+            serde_json::json!({ "player_id": player_id, "member_count": member_count }),
+        )
+        .await?;
+        txn.commit().await?;
+
+        self.get_membership(chat_id, player_id)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    pub async fn remove_member(
+        &self,
+        chat_id: Uuid,
+        actor_id: Uuid,
+        player_id: Uuid,
+    ) -> Result<(), AppError> {
+        let (_, target) = self.moderate(chat_id, actor_id, player_id).await?;
+        self.ensure_manual_membership(chat_id).await?;
+
+        let txn = self.db.begin().await?;
+        Self::end_membership(&txn, target).await?;
+        Self::log_activity(
+            &txn,
+            chat_id,
+            actor_id,
+            "remove_chat_member",
+            serde_json::json!({ "player_id": player_id }),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(())
+    }
+
+    /// Only the owner changes roles. Making someone else owner hands over
+    /// ownership and leaves the previous owner an admin.
+    pub async fn set_member_role(
+        &self,
+        chat_id: Uuid,
+        actor_id: Uuid,
+        player_id: Uuid,
+        role: ChatMemberRole,
+    ) -> Result<chat_member::Model, AppError> {
+        let (actor, target) = self.moderate(chat_id, actor_id, player_id).await?;
+        if actor.role != ChatMemberRole::Owner {
+            return Err(AppError::Forbidden);
+        }
+        if role == ChatMemberRole::Owner {
+            self.ensure_manual_membership(chat_id).await?;
+        }
+
+        let now = Utc::now();
+        let txn = self.db.begin().await?;
+
+        if role == ChatMemberRole::Owner {
+            let mut demoted: chat_member::ActiveModel = actor.into();
+            demoted.role = Set(ChatMemberRole::Admin);
+            demoted.updated_at = Set(now);
+            demoted.update(&txn).await?;
+        }
+
+        let mut update: chat_member::ActiveModel = target.into();
+        update.role = Set(role);
+        if role == ChatMemberRole::Owner {
+            // Nobody outranks the owner to lift a mute later
+            update.is_muted = Set(false);
+            update.muted_until = Set(None);
+        }
+        update.updated_at = Set(now);
+        let updated = update.update(&txn).await?;
+
+        Self::log_activity(
+            &txn,
+            chat_id,
+            actor_id,
+            "set_chat_member_role",
+            serde_json::json!({ "player_id": player_id, "role": role }),
+        )
+        .await?;
+        txn.commit().await?;
+        Ok(updated)
+    }
+
+    /// Mutes a member until `until`, or until unmuted when it's `None`.
+    pub async fn set_member_muted(
+        &self,
+        chat_id: Uuid,
+        actor_id: Uuid,
+        player_id: Uuid,
+        muted: bool,
+        until: Option<DateTime<Utc>>,
+    ) -> Result<chat_member::Model, AppError> {
+        if muted && until.is_some_and(|until| until <= Utc::now()) {
+            return Err(AppError::Validation(
+                "Mute end must be in the future".to_string(),
+            ));
+        }
+        let (_, target) = self.moderate(chat_id, actor_id, player_id).await?;
+
+        let mut update: chat_member::ActiveModel = target.into();
+        update.is_muted = Set(muted);
+        update.muted_until = Set(if muted { until } else { None });
+        update.updated_at = Set(Utc::now());
+        Ok(update.update(&self.db).await?)
+    }
+
+    /// Moves the member's read pointer forward to `message_id`. Older messages
+    /// leave it where it is, so out-of-order acks from several devices are fine.
+    pub async fn mark_read(
+        &self,
+        chat_id: Uuid,
+        player_id: Uuid,
+        message_id: Uuid,
+    ) -> Result<chat_member::Model, AppError> {
+        let member = self.require_member(chat_id, player_id).await?;
+        let message = ChatMessage::find_by_id(message_id)
+            .filter(chat_message::Column::ChatId.eq(chat_id))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        if let Some(current) = member.last_read_message_id {
+            let current = ChatMessage::find_by_id(current).one(&self.db).await?;
+            if current.is_some_and(|current| current.created_at >= message.created_at) {
+                return Ok(member);
+            }
+        }
+
+        let now = Utc::now();
+        let mut update: chat_member::ActiveModel = member.into();
+        update.last_read_message_id = Set(Some(message.id));
+        update.last_read_at = Set(Some(now));
+        update.updated_at = Set(now);
+        Ok(update.update(&self.db).await?)
+    }
+
+    async fn chat_type(&self, chat_id: Uuid) -> Result<Option<String>, AppError> {
+        Ok(chat::Entity::find_by_id(chat_id)
+            .select_only()
+            .column(chat::Column::ChatType)
+            .into_tuple()
+            .one(&self.db)
+            .await?)
+    }
+
+    async fn find_chat(&self, chat_id: Uuid) -> Result<chat::Model, AppError> {
+        chat::Entity::find_by_id(chat_id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Archived chats keep their history readable but take no new activity.
+    async fn ensure_not_archived(&self, chat_id: Uuid) -> Result<(), AppError> {
+        if self.find_chat(chat_id).await?.archived_at.is_some() {
+            return Err(AppError::Validation("This chat is archived".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Members and owners of provisioned chats follow the rosters, so they
+    /// aren't changed by hand.
+    async fn ensure_manual_membership(&self, chat_id: Uuid) -> Result<(), AppError> {
+        if self.find_chat(chat_id).await?.is_provisioned() {
+            return Err(AppError::Validation(
+                "Members of this chat follow the team roster".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Messages in a 1:1 conversation stop once either side blocks the other.
+    async fn ensure_not_blocked(&self, chat_id: Uuid, sender_id: Uuid) -> Result<(), AppError> {
+        if self.chat_type(chat_id).await?.as_deref() != Some(DIRECT_CHAT) {
+            return Ok(());
+        }
+        let other: Option<Uuid> = ChatMember::find()
+            .select_only()
+            .column(chat_member::Column::PlayerId)
+            .filter(chat_member::Column::ChatId.eq(chat_id))
+            .filter(chat_member::Column::PlayerId.ne(sender_id))
+            .into_tuple()
+            .one(&self.db)
+            .await?;
+        match other {
+            Some(other) if DirectMessageService::is_blocked(&self.db, sender_id, other).await? => {
+                Err(AppError::Forbidden)
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// The actor's and target's memberships, when the actor may moderate the target.
+    async fn moderate(
+        &self,
+        chat_id: Uuid,
+        actor_id: Uuid,
+        player_id: Uuid,
+    ) -> Result<(chat_member::Model, chat_member::Model), AppError> {
+        let actor = self.require_member(chat_id, actor_id).await?;
+        let target = self
+            .get_membership(chat_id, player_id)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        if actor.id == target.id || !actor.role.outranks(target.role) {
+            return Err(AppError::Forbidden);
+        }
+        Ok((actor, target))
+    }
+
+    /// Adds or re-activates a membership and returns the chat's member count.
+    /// Joining again after leaving starts over with a fresh role and mute state.
+    pub(crate) async fn add_membership<C: ConnectionTrait>(
+        conn: &C,
+        chat_id: Uuid,
+        player_id: Uuid,
+        role: ChatMemberRole,
+    ) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let existing = ChatMember::find()
+            .filter(chat_member::Column::ChatId.eq(chat_id))
+            .filter(chat_member::Column::PlayerId.eq(player_id))
+            .one(conn)
+            .await?;
+
+        match existing {
+            Some(member) if member.left_at.is_none() => {}
+            Some(member) => {
+                let mut update: chat_member::ActiveModel = member.into();
+                update.role = Set(role);
+                update.is_muted = Set(false);
+                update.muted_until = Set(None);
+                update.joined_at = Set(now);
+                update.left_at = Set(None);
+                update.updated_at = Set(now);
+                update.update(conn).await?;
+            }
+            None => {
+                chat_member::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    chat_id: Set(chat_id),
+                    player_id: Set(player_id),
+                    role: Set(role),
+                    is_muted: Set(false),
+                    muted_until: Set(None),
+                    joined_at: Set(now),
+                    left_at: Set(None),
+                    last_read_message_id: Set(None),
+                    last_read_at: Set(None),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(conn)
+                .await?;
+            }
+        }
+
+        Ok(ChatMember::find()
+            .filter(chat_member::Column::ChatId.eq(chat_id))
+            .filter(chat_member::Column::LeftAt.is_null())
+            .count(conn)
+            .await?)
+    }
+
+    async fn end_membership<C: ConnectionTrait>(
+        conn: &C,
+        member: chat_member::Model,
+    ) -> Result<(), AppError> {
+        let now = Utc::now();
+        let mut update: chat_member::ActiveModel = member.into();
+        update.left_at = Set(Some(now));
+        update.updated_at = Set(now);
+        update.update(conn).await?;
+        Ok(())
+    }
+
+    /// Records chat activity in the outbox with the change it describes; the
+    /// activity log subscriber writes it to `activity_logs`.
+    async fn log_activity<C: ConnectionTrait>(
+        conn: &C,
+        chat_id: Uuid,
+        actor_id: Uuid,
+        action: &str,
+        details: serde_json::Value,
+    ) -> Result<(), AppError> {
+        OutboxService::record(
+            conn,
+            &DomainEvent::ChatActivity {
+                chat_id,
+                actor_id,
+                action: action.to_string(),
+                details,
+                occurred_at: Utc::now(),
+            },
+        )
+        .await
+    }
 }
@@ -1,16 +1,285 @@
-use crate::models::postgres::{community, community_member, community_post};
-use anyhow::Result;
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use crate::models::enums::{CommunityPostType, ModeratedContent};
+use crate::models::postgres::{
+    community, community_member, community_post, community_vote, post_comment, CommunityPost,
+    CommunityVote, PostComment,
+};
+use crate::services::moderation_service::{
+    ModerationService, QueuedContent, Screening, Submission,
+};
+use crate::utils::errors::AppError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
 use uuid::Uuid;
 
+const MAX_POST_TITLE_CHARS: usize = 200;
+const MAX_POST_BODY_CHARS: usize = 40_000;
+const MAX_POST_TAGS: usize = 10;
+const MAX_TAG_CHARS: usize = 30;
+const MAX_POST_ATTACHMENTS: usize = 10;
+const MAX_COMMENT_CHARS: usize = 10_000;
+const DEFAULT_FEED_PAGE_SIZE: u64 = 25;
+const MAX_FEED_PAGE_SIZE: u64 = 100;
+// A player's views of a post add to its view count once per window
+const VIEW_WINDOW_SECS: u64 = 24 * 60 * 60;
+// Replies deeper than this are refused; threads that long stop being threads
+const MAX_COMMENT_DEPTH: i16 = 8;
+const DEFAULT_COMMENT_PAGE_SIZE: u64 = 50;
+const MAX_COMMENT_PAGE_SIZE: u64 = 100;
+// A comment page carries replies this many levels down, up to a total count;
+// clients fetch deeper or further replies by parent
+const NESTED_REPLY_LEVELS: i32 = 3;
+const MAX_NESTED_REPLIES: u64 = 200;
+
+/// Markdown body and metadata of a new post.
+pub struct NewPost {
+    pub title: String,
+    pub body: String,
+    pub post_type: CommunityPostType,
+    pub tags: Vec<String>,
+    pub attachments: Vec<PostAttachment>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostAttachment {
+    pub url: String,
+    pub content_type: Option<String>,
+    pub name: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct PostView {
+    #[serde(flatten)]
+    pub post: community_post::Model,
+    pub my_vote: i16,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentNode {
+    #[serde(flatten)]
+    pub comment: post_comment::Model,
+    pub my_vote: i16,
+    pub replies: Vec<CommentNode>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct VoteTally {
+    pub upvotes: i32,
+    pub downvotes: i32,
+    pub my_vote: i16,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CommentSort {
+    #[default]
+    Top,
+    New,
+    Old,
+}
+
+impl CommentSort {
+    fn order_by(&self) -> &'static str {
+        match self {
+            CommentSort::Top => "(upvotes - downvotes) DESC, created_at ASC, id",
+            CommentSort::New => "created_at DESC, id",
+            CommentSort::Old => "created_at ASC, id",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedSort {
+    #[default]
+    Hot,
+    New,
+    Top,
+    Comments, // most commented
+}
+
+impl FeedSort {
+    fn key(&self, alias: &str) -> String {
+        match self {
+            FeedSort::Hot => format!("{alias}.hot_score"),
+            FeedSort::New => format!("{alias}.created_at"),
+            FeedSort::Top => format!("({alias}.upvotes - {alias}.downvotes)"),
+            FeedSort::Comments => format!("{alias}.comment_count"),
+        }
+    }
+
+    fn cursor_key(&self, post: &community_post::Model) -> SortKey {
+        match self {
+            FeedSort::Hot => SortKey::Hot(post.hot_score.to_bits()),
+            FeedSort::New => SortKey::New(post.created_at),
+            FeedSort::Top => SortKey::Top(post.upvotes - post.downvotes),
+            FeedSort::Comments => SortKey::Comments(post.comment_count),
+        }
+    }
+
+    /// The cursor's key as a query value, if the cursor came from this sort.
+    fn key_value(&self, key: &SortKey) -> Option<Value> {
+        match (self, key) {
+            (FeedSort::Hot, SortKey::Hot(bits)) => Some(f64::from_bits(*bits).into()),
+            (FeedSort::New, SortKey::New(at)) => Some((*at).into()),
+            (FeedSort::Top, SortKey::Top(score)) => Some((*score).into()),
+            (FeedSort::Comments, SortKey::Comments(count)) => Some((*count).into()),
+            _ => None,
+        }
+    }
+
+    /// How far back the sort looks, in seconds. Hot and new see every post.
+    fn window(&self, period: FeedPeriod) -> Option<f64> {
+        let days = match (self, period) {
+            (FeedSort::Hot | FeedSort::New, _) | (_, FeedPeriod::All) => return None,
+            (_, FeedPeriod::Day) => 1,
+            (_, FeedPeriod::Week) => 7,
+            (_, FeedPeriod::Month) => 30,
+            (_, FeedPeriod::Year) => 365,
+        };
+        Some((days * 86_400) as f64)
+    }
+}
+
+/// Period for the top and most-commented sorts.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedPeriod {
+    Day,
+    #[default]
+    Week,
+    Month,
+    Year,
+    All,
+}
+
+/// `after` is the `next_cursor` of the previous page.
+#[derive(Debug, Default, Deserialize)]
+pub struct FeedQuery {
+    #[serde(default)]
+    pub sort: FeedSort,
+    pub period: Option<FeedPeriod>,
+    pub after: Option<String>,
+    pub limit: Option<u64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedPage {
+    pub posts: Vec<PostView>,
+    pub next_cursor: Option<String>,
+}
+
+/// Where a page ended: the last post's position in the sort order as it was
+/// served, so later votes or edits don't move the next page.
+#[derive(Serialize, Deserialize)]
+struct FeedCursor {
+    pinned: bool,
+    key: SortKey,
+    id: Uuid,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum SortKey {
+    Hot(u64), // bits of the score; JSON floats don't round-trip exactly
+    New(DateTime<Utc>),
+    Top(i32),
+    Comments(i32),
+}
+
+impl FeedCursor {
+    fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    fn decode(cursor: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+#[derive(Clone, Copy)]
+enum FeedScope {
+    Community(Uuid),
+    Home(Uuid), // communities the player belongs to
+}
+
+/// One page of a thread: top-level comments, or replies to `parent_id`.
+#[derive(Debug, Default, Deserialize)]
+pub struct CommentPage {
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub sort: CommentSort,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Standing {
+    Member,
+    Moderator,
+}
+
+#[derive(Clone, Copy)]
+enum VoteTarget {
+    Post(Uuid),
+    Comment(Uuid),
+}
+
+fn validate_tags(tags: Vec<String>) -> Result<Vec<String>, String> {
+    let mut normalized: Vec<String> = Vec::new();
+    for tag in tags {
+        let tag = tag.trim().trim_start_matches('#').to_lowercase();
+        if tag.is_empty() || normalized.contains(&tag) {
+            continue;
+        }
+        if tag.chars().count() > MAX_TAG_CHARS
+            || !tag
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+        {
+            return Err(format!(
+                "Tags are up to {} letters, digits, '-' or '_'",
+                MAX_TAG_CHARS
+            ));
+        }
+        normalized.push(tag);
+    }
+    if normalized.len() > MAX_POST_TAGS {
+        return Err(format!("At most {} tags per post", MAX_POST_TAGS));
+    }
+    Ok(normalized)
+}
+
+fn validate_attachments(attachments: &[PostAttachment]) -> Result<(), String> {
+    if attachments.len() > MAX_POST_ATTACHMENTS {
+        return Err(format!(
+            "At most {} attachments per post",
+            MAX_POST_ATTACHMENTS
+        ));
+    }
+    if attachments
+        .iter()
+        .any(|a| !(a.url.starts_with("https://") || a.url.starts_with("http://")))
+    {
+        return Err("Attachment URLs must be http or https".to_string());
+    }
+    Ok(())
+}
+
 #[derive(Clone)]
 pub struct CommunityService {
     db: DatabaseConnection,
+    moderation: ModerationService,
 }
 
 impl CommunityService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, moderation: ModerationService) -> Self {
+        Self { db, moderation }
     }
 
     pub async fn create_community(
@@ -19,7 +288,7 @@ impl CommunityService {
         description: String,
         community_type: String,
         owner: String,
-    ) -> Result<String> {
+    ) -> Result<String, AppError> {
         let community_id = Uuid::new_v4();
         let owner_uuid = Uuid::parse_str(&owner)?;
 
@@ -38,50 +307,641 @@ impl CommunityService {
         Ok(community_id.to_string())
     }
 
-    pub async fn get_community(&self, community_id: &str) -> Result<Option<community::Model>> {
+    pub async fn get_community(
+        &self,
+        community_id: &str,
+    ) -> Result<Option<community::Model>, AppError> {
         let community_uuid = Uuid::parse_str(community_id)?;
         Ok(community::Entity::find_by_id(community_uuid)
             .one(&self.db)
             .await?)
     }
 
-    pub async fn add_post_to_community(
+    // ========================================
+    // POSTS
+    // ========================================
+
+    /// Members post; announcements are for the owner and moderators.
+    pub async fn create_post(
         &self,
-        community_id: String,
-        post_id: String,
-        pinned: bool,
-        added_by: String,
-    ) -> Result<String> {
-        let post_uuid = Uuid::new_v4();
-        let community_uuid = Uuid::parse_str(&community_id)?;
-        let author_uuid = Uuid::parse_str(&added_by)?;
+        community_id: Uuid,
+        author_id: Uuid,
+        new_post: NewPost,
+    ) -> Result<PostView, AppError> {
+        let community = self.find_community(community_id).await?;
+        let standing = self
+            .standing(&community, author_id)
+            .await?
+            .ok_or(AppError::Forbidden)?;
 
-        let community_post = community_post::ActiveModel {
-            id: Set(post_uuid),
-            community_id: Set(community_uuid),
-            author_id: Set(author_uuid),
-            title: Set(Some("Post".to_string())),
-            content: Set(post_id),
-            pinned: Set(pinned),
+        let NewPost {
+            title,
+            body,
+            post_type,
+            tags,
+            attachments,
+        } = new_post;
+        let title = title.trim().to_string();
+        if title.is_empty() || title.chars().count() > MAX_POST_TITLE_CHARS {
+            return Err(AppError::Validation(format!(
+                "Titles are 1 to {} characters",
+                MAX_POST_TITLE_CHARS
+            )));
+        }
+        if body.chars().count() > MAX_POST_BODY_CHARS {
+            return Err(AppError::Validation(format!(
+                "Posts are limited to {} characters",
+                MAX_POST_BODY_CHARS
+            )));
+        }
+        let tags = validate_tags(tags).map_err(AppError::Validation)?;
+        validate_attachments(&attachments).map_err(AppError::Validation)?;
+        match post_type {
+            CommunityPostType::Poll | CommunityPostType::Event => {
+                return Err(AppError::Validation(
+                    "Poll and event posts aren't supported yet".to_string(),
+                ));
+            }
+            CommunityPostType::Image | CommunityPostType::Video if attachments.is_empty() => {
+                return Err(AppError::Validation(
+                    "Image and video posts need an attachment".to_string(),
+                ));
+            }
+            CommunityPostType::Text if body.trim().is_empty() => {
+                return Err(AppError::Validation("Text posts need a body".to_string()));
+            }
+            CommunityPostType::Announcement if standing != Standing::Moderator => {
+                return Err(AppError::Forbidden);
+            }
+            _ => {}
+        }
+
+        // The spam stage matches duplicates against title and content joined
+        // the same way
+        let text = format!("{}\n{}", title, body);
+        let screening = self
+            .moderation
+            .screen(&Submission {
+                content_type: ModeratedContent::CommunityPost,
+                author_id,
+                text: &text,
+                edit_of: None,
+            })
+            .await?;
+
+        let post = community_post::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            community_id: Set(community_id),
+            author_id: Set(author_id),
+            title: Set(Some(title)),
+            content: Set(body),
+            post_type: Set(post_type),
+            tags: Set(tags),
+            attachments: Set(serde_json::to_value(&attachments)?),
             ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+
+        self.queue_flags(
+            screening,
+            ModeratedContent::CommunityPost,
+            post.id,
+            author_id,
+            &text,
+        )
+        .await;
+        Ok(PostView { post, my_vote: 0 })
+    }
+
+    pub async fn get_post(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        viewer_id: Uuid,
+    ) -> Result<PostView, AppError> {
+        let community = self.find_community(community_id).await?;
+        self.ensure_can_view(&community, viewer_id).await?;
+        let post = self.find_post(community_id, post_id).await?;
+        let my_vote = self
+            .my_votes_on_posts(viewer_id, &[post_id])
+            .await?
+            .remove(&post_id)
+            .unwrap_or(0);
+
+        Ok(PostView { post, my_vote })
+    }
+
+    /// Opens a post, counting the view once per viewer per window.
+    pub async fn view_post(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        viewer_id: Uuid,
+    ) -> Result<PostView, AppError> {
+        let mut view = self.get_post(community_id, post_id, viewer_id).await?;
+        let counted = self
+            .db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"WITH counted AS (
+                       INSERT INTO community_post_views (post_id, player_id) VALUES ($1, $2)
+                       ON CONFLICT (post_id, player_id) DO UPDATE SET viewed_at = NOW()
+                       WHERE community_post_views.viewed_at <= NOW() - make_interval(secs => $3)
+                       RETURNING post_id
+                   )
+                   UPDATE community_posts SET view_count = view_count + 1
+                   WHERE id IN (SELECT post_id FROM counted)"#,
+                [
+                    post_id.into(),
+                    viewer_id.into(),
+                    (VIEW_WINDOW_SECS as f64).into(),
+                ],
+            ))
+            .await?
+            .rows_affected()
+            > 0;
+        if counted {
+            view.post.view_count += 1;
+        }
+
+        Ok(view)
+    }
+
+    /// Authors delete their own posts; the owner and moderators anyone's.
+    pub async fn delete_post(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        actor_id: Uuid,
+    ) -> Result<(), AppError> {
+        let community = self.find_community(community_id).await?;
+        let post = self.find_post(community_id, post_id).await?;
+        if post.author_id != actor_id
+            && self.standing(&community, actor_id).await? != Some(Standing::Moderator)
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        Self::remove_post(&self.db, post_id).await
+    }
+
+    /// Soft-deletes a post, keeping its comments and votes.
+    pub async fn remove_post<C: ConnectionTrait>(conn: &C, post_id: Uuid) -> Result<(), AppError> {
+        CommunityPost::update_many()
+            .col_expr(
+                community_post::Column::DeletedAt,
+                Expr::value(Some(Utc::now())),
+            )
+            .filter(community_post::Column::Id.eq(post_id))
+            .filter(community_post::Column::DeletedAt.is_null())
+            .exec(conn)
+            .await?;
+        Ok(())
+    }
+
+    /// `value` is 1, -1, or 0 to take the vote back.
+    pub async fn vote_post(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        player_id: Uuid,
+        value: i16,
+    ) -> Result<VoteTally, AppError> {
+        let community = self.find_community(community_id).await?;
+        self.standing(&community, player_id)
+            .await?
+            .ok_or(AppError::Forbidden)?;
+        self.find_post(community_id, post_id).await?;
+
+        self.set_vote(player_id, VoteTarget::Post(post_id), value)
+            .await?;
+        let post = self.find_post(community_id, post_id).await?;
+        Ok(VoteTally {
+            upvotes: post.upvotes,
+            downvotes: post.downvotes,
+            my_vote: value,
+        })
+    }
+
+    // ========================================
+    // FEEDS
+    // ========================================
+
+    /// A community's feed, pinned posts first.
+    pub async fn community_feed(
+        &self,
+        community_id: Uuid,
+        viewer_id: Uuid,
+        query: FeedQuery,
+    ) -> Result<FeedPage, AppError> {
+        let community = self.find_community(community_id).await?;
+        self.ensure_can_view(&community, viewer_id).await?;
+        self.feed(FeedScope::Community(community_id), viewer_id, query)
+            .await
+    }
+
+    /// Posts from every community the player belongs to. Pins only apply
+    /// within their own community, so they aren't lifted here.
+    pub async fn home_feed(&self, player_id: Uuid, query: FeedQuery) -> Result<FeedPage, AppError> {
+        self.feed(FeedScope::Home(player_id), player_id, query)
+            .await
+    }
+
+    async fn feed(
+        &self,
+        scope: FeedScope,
+        viewer_id: Uuid,
+        query: FeedQuery,
+    ) -> Result<FeedPage, AppError> {
+        let limit = query
+            .limit
+            .unwrap_or(DEFAULT_FEED_PAGE_SIZE)
+            .clamp(1, MAX_FEED_PAGE_SIZE);
+        let (scope_id, scope_filter, pinned_first) = match scope {
+            FeedScope::Community(community_id) => {
+                (community_id, "p.community_id = $1".to_string(), true)
+            }
+            FeedScope::Home(player_id) => (
+                player_id,
+                r#"p.community_id IN (
+                       SELECT community_id FROM community_members
+                       WHERE player_id = $1 AND (role <> 'banned' OR banned_until <= NOW())
+                       UNION SELECT id FROM communities WHERE owner_id = $1
+                   )"#
+                .to_string(),
+                false,
+            ),
         };
+        // Posts are ordered by these, all descending; a cursor continues
+        // below the position it recorded
+        let mut sort_columns = Vec::new();
+        if pinned_first {
+            sort_columns.push("p.pinned".to_string());
+        }
+        sort_columns.push(query.sort.key("p"));
+        sort_columns.push("p.id".to_string());
 
-        community_post.insert(&self.db).await?;
-        Ok(post_uuid.to_string())
+        let mut values: Vec<Value> = vec![scope_id.into(), ((limit + 1) as i64).into()];
+        let mut filters = vec![scope_filter, "p.deleted_at IS NULL".to_string()];
+        if let Some(secs) = query.sort.window(query.period.unwrap_or_default()) {
+            values.push(secs.into());
+            filters.push(format!(
+                "p.created_at > NOW() - make_interval(secs => ${})",
+                values.len()
+            ));
+        }
+        if let Some(after) = query.after.as_deref() {
+            let cursor = FeedCursor::decode(after);
+            let key = cursor
+                .as_ref()
+                .and_then(|cursor| query.sort.key_value(&cursor.key));
+            let (Some(cursor), Some(key)) = (cursor, key) else {
+                return Err(AppError::Validation(
+                    "Cursor must come from this feed and sort".to_string(),
+                ));
+            };
+            let mut bounds = Vec::new();
+            if pinned_first {
+                values.push(cursor.pinned.into());
+                bounds.push(format!("${}", values.len()));
+            }
+            values.push(key);
+            bounds.push(format!("${}", values.len()));
+            values.push(cursor.id.into());
+            bounds.push(format!("${}", values.len()));
+            filters.push(format!(
+                "({}) < ({})",
+                sort_columns.join(", "),
+                bounds.join(", ")
+            ));
+        }
+
+        let mut posts = CommunityPost::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    "SELECT p.* FROM community_posts p WHERE {} ORDER BY {} LIMIT $2",
+                    filters.join(" AND "),
+                    sort_columns
+                        .iter()
+                        .map(|column| format!("{column} DESC"))
+                        .collect::<Vec<_>>()
+                        .join(", ")
+                ),
+                values,
+            ))
+            .all(&self.db)
+            .await?;
+        let has_more = posts.len() as u64 > limit;
+        posts.truncate(limit as usize);
+
+        let next_cursor = posts.last().filter(|_| has_more).map(|post| {
+            FeedCursor {
+                pinned: post.pinned,
+                key: query.sort.cursor_key(post),
+                id: post.id,
+            }
+            .encode()
+        });
+
+        let ids: Vec<Uuid> = posts.iter().map(|p| p.id).collect();
+        let votes = self.my_votes_on_posts(viewer_id, &ids).await?;
+        Ok(FeedPage {
+            next_cursor,
+            posts: posts
+                .into_iter()
+                .map(|post| PostView {
+                    my_vote: votes.get(&post.id).copied().unwrap_or(0),
+                    post,
+                })
+                .collect(),
+        })
     }
 
-    pub async fn get_community_posts(
+    // ========================================
+    // COMMENTS
+    // ========================================
+
+    /// Comments on a post, or replies to `parent_id`. Locked posts take no
+    /// new comments.
+    pub async fn create_comment(
         &self,
-        community_id: &str,
-    ) -> Result<Vec<community_post::Model>> {
-        let community_uuid = Uuid::parse_str(community_id)?;
-        Ok(community_post::Entity::find()
-            .filter(community_post::Column::CommunityId.eq(community_uuid))
+        community_id: Uuid,
+        post_id: Uuid,
+        author_id: Uuid,
+        parent_id: Option<Uuid>,
+        body: String,
+    ) -> Result<post_comment::Model, AppError> {
+        let community = self.find_community(community_id).await?;
+        self.standing(&community, author_id)
+            .await?
+            .ok_or(AppError::Forbidden)?;
+        let post = self.find_post(community_id, post_id).await?;
+        if post.locked {
+            return Err(AppError::Validation("This post is locked".to_string()));
+        }
+
+        let body = body.trim().to_string();
+        if body.is_empty() || body.chars().count() > MAX_COMMENT_CHARS {
+            return Err(AppError::Validation(format!(
+                "Comments are 1 to {} characters",
+                MAX_COMMENT_CHARS
+            )));
+        }
+
+        let depth = match parent_id {
+            Some(parent_id) => {
+                let parent = self.find_comment(post_id, parent_id).await?;
+                if parent.deleted_at.is_some() {
+                    return Err(AppError::Validation(
+                        "Can't reply to a deleted comment".to_string(),
+                    ));
+                }
+                if parent.depth >= MAX_COMMENT_DEPTH {
+                    return Err(AppError::Validation(
+                        "This thread is too deep to reply to".to_string(),
+                    ));
+                }
+                parent.depth + 1
+            }
+            None => 0,
+        };
+
+        let screening = self
+            .moderation
+            .screen(&Submission {
+                content_type: ModeratedContent::PostComment,
+                author_id,
+                text: &body,
+                edit_of: None,
+            })
+            .await?;
+
+        let comment = post_comment::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            post_id: Set(post_id),
+            author_id: Set(author_id),
+            parent_id: Set(parent_id),
+            content: Set(body),
+            depth: Set(depth),
+            ..Default::default()
+        }
+        .insert(&self.db)
+        .await?;
+
+        self.queue_flags(
+            screening,
+            ModeratedContent::PostComment,
+            comment.id,
+            author_id,
+            &comment.content,
+        )
+        .await;
+        Ok(comment)
+    }
+
+    /// A page of comments under `parent_id` (top-level without one), each
+    /// carrying its first few levels of replies. Deleted comments only show
+    /// while they still have replies.
+    pub async fn list_comments(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        viewer_id: Uuid,
+        page: CommentPage,
+    ) -> Result<Vec<CommentNode>, AppError> {
+        let CommentPage {
+            parent_id,
+            sort,
+            limit,
+            offset,
+        } = page;
+        let community = self.find_community(community_id).await?;
+        self.ensure_can_view(&community, viewer_id).await?;
+        self.find_post(community_id, post_id).await?;
+        if let Some(parent_id) = parent_id {
+            self.find_comment(post_id, parent_id).await?;
+        }
+
+        let comments = PostComment::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"SELECT * FROM post_comments
+                       WHERE post_id = $1 AND parent_id IS NOT DISTINCT FROM $2
+                         AND (deleted_at IS NULL OR reply_count > 0)
+                       ORDER BY {}
+                       LIMIT $3 OFFSET $4"#,
+                    sort.order_by()
+                ),
+                [
+                    post_id.into(),
+                    parent_id.into(),
+                    (limit
+                        .unwrap_or(DEFAULT_COMMENT_PAGE_SIZE)
+                        .clamp(1, MAX_COMMENT_PAGE_SIZE) as i64)
+                        .into(),
+                    (offset.unwrap_or(0) as i64).into(),
+                ],
+            ))
             .all(&self.db)
-            .await?)
+            .await?;
+        if comments.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let page_ids: Vec<Uuid> = comments.iter().map(|c| c.id).collect();
+        let replies = PostComment::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"WITH RECURSIVE thread AS (
+                           SELECT c.*, 1 AS level FROM post_comments c WHERE c.parent_id = ANY($1)
+                           UNION ALL
+                           SELECT c.*, t.level + 1 FROM post_comments c
+                           JOIN thread t ON c.parent_id = t.id
+                           WHERE t.level < $2
+                       )
+                       SELECT * FROM thread
+                       WHERE deleted_at IS NULL OR reply_count > 0
+                       ORDER BY level, {}
+                       LIMIT $3"#,
+                    sort.order_by()
+                ),
+                [
+                    page_ids.clone().into(),
+                    NESTED_REPLY_LEVELS.into(),
+                    (MAX_NESTED_REPLIES as i64).into(),
+                ],
+            ))
+            .all(&self.db)
+            .await?;
+
+        let mut all_ids = page_ids;
+        all_ids.extend(replies.iter().map(|c| c.id));
+        let votes = self.my_votes_on_comments(viewer_id, &all_ids).await?;
+
+        let mut children: HashMap<Uuid, Vec<post_comment::Model>> = HashMap::new();
+        for reply in replies {
+            if let Some(parent) = reply.parent_id {
+                children.entry(parent).or_default().push(reply);
+            }
+        }
+        Ok(comments
+            .into_iter()
+            .map(|comment| Self::build_node(comment, &mut children, &votes))
+            .collect())
+    }
+
+    fn build_node(
+        comment: post_comment::Model,
+        children: &mut HashMap<Uuid, Vec<post_comment::Model>>,
+        votes: &HashMap<Uuid, i16>,
+    ) -> CommentNode {
+        let replies = children
+            .remove(&comment.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|reply| Self::build_node(reply, children, votes))
+            .collect();
+        CommentNode {
+            my_vote: votes.get(&comment.id).copied().unwrap_or(0),
+            comment,
+            replies,
+        }
+    }
+
+    /// A live comment, for reporting.
+    pub async fn get_comment(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        comment_id: Uuid,
+        viewer_id: Uuid,
+    ) -> Result<post_comment::Model, AppError> {
+        let community = self.find_community(community_id).await?;
+        self.ensure_can_view(&community, viewer_id).await?;
+        self.find_post(community_id, post_id).await?;
+        let comment = self.find_comment(post_id, comment_id).await?;
+        if comment.deleted_at.is_some() {
+            return Err(AppError::NotFound);
+        }
+        Ok(comment)
+    }
+
+    /// Authors delete their own comments; the owner and moderators anyone's.
+    pub async fn delete_comment(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        comment_id: Uuid,
+        actor_id: Uuid,
+    ) -> Result<(), AppError> {
+        let community = self.find_community(community_id).await?;
+        self.find_post(community_id, post_id).await?;
+        let comment = self.find_comment(post_id, comment_id).await?;
+        if comment.author_id != actor_id
+            && self.standing(&community, actor_id).await? != Some(Standing::Moderator)
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        Self::tombstone_comment(&self.db, comment_id).await
+    }
+
+    /// Clears a comment's text but keeps it in the thread so its replies
+    /// stay in place.
+    pub async fn tombstone_comment<C: ConnectionTrait>(
+        conn: &C,
+        comment_id: Uuid,
+    ) -> Result<(), AppError> {
+        PostComment::update_many()
+            .col_expr(post_comment::Column::Content, Expr::value(String::new()))
+            .col_expr(
+                post_comment::Column::DeletedAt,
+                Expr::value(Some(Utc::now())),
+            )
+            .filter(post_comment::Column::Id.eq(comment_id))
+            .filter(post_comment::Column::DeletedAt.is_null())
+            .exec(conn)
+            .await?;
+        Ok(())
+    }
+
+    pub async fn vote_comment(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+        comment_id: Uuid,
+        player_id: Uuid,
+        value: i16,
+    ) -> Result<VoteTally, AppError> {
+        let community = self.find_community(community_id).await?;
+        self.standing(&community, player_id)
+            .await?
+            .ok_or(AppError::Forbidden)?;
+        self.find_post(community_id, post_id).await?;
+        let comment = self.find_comment(post_id, comment_id).await?;
+        if comment.deleted_at.is_some() {
+            return Err(AppError::NotFound);
+        }
+
+        self.set_vote(player_id, VoteTarget::Comment(comment_id), value)
+            .await?;
+        let comment = self.find_comment(post_id, comment_id).await?;
+        Ok(VoteTally {
+            upvotes: comment.upvotes,
+            downvotes: comment.downvotes,
+            my_vote: value,
+        })
     }
 
-    pub async fn join_community(&self, community_id: &str, user_id: &str) -> Result<()> {
+    // ========================================
+    // MEMBERS
+    // ========================================
+
+    pub async fn join_community(&self, community_id: &str, user_id: &str) -> Result<(), AppError> {
         let community_uuid = Uuid::parse_str(community_id)?;
         let user_uuid = Uuid::parse_str(user_id)?;
 
@@ -97,7 +957,7 @@ impl CommunityService {
         Ok(())
     }
 
-    pub async fn leave_community(&self, community_id: &str, user_id: &str) -> Result<()> {
+    pub async fn leave_community(&self, community_id: &str, user_id: &str) -> Result<(), AppError> {
         let community_uuid = Uuid::parse_str(community_id)?;
         let user_uuid = Uuid::parse_str(user_id)?;
 
@@ -110,7 +970,10 @@ impl CommunityService {
         Ok(())
     }
 
-    pub async fn get_communities_by_owner(&self, owner_id: &str) -> Result<Vec<community::Model>> {
+    pub async fn get_communities_by_owner(
+        &self,
+        owner_id: &str,
+    ) -> Result<Vec<community::Model>, AppError> {
         let owner_uuid = Uuid::parse_str(owner_id)?;
         Ok(community::Entity::find()
             .filter(community::Column::OwnerId.eq(owner_uuid))
@@ -121,11 +984,176 @@ impl CommunityService {
     pub async fn get_community_members(
         &self,
         community_id: &str,
-    ) -> Result<Vec<community_member::Model>> {
+    ) -> Result<Vec<community_member::Model>, AppError> {
         let community_uuid = Uuid::parse_str(community_id)?;
         Ok(community_member::Entity::find()
             .filter(community_member::Column::CommunityId.eq(community_uuid))
             .all(&self.db)
             .await?)
     }
+
+    async fn find_community(&self, community_id: Uuid) -> Result<community::Model, AppError> {
+        community::Entity::find_by_id(community_id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    async fn find_post(
+        &self,
+        community_id: Uuid,
+        post_id: Uuid,
+    ) -> Result<community_post::Model, AppError> {
+        CommunityPost::find_by_id(post_id)
+            .filter(community_post::Column::CommunityId.eq(community_id))
+            .filter(community_post::Column::DeletedAt.is_null())
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    async fn find_comment(
+        &self,
+        post_id: Uuid,
+        comment_id: Uuid,
+    ) -> Result<post_comment::Model, AppError> {
+        PostComment::find_by_id(comment_id)
+            .filter(post_comment::Column::PostId.eq(post_id))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// How the player takes part in the community: `None` for outsiders and
+    /// banned members. The owner always moderates.
+    async fn standing(
+        &self,
+        community: &community::Model,
+        player_id: Uuid,
+    ) -> Result<Option<Standing>, AppError> {
+        if community.owner_id == player_id {
+            return Ok(Some(Standing::Moderator));
+        }
+        let member = community_member::Entity::find()
+            .filter(community_member::Column::CommunityId.eq(community.id))
+            .filter(community_member::Column::PlayerId.eq(player_id))
+            .one(&self.db)
+            .await?;
+
+        Ok(member.and_then(|m| match m.role.as_str() {
+            "banned" if m.banned_until.is_none_or(|until| until > Utc::now()) => None,
+            "owner" | "moderator" => Some(Standing::Moderator),
+            _ => Some(Standing::Member),
+        }))
+    }
+
+    /// Public communities are readable by anyone signed in, others by members.
+    async fn ensure_can_view(
+        &self,
+        community: &community::Model,
+        viewer_id: Uuid,
+    ) -> Result<(), AppError> {
+        if community.privacy == "public" || self.standing(community, viewer_id).await?.is_some() {
+            Ok(())
+        } else {
+            Err(AppError::Forbidden)
+        }
+    }
+
+    async fn set_vote(
+        &self,
+        player_id: Uuid,
+        target: VoteTarget,
+        value: i16,
+    ) -> Result<(), AppError> {
+        let (column, target_id) = match target {
+            VoteTarget::Post(id) => ("post_id", id),
+            VoteTarget::Comment(id) => ("comment_id", id),
+        };
+        let statement = match value {
+            0 => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!("DELETE FROM community_votes WHERE {column} = $1 AND player_id = $2"),
+                [target_id.into(), player_id.into()],
+            ),
+            1 | -1 => Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                format!(
+                    r#"INSERT INTO community_votes (id, player_id, {column}, value)
+                       VALUES (gen_random_uuid(), $2, $1, $3)
+                       ON CONFLICT ({column}, player_id) WHERE {column} IS NOT NULL
+                       DO UPDATE SET value = EXCLUDED.value
+                       WHERE community_votes.value <> EXCLUDED.value"#
+                ),
+                [target_id.into(), player_id.into(), value.into()],
+            ),
+            _ => {
+                return Err(AppError::Validation(
+                    "A vote is 1, -1, or 0 to clear it".to_string(),
+                ))
+            }
+        };
+
+        self.db.execute(statement).await?;
+        Ok(())
+    }
+
+    async fn my_votes_on_posts(
+        &self,
+        player_id: Uuid,
+        post_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, i16>, AppError> {
+        let ids: HashSet<Uuid> = post_ids.iter().copied().collect();
+        Ok(CommunityVote::find()
+            .filter(community_vote::Column::PlayerId.eq(player_id))
+            .filter(community_vote::Column::PostId.is_in(ids))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .filter_map(|v| v.post_id.map(|id| (id, v.value)))
+            .collect())
+    }
+
+    async fn my_votes_on_comments(
+        &self,
+        player_id: Uuid,
+        comment_ids: &[Uuid],
+    ) -> Result<HashMap<Uuid, i16>, AppError> {
+        let ids: HashSet<Uuid> = comment_ids.iter().copied().collect();
+        Ok(CommunityVote::find()
+            .filter(community_vote::Column::PlayerId.eq(player_id))
+            .filter(community_vote::Column::CommentId.is_in(ids))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .filter_map(|v| v.comment_id.map(|id| (id, v.value)))
+            .collect())
+    }
+
+    /// Queues stored content its screening flagged. The content is already
+    /// published, so a failure here is only logged.
+    async fn queue_flags(
+        &self,
+        screening: Screening,
+        content_type: ModeratedContent,
+        content_id: Uuid,
+        author_id: Uuid,
+        text: &str,
+    ) {
+        if let Err(e) = self
+            .moderation
+            .queue_flags(
+                screening,
+                QueuedContent {
+                    content_type,
+                    content_id,
+                    author_id,
+                    text,
+                },
+            )
+            .await
+        {
+            tracing::warn!("Failed to queue flagged content {}: {}", content_id, e);
+        }
+    }
 }
@@ -0,0 +1,53 @@
+use crate::models::postgres::{consumed_token, ConsumedToken};
+use crate::utils::errors::AppError;
+use chrono::{DateTime, Utc};
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct ConsumedTokenService {
+    db: DatabaseConnection,
+}
+
+impl ConsumedTokenService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Marks a single-use token as redeemed. Returns false if it already was,
+    /// including when two requests race to redeem the same token.
+    pub async fn consume(
+        &self,
+        jti: Uuid,
+        token_type: &str,
+        user_id: Uuid,
+        expires_at: DateTime<Utc>,
+    ) -> Result<bool, AppError> {
+        let inserted = ConsumedToken::insert(consumed_token::ActiveModel {
+            jti: Set(jti),
+            token_type: Set(token_type.to_string()),
+            user_id: Set(user_id),
+            consumed_at: Set(Utc::now()),
+            expires_at: Set(expires_at),
+        })
+        .on_conflict(
+            OnConflict::column(consumed_token::Column::Jti)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&self.db)
+        .await?;
+
+        Ok(inserted == 1)
+    }
+
+    /// Drops rows for tokens that have expired anyway and can no longer be replayed.
+    pub async fn purge_expired(&self) -> Result<u64, AppError> {
+        let result = ConsumedToken::delete_many()
+            .filter(consumed_token::Column::ExpiresAt.lt(Utc::now()))
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -1,3 +1,4 @@
+use crate::services::announcement_service::{IN_AUDIENCE, IS_LIVE};
 use crate::utils::errors::AppError;
 use redis::{AsyncCommands, Client as RedisClient};
 use serde::{Deserialize, Serialize};
@@ -107,7 +108,7 @@ pub struct MatchSummary {
     pub placement: i32,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Serialize, Deserialize, sqlx::FromRow)]
 pub struct SystemMessage {
     pub id: Uuid,
     pub message: String,
@@ -201,7 +202,7 @@ impl DashboardService {
         ORDER BY b.created_at DESC LIMIT 3
     ),
     system_msgs AS (
-        SELECT cm.id, cm.message, cm.message_type, cm.created_at
+        SELECT cm.id, cm.message, cm.message_type, cm.created_at, FALSE AS read
         FROM chat_messages cm
         WHERE cm.receiver_id = $1 AND cm.message_type = 'system'
         ORDER BY cm.created_at DESC LIMIT 5
@@ -250,7 +251,7 @@ impl DashboardService {
         )) FILTER (WHERE rb.id IS NOT NULL), '[]') as recent_matches,
         COALESCE(json_agg(DISTINCT jsonb_build_object(
             'id', sm.id, 'message', sm.message, 'message_type', COALESCE(sm.message_type, 'info'),
-            'created_at', sm.created_at, 'read', false
+            'created_at', sm.created_at, 'read', sm.read
         )) FILTER (WHERE sm.id IS NOT NULL), '[]') as system_messages,
         COALESCE(json_agg(DISTINCT jsonb_build_object(
             'id', cr.id, 'sender_username', cr.sender_username,
@@ -332,11 +333,13 @@ impl DashboardService {
                 .recent_matches
                 .unwrap_or_else(|| serde_json::json!([])),
         )?;
-        let system_messages: Vec<SystemMessage> = serde_json::from_value(
+        let mut system_messages: Vec<SystemMessage> = serde_json::from_value(
             dashboard_query
                 .system_messages
                 .unwrap_or_else(|| serde_json::json!([])),
         )?;
+        system_messages.extend(self.live_announcements(user_id).await?);
+        system_messages.sort_by_key(|m| std::cmp::Reverse(m.created_at));
         let recruitment_approaches: Vec<RecruitmentApproach> = serde_json::from_value(
             dashboard_query
                 .recruitment_approaches
@@ -389,6 +392,24 @@ impl DashboardService {
         Ok(dashboard)
     }
 
+    /// Live announcements addressed to the player, shown alongside system
+    /// messages. Acknowledged ones count as read.
+    async fn live_announcements(&self, user_id: Uuid) -> Result<Vec<SystemMessage>, AppError> {
+        Ok(sqlx::query_as(&format!(
+            r#"SELECT a.id, a.title AS message, a.severity AS message_type,
+                      a.publish_at AS created_at, ack.id IS NOT NULL AS read
+               FROM announcements a
+               JOIN players p ON p.id = $1
+               LEFT JOIN announcement_acknowledgements ack
+                 ON ack.announcement_id = a.id AND ack.player_id = p.id
+               WHERE {IS_LIVE} AND {IN_AUDIENCE}
+               ORDER BY a.publish_at DESC LIMIT 5"#
+        ))
+        .bind(user_id)
+        .fetch_all(&self.sql_pool)
+        .await?)
+    }
+
     async fn get_cached_dashboard(&self, user_id: Uuid) -> Result<Option<DashboardData>, AppError> {
         let cache_start = Instant::now();
         let Some(redis) = &self.redis else {
@@ -438,6 +459,30 @@ impl DashboardService {
         Ok(None)
     }
 
+    /// Drops cached dashboards so the next request rebuilds them.
+    pub async fn invalidate(&self, user_ids: &[Uuid]) -> Result<(), AppError> {
+        let Some(redis) = &self.redis else {
+            return Ok(());
+        };
+        if user_ids.is_empty() {
+            return Ok(());
+        }
+
+        let keys: Vec<String> = user_ids
+            .iter()
+            .map(|id| format!("dashboard:{}", id))
+            .collect();
+        let mut conn = redis
+            .get_async_connection()
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        let _: () = conn
+            .del(keys)
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        Ok(())
+    }
+
     async fn cache_dashboard_data(
         &self,
         user_id: Uuid,
@@ -0,0 +1,275 @@
+use crate::models::enums::{ChatMemberRole, DmPrivacy};
+use crate::models::postgres::{chat, player_connection, Chat, Player, PlayerConnection};
+use crate::services::chat_service::ChatService;
+use crate::utils::errors::AppError;
+use chrono::{DateTime, Utc};
+use sea_orm::*;
+use serde::Serialize;
+use std::collections::HashSet;
+use uuid::Uuid;
+
+pub const DIRECT_CHAT: &str = "direct";
+pub const GROUP_DM_CHAT: &str = "group_dm";
+
+// Including the creator
+pub const MAX_GROUP_DM_MEMBERS: usize = 10;
+
+/// A conversation in a player's inbox.
+#[derive(Debug, Serialize, FromQueryResult)]
+pub struct InboxEntry {
+    pub chat_id: Uuid,
+    pub chat_type: String,
+    pub name: String,
+    pub member_ids: Vec<Uuid>, // everyone but the viewer
+    pub last_message_id: Option<Uuid>,
+    pub last_message: Option<String>,
+    pub last_sender_id: Option<Uuid>,
+    pub last_message_at: Option<DateTime<Utc>>,
+    pub unread_count: i64,
+}
+
+/// 1:1 and small group conversations between players. They are ordinary
+/// private chats underneath, so messages go through the `/chats` routes.
+#[derive(Clone)]
+pub struct DirectMessageService {
+    db: DatabaseConnection,
+}
+
+impl DirectMessageService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn direct_key(a: Uuid, b: Uuid) -> String {
+        let (low, high) = if a < b { (a, b) } else { (b, a) };
+        format!("{}:{}", low, high)
+    }
+
+    /// Whether either player has blocked the other.
+    pub async fn is_blocked<C: ConnectionTrait>(
+        conn: &C,
+        a: Uuid,
+        b: Uuid,
+    ) -> Result<bool, AppError> {
+        Self::connection_with_status(conn, a, b, "blocked").await
+    }
+
+    async fn connection_with_status<C: ConnectionTrait>(
+        conn: &C,
+        a: Uuid,
+        b: Uuid,
+        status: &str,
+    ) -> Result<bool, AppError> {
+        let count = PlayerConnection::find()
+            .filter(
+                Condition::any()
+                    .add(
+                        Condition::all()
+                            .add(player_connection::Column::RequesterId.eq(a))
+                            .add(player_connection::Column::RecipientId.eq(b)),
+                    )
+                    .add(
+                        Condition::all()
+                            .add(player_connection::Column::RequesterId.eq(b))
+                            .add(player_connection::Column::RecipientId.eq(a)),
+                    ),
+            )
+            .filter(player_connection::Column::Status.eq(status))
+            .count(conn)
+            .await?;
+        Ok(count > 0)
+    }
+
+    /// Checks that `sender` may start a conversation with `recipient`: neither
+    /// has blocked the other, and connection-only players are connected.
+    pub async fn ensure_can_message<C: ConnectionTrait>(
+        conn: &C,
+        sender: Uuid,
+        recipient: Uuid,
+    ) -> Result<(), AppError> {
+        let recipient = Player::find_by_id(recipient)
+            .one(conn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        if Self::is_blocked(conn, sender, recipient.id).await? {
+            return Err(AppError::Forbidden);
+        }
+        if recipient.dm_privacy == DmPrivacy::Connections
+            && !Self::connection_with_status(conn, sender, recipient.id, "accepted").await?
+        {
+            return Err(AppError::Forbidden);
+        }
+        Ok(())
+    }
+
+    /// Returns the direct chat between the two players, creating it on first use.
+    pub async fn open_direct(
+        &self,
+        player_id: Uuid,
+        other_id: Uuid,
+    ) -> Result<chat::Model, AppError> {
+        if player_id == other_id {
+            return Err(AppError::Validation(
+                "You can't message yourself".to_string(),
+            ));
+        }
+
+        let key = Self::direct_key(player_id, other_id);
+        if let Some(existing) = self.find_direct(&key).await? {
+            // Privacy only gates new conversations, blocks always apply
+            if Self::is_blocked(&self.db, player_id, other_id).await? {
+                return Err(AppError::Forbidden);
+            }
+            return Ok(existing);
+        }
+
+        Self::ensure_can_message(&self.db, player_id, other_id).await?;
+
+        let txn = self.db.begin().await?;
+        let inserted = chat::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            chat_type: Set(DIRECT_CHAT.to_string()),
+            name: Set(String::new()),
+            created_by: Set(Some(player_id)),
+            is_private: Set(true),
+            max_participants: Set(2),
+            direct_key: Set(Some(key.clone())),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await;
+
+        let chat = match inserted {
+            Ok(chat) => chat,
+            // Both players opened the conversation at the same time
+            Err(e) if matches!(e.sql_err(), Some(SqlErr::UniqueConstraintViolation(_))) => {
+                txn.rollback().await?;
+                return self.find_direct(&key).await?.ok_or(AppError::NotFound);
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        // Neither side moderates a 1:1 conversation
+        for member in [player_id, other_id] {
+            ChatService::add_membership(&txn, chat.id, member, ChatMemberRole::Member).await?;
+        }
+        txn.commit().await?;
+
+        Ok(chat)
+    }
+
+    async fn find_direct(&self, key: &str) -> Result<Option<chat::Model>, AppError> {
+        Ok(Chat::find()
+            .filter(chat::Column::DirectKey.eq(key))
+            .one(&self.db)
+            .await?)
+    }
+
+    /// Creates a group conversation owned by `creator_id`.
+    pub async fn create_group(
+        &self,
+        creator_id: Uuid,
+        name: Option<String>,
+        player_ids: Vec<Uuid>,
+    ) -> Result<chat::Model, AppError> {
+        let mut seen = HashSet::from([creator_id]);
+        let invitees: Vec<Uuid> = player_ids
+            .into_iter()
+            .filter(|id| seen.insert(*id))
+            .collect();
+
+        if invitees.len() < 2 {
+            return Err(AppError::Validation(
+                "A group needs at least two other players".to_string(),
+            ));
+        }
+        if invitees.len() + 1 > MAX_GROUP_DM_MEMBERS {
+            return Err(AppError::Validation(format!(
+                "Groups are limited to {} members",
+                MAX_GROUP_DM_MEMBERS
+            )));
+        }
+        let name = name.map(|n| n.trim().to_string()).unwrap_or_default();
+        if name.chars().count() > 100 {
+            return Err(AppError::Validation(
+                "Group name must be at most 100 characters".to_string(),
+            ));
+        }
+
+        for invitee in &invitees {
+            Self::ensure_can_message(&self.db, creator_id, *invitee).await?;
+        }
+
+        let txn = self.db.begin().await?;
+        let chat = chat::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            chat_type: Set(GROUP_DM_CHAT.to_string()),
+            name: Set(name),
+            created_by: Set(Some(creator_id)),
+            is_private: Set(true),
+            max_participants: Set(MAX_GROUP_DM_MEMBERS as i32),
+            ..Default::default()
+        }
+        .insert(&txn)
+        .await?;
+
+        ChatService::add_membership(&txn, chat.id, creator_id, ChatMemberRole::Owner).await?;
+        for invitee in invitees {
+            ChatService::add_membership(&txn, chat.id, invitee, ChatMemberRole::Member).await?;
+        }
+        txn.commit().await?;
+
+        Ok(chat)
+    }
+
+    /// The player's conversations, most recently active first. Unread counts
+    /// cover other members' messages after the player's read pointer.
+    pub async fn inbox(
+        &self,
+        player_id: Uuid,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<InboxEntry>, AppError> {
+        Ok(InboxEntry::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"SELECT c.id AS chat_id, c.chat_type, c.name,
+                      ARRAY(
+                          SELECT o.player_id FROM chat_members o
+                          WHERE o.chat_id = c.id AND o.left_at IS NULL AND o.player_id <> $1
+                          ORDER BY o.joined_at
+                      ) AS member_ids,
+                      lm.id AS last_message_id, lm.message AS last_message,
+                      lm.sender_id AS last_sender_id, lm.created_at AS last_message_at,
+                      (
+                          SELECT COUNT(*) FROM chat_messages u
+                          WHERE u.chat_id = c.id AND u.deleted_at IS NULL AND u.sender_id <> $1
+                            AND u.created_at > COALESCE(
+                                (SELECT r.created_at FROM chat_messages r WHERE r.id = m.last_read_message_id),
+                                m.joined_at
+                            )
+                      ) AS unread_count
+               FROM chat_members m
+               JOIN chats c ON c.id = m.chat_id
+               LEFT JOIN LATERAL (
+                   SELECT id, message, sender_id, created_at FROM chat_messages
+                   WHERE chat_id = c.id AND deleted_at IS NULL
+                   ORDER BY created_at DESC
+                   LIMIT 1
+               ) lm ON TRUE
+               WHERE m.player_id = $1 AND m.left_at IS NULL
+                 AND c.chat_type IN ('direct', 'group_dm')
+                 -- an opened but empty 1:1 only shows up for whoever opened it
+                 AND (lm.id IS NOT NULL OR c.created_by = $1 OR c.chat_type = 'group_dm')
+               ORDER BY COALESCE(lm.created_at, c.created_at) DESC
+               LIMIT $2 OFFSET $3"#,
+            [
+                player_id.into(),
+                (limit as i64).into(),
+                (offset as i64).into(),
+            ],
+        ))
+        .all(&self.db)
+        .await?)
+    }
+}
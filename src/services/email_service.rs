@@ -180,4 +180,216 @@ impl EmailService {
         tracing::info!("Verification email sent to {}", to_email);
         Ok(())
     }
+
+    pub async fn send_organization_invite(
+        &self,
+        to_email: &str,
+        org_name: &str,
+        role: &str,
+        invite_token: &str,
+    ) -> Result<(), AppError> {
+        let invite_link = format!("http://localhost:5173/organization-invite/{}", invite_token);
+
+        // Development mode - just log
+        if self.config.smtp_user.is_empty() {
+            tracing::info!(
+                "Organization invite link for {} ({} as {}): {}",
+                to_email,
+                org_name,
+                role,
+                invite_link
+            );
+            return Ok(());
+        }
+
+        // Production mode - send actual email
+        let from: Mailbox = format!("{} <{}>", self.config.from_name, self.config.from_email)
+            .parse()
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let to: Mailbox = to_email
+            .parse()
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let email = Message::builder()
+            .from(from)
+            .to(to)
+            .subject(format!("You're invited to join {} - Aegis Gaming", org_name))
+            .header(ContentType::TEXT_HTML)
+            .body(format!(
+                r#"
+                <div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
+                    <div style="text-align: center; margin-bottom: 30px;">
+                        <h1 style="color: #f59e0b; margin: 0;">Aegis Gaming</h1>
+                    </div>
+                    
+                    <h2 style="color: #333; margin-bottom: 20px;">Join {} on Aegis</h2>
+                    
+                    <p style="color: #666; line-height: 1.6; margin-bottom: 20px;">
+                        You have been invited to join <strong>{}</strong> as <strong>{}</strong>. Sign in with your player account and accept the invite below:
+                    </p>
+                    
+                    <div style="text-align: center; margin: 30px 0;">
+                        <a href="{}" style="background-color: #f59e0b; color: white; padding: 12px 30px; text-decoration: none; border-radius: 5px; font-weight: bold; display: inline-block;">
+                            Accept Invite
+                        </a>
+                    </div>
+                    
+                    <p style="color: #999; font-size: 14px; line-height: 1.6;">
+                        This invite will expire in 3 days. If you weren't expecting it, you can safely ignore this email.
+                    </p>
+                    
+                    <hr style="border: none; border-top: 1px solid #eee; margin: 30px 0;">
+                    
+                    <p style="color: #999; font-size: 12px; text-align: center;">
+                        © 2024 Aegis Gaming. All rights reserved.
+                    </p>
+                </div>
+                "#,
+                org_name, org_name, role, invite_link
+            ))
+            .map_err(|_| AppError::InternalServerError)?;
+
+        self.mailer.send(email).await.map_err(|e| {
+            tracing::error!("Failed to send organization invite email: {}", e);
+            AppError::InternalServerError
+        })?;
+
+        tracing::info!("Organization invite email sent to {}", to_email);
+        Ok(())
+    }
+
+    pub async fn send_magic_login_link(
+        &self,
+        to_email: &str,
+        login_token: &str,
+        expires_in_minutes: i64,
+    ) -> Result<(), AppError> {
+        let login_link = format!("http://localhost:5173/magic-login/{}", login_token);
+
+        // Development mode - just log
+        if self.config.smtp_user.is_empty() {
+            tracing::info!("Magic login link for {}: {}", to_email, login_link);
+            return Ok(());
+        }
+
+        // Production mode - send actual email
+        let from: Mailbox = format!("{} <{}>", self.config.from_name, self.config.from_email)
+            .parse()
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let to: Mailbox = to_email
+            .parse()
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let email = Message::builder()
+            .from(from)
+            .to(to)
+            .subject("Your Sign-In Link - Aegis Gaming")
+            .header(ContentType::TEXT_HTML)
+            .body(format!(
+                r#"
+                <div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
+                    <div style="text-align: center; margin-bottom: 30px;">
+                        <h1 style="color: #f59e0b; margin: 0;">Aegis Gaming</h1>
+                    </div>
+                    
+                    <h2 style="color: #333; margin-bottom: 20px;">Sign in to Aegis Gaming</h2>
+                    
+                    <p style="color: #666; line-height: 1.6; margin-bottom: 20px;">
+                        Click the button below to sign in. Open it on the same device and browser you requested it from:
+                    </p>
+                    
+                    <div style="text-align: center; margin: 30px 0;">
+                        <a href="{}" style="background-color: #f59e0b; color: white; padding: 12px 30px; text-decoration: none; border-radius: 5px; font-weight: bold; display: inline-block;">
+                            Sign In
+                        </a>
+                    </div>
+                    
+                    <p style="color: #999; font-size: 14px; line-height: 1.6;">
+                        This link works once and expires in {} minutes. If you didn't request it, you can safely ignore this email.
+                    </p>
+                    
+                    <hr style="border: none; border-top: 1px solid #eee; margin: 30px 0;">
+                    
+                    <p style="color: #999; font-size: 12px; text-align: center;">
+                        © 2024 Aegis Gaming. All rights reserved.
+                    </p>
+                </div>
+                "#,
+                login_link, expires_in_minutes
+            ))
+            .map_err(|_| AppError::InternalServerError)?;
+
+        self.mailer.send(email).await.map_err(|e| {
+            tracing::error!("Failed to send magic login email: {}", e);
+            AppError::InternalServerError
+        })?;
+
+        tracing::info!("Magic login email sent to {}", to_email);
+        Ok(())
+    }
+
+    pub async fn send_security_alert(
+        &self,
+        to_email: &str,
+        heading: &str,
+        message: &str,
+    ) -> Result<(), AppError> {
+        // Development mode - just log
+        if self.config.smtp_user.is_empty() {
+            tracing::info!("Security alert for {}: {} - {}", to_email, heading, message);
+            return Ok(());
+        }
+
+        // Production mode - send actual email
+        let from: Mailbox = format!("{} <{}>", self.config.from_name, self.config.from_email)
+            .parse()
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let to: Mailbox = to_email
+            .parse()
+            .map_err(|_| AppError::InternalServerError)?;
+
+        let email = Message::builder()
+            .from(from)
+            .to(to)
+            .subject(format!("Security Alert: {} - Aegis Gaming", heading))
+            .header(ContentType::TEXT_HTML)
+            .body(format!(
+                r#"
+                <div style="font-family: Arial, sans-serif; max-width: 600px; margin: 0 auto; padding: 20px;">
+                    <div style="text-align: center; margin-bottom: 30px;">
+                        <h1 style="color: #f59e0b; margin: 0;">Aegis Gaming</h1>
+                    </div>
+                    
+                    <h2 style="color: #333; margin-bottom: 20px;">{}</h2>
+                    
+                    <p style="color: #666; line-height: 1.6; margin-bottom: 20px;">
+                        {}
+                    </p>
+                    
+                    <p style="color: #999; font-size: 14px; line-height: 1.6;">
+                        You are receiving this because of activity on your Aegis Gaming account.
+                    </p>
+                    
+                    <hr style="border: none; border-top: 1px solid #eee; margin: 30px 0;">
+                    
+                    <p style="color: #999; font-size: 12px; text-align: center;">
+                        © 2024 Aegis Gaming. All rights reserved.
+                    </p>
+                </div>
+                "#,
+                heading, message
+            ))
+            .map_err(|_| AppError::InternalServerError)?;
+
+        self.mailer.send(email).await.map_err(|e| {
+            tracing::error!("Failed to send security alert email: {}", e);
+            AppError::InternalServerError
+        })?;
+
+        tracing::info!("Security alert email sent to {}", to_email);
+        Ok(())
+    }
 }
@@ -0,0 +1,406 @@
+use crate::models::enums::{ApprovalStatus, WebhookEvent};
+use crate::models::postgres::{
+    activity_log, player, tournament_team, ActivityLog, Battle, Player, Tournament, TournamentTeam,
+};
+use crate::services::auth_service::UserType;
+use crate::services::chat_channel_service::ChatChannelService;
+use crate::services::chat_realtime_service::{ChatRealtimeService, NOTIFICATION};
+use crate::services::outbox_service::{DomainEvent, EventSubscriber};
+use crate::services::{AuthService, DashboardService, EmailService, WebhookService};
+use crate::utils::errors::AppError;
+use async_trait::async_trait;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+use uuid::Uuid;
+
+/// Sends the verification email for password sign-ups.
+pub struct VerificationEmailSubscriber {
+    pub auth_service: AuthService,
+    pub email_service: EmailService,
+}
+
+#[async_trait]
+impl EventSubscriber for VerificationEmailSubscriber {
+    fn name(&self) -> &'static str {
+        "verification_email"
+    }
+
+    async fn handle(&self, _event_id: Uuid, event: &DomainEvent) -> Result<(), AppError> {
+        let DomainEvent::PlayerRegistered {
+            player_id,
+            email,
+            verified: false,
+            method,
+            ..
+        } = event
+        else {
+            return Ok(());
+        };
+        // Social sign-ups verify through their provider
+        if method != "password" {
+            return Ok(());
+        }
+
+        let token = self.auth_service.generate_temp_token(
+            *player_id,
+            UserType::Player,
+            "verify_email",
+            24,
+        )?;
+        self.email_service
+            .send_verification_email(email, &token)
+            .await
+    }
+}
+
+/// Drops cached dashboards of the players an event shows up for.
+pub struct DashboardCacheSubscriber {
+    pub db: DatabaseConnection,
+    pub dashboard_service: DashboardService,
+}
+
+impl DashboardCacheSubscriber {
+    async fn team_players(&self, team_ids: Vec<Uuid>) -> Result<Vec<Uuid>, AppError> {
+        Ok(Player::find()
+            .select_only()
+            .column(player::Column::Id)
+            .filter(player::Column::TeamId.is_in(team_ids))
+            .into_tuple()
+            .all(&self.db)
+            .await?)
+    }
+
+    async fn tournament_players(&self, tournament_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let team_ids: Vec<Uuid> = TournamentTeam::find()
+            .select_only()
+            .column(tournament_team::Column::TeamId)
+            .filter(tournament_team::Column::TournamentId.eq(tournament_id))
+            .into_tuple()
+            .all(&self.db)
+            .await?;
+        self.team_players(team_ids).await
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for DashboardCacheSubscriber {
+    fn name(&self) -> &'static str {
+        "dashboard_cache"
+    }
+
+    async fn handle(&self, _event_id: Uuid, event: &DomainEvent) -> Result<(), AppError> {
+        let players = match event {
+            DomainEvent::TeamJoined { team_id, .. } => self.team_players(vec![*team_id]).await?,
+            DomainEvent::TournamentTransitioned { tournament_id, .. }
+            | DomainEvent::ResultVerified { tournament_id, .. } => {
+                self.tournament_players(*tournament_id).await?
+            }
+            DomainEvent::PlayerRegistered { .. } | DomainEvent::ChatActivity { .. } => {
+                return Ok(())
+            }
+        };
+        self.dashboard_service.invalidate(&players).await
+    }
+}
+
+/// Forwards events to the webhooks of the organization running the tournament.
+pub struct WebhookSubscriber {
+    pub db: DatabaseConnection,
+    pub webhook_service: WebhookService,
+}
+
+impl WebhookSubscriber {
+    async fn tournament_org(&self, tournament_id: Uuid) -> Result<Option<Uuid>, AppError> {
+        Ok(Tournament::find_by_id(tournament_id)
+            .one(&self.db)
+            .await?
+            .and_then(|t| t.submitted_by))
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for WebhookSubscriber {
+    fn name(&self) -> &'static str {
+        "webhooks"
+    }
+
+    async fn handle(&self, event_id: Uuid, event: &DomainEvent) -> Result<(), AppError> {
+        let (org_id, webhook_event, data) = match event {
+            DomainEvent::TournamentTransitioned {
+                tournament_id,
+                tournament_name,
+                organization_id,
+                status,
+                approval_status,
+                previous_approval_status,
+            } => (
+                *organization_id,
+                WebhookEvent::TournamentStatusChanged,
+                serde_json::json!({
+                    "tournament_id": tournament_id,
+                    "tournament_name": tournament_name,
+                    "status": status,
+                    "approval_status": approval_status,
+                    "previous_approval_status": previous_approval_status,
+                }),
+            ),
+            DomainEvent::TeamJoined {
+                tournament_team_id,
+                tournament_id,
+                team_id,
+            } => (
+                self.tournament_org(*tournament_id).await?,
+                WebhookEvent::TeamRegistered,
+                serde_json::json!({
+                    "registration_id": tournament_team_id,
+                    "tournament_id": tournament_id,
+                    "team_id": team_id,
+                }),
+            ),
+            DomainEvent::ResultVerified {
+                battle_id,
+                tournament_id,
+            } => (
+                self.tournament_org(*tournament_id).await?,
+                WebhookEvent::BattleResultVerified,
+                serde_json::json!({
+                    "battle_id": battle_id,
+                    "tournament_id": tournament_id,
+                }),
+            ),
+            DomainEvent::PlayerRegistered { .. } | DomainEvent::ChatActivity { .. } => {
+                return Ok(())
+            }
+        };
+
+        if let Some(org_id) = org_id {
+            self.webhook_service
+                .dispatch_event(org_id, event_id, webhook_event, data)
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// Keeps lobbies in step with tournament transitions and archives a battle's
+/// lobby once its results are verified. Registrations sync the tournament
+/// lobby in their own transaction; the periodic reconcile catches the rest.
+pub struct ChatChannelSubscriber {
+    pub db: DatabaseConnection,
+    pub chat_channels: ChatChannelService,
+}
+
+#[async_trait]
+impl EventSubscriber for ChatChannelSubscriber {
+    fn name(&self) -> &'static str {
+        "chat_channels"
+    }
+
+    async fn handle(&self, _event_id: Uuid, event: &DomainEvent) -> Result<(), AppError> {
+        let ended = match event {
+            DomainEvent::TournamentTransitioned { tournament_id, .. } => {
+                ChatChannelService::provision_tournament_lobby(&self.db, *tournament_id).await?
+            }
+            DomainEvent::ResultVerified { battle_id, .. } => {
+                ChatChannelService::provision_battle_lobby(&self.db, *battle_id).await?
+            }
+            DomainEvent::PlayerRegistered { .. }
+            | DomainEvent::TeamJoined { .. }
+            | DomainEvent::ChatActivity { .. } => return Ok(()),
+        };
+        self.chat_channels.evict(ended).await;
+        Ok(())
+    }
+}
+
+/// Tells the players of the teams an event concerns, over their sockets. The
+/// outbox event id goes along so clients can drop a redelivered notification.
+pub struct NotificationSubscriber {
+    pub db: DatabaseConnection,
+    pub realtime: ChatRealtimeService,
+}
+
+impl NotificationSubscriber {
+    async fn team_players(&self, team_ids: Vec<Uuid>) -> Result<Vec<Uuid>, AppError> {
+        Ok(Player::find()
+            .select_only()
+            .column(player::Column::Id)
+            .filter(player::Column::TeamId.is_in(team_ids))
+            .into_tuple()
+            .all(&self.db)
+            .await?)
+    }
+
+    async fn registered_teams(&self, tournament_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        Ok(TournamentTeam::find()
+            .select_only()
+            .column(tournament_team::Column::TeamId)
+            .filter(tournament_team::Column::TournamentId.eq(tournament_id))
+            .into_tuple()
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Teams listed in a battle's verified results.
+    async fn result_teams(&self, battle_id: Uuid) -> Result<Vec<Uuid>, AppError> {
+        let Some(battle) = Battle::find_by_id(battle_id).one(&self.db).await? else {
+            return Ok(Vec::new());
+        };
+        Ok(battle.battle_stats["results"]
+            .as_array()
+            .map(|results| {
+                results
+                    .iter()
+                    .filter_map(|r| r["team_id"].as_str()?.parse().ok())
+                    .collect()
+            })
+            .unwrap_or_default())
+    }
+}
+
+#[async_trait]
+impl EventSubscriber for NotificationSubscriber {
+    fn name(&self) -> &'static str {
+        "notifications"
+    }
+
+    async fn handle(&self, event_id: Uuid, event: &DomainEvent) -> Result<(), AppError> {
+        let (team_ids, data) = match event {
+            DomainEvent::TeamJoined {
+                tournament_id,
+                team_id,
+                ..
+            } => (
+                vec![*team_id],
+                serde_json::json!({"tournament_id": tournament_id, "team_id": team_id}),
+            ),
+            // Teams only hear about a tournament once it is public
+            DomainEvent::TournamentTransitioned {
+                tournament_id,
+                tournament_name,
+                status,
+                approval_status,
+                ..
+            } if *approval_status == ApprovalStatus::Approved => (
+                self.registered_teams(*tournament_id).await?,
+                serde_json::json!({
+                    "tournament_id": tournament_id,
+                    "tournament_name": tournament_name,
+                    "status": status,
+                }),
+            ),
+            DomainEvent::ResultVerified {
+                battle_id,
+                tournament_id,
+            } => (
+                self.result_teams(*battle_id).await?,
+                serde_json::json!({"battle_id": battle_id, "tournament_id": tournament_id}),
+            ),
+            _ => return Ok(()),
+        };
+
+        let players = self.team_players(team_ids).await?;
+        self.realtime
+            .notify_players(
+                &players,
+                NOTIFICATION,
+                serde_json::json!({
+                    "id": event_id,
+                    "type": event.event_type(),
+                    "data": data,
+                }),
+            )
+            .await;
+        Ok(())
+    }
+}
+
+/// Recomputes a tournament's standings from its verified battle results.
+/// Totals are rebuilt rather than incremented, so redelivery is harmless.
+pub struct LeaderboardSubscriber {
+    pub db: DatabaseConnection,
+}
+
+#[async_trait]
+impl EventSubscriber for LeaderboardSubscriber {
+    fn name(&self) -> &'static str {
+        "leaderboards"
+    }
+
+    async fn handle(&self, _event_id: Uuid, event: &DomainEvent) -> Result<(), AppError> {
+        let DomainEvent::ResultVerified { tournament_id, .. } = event else {
+            return Ok(());
+        };
+
+        self.db
+            .execute(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"UPDATE tournament_teams tt
+                   SET total_tournament_points = totals.points,
+                       total_tournament_kills = totals.kills
+                   FROM (
+                       SELECT t.id,
+                              COALESCE(SUM((r.value->>'points')::int), 0) AS points,
+                              COALESCE(SUM((r.value->>'kills')::int), 0) AS kills
+                       FROM tournament_teams t
+                       LEFT JOIN battles b
+                           ON b.tournament = t.tournament_id AND b.status = 'completed'
+                       LEFT JOIN LATERAL jsonb_array_elements(
+                           COALESCE(b.battle_stats->'results', '[]'::jsonb)
+                       ) r ON r.value->>'team_id' = t.team_id::text
+                       WHERE t.tournament_id = $1
+                       GROUP BY t.id
+                   ) totals
+                   WHERE tt.id = totals.id"#,
+                [(*tournament_id).into()],
+            ))
+            .await?;
+        Ok(())
+    }
+}
+
+/// Writes chat activity to `activity_logs`. The row takes the outbox event
+/// id, so a redelivered event isn't logged twice.
+pub struct ActivityLogSubscriber {
+    pub db: DatabaseConnection,
+}
+
+#[async_trait]
+impl EventSubscriber for ActivityLogSubscriber {
+    fn name(&self) -> &'static str {
+        "activity_log"
+    }
+
+    async fn handle(&self, event_id: Uuid, event: &DomainEvent) -> Result<(), AppError> {
+        let DomainEvent::ChatActivity {
+            chat_id,
+            actor_id,
+            action,
+            details,
+            occurred_at,
+        } = event
+        else {
+            return Ok(());
+        };
+
+        ActivityLog::insert(activity_log::ActiveModel {
+            id: Set(event_id),
+            entity_type: Set("chat".to_string()),
+            entity_id: Set(*chat_id),
+            actor_id: Set(Some(*actor_id)),
+            action: Set(action.clone()),
+            details: Set(details.clone()),
+            ip_address: Set(None),
+            user_agent: Set(None),
+            created_at: Set(*occurred_at),
+        })
+        .on_conflict(
+            OnConflict::column(activity_log::Column::Id)
+                .do_nothing()
+                .to_owned(),
+        )
+        .exec_without_returning(&self.db)
+        .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,99 @@
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+const CACHE_LIMIT: usize = 10_000;
+
+/// Approximate location for an IP, for display only. Lookups go to the HTTP
+/// service in `AEGIS_GEOIP__LOOKUP_URL` (with an `{ip}` placeholder) when one
+/// is configured; without it only private addresses are recognised.
+#[derive(Clone)]
+pub struct GeoIpService {
+    http: reqwest::Client,
+    lookup_url: Option<String>,
+    cache: Arc<RwLock<HashMap<String, Option<String>>>>,
+}
+
+impl GeoIpService {
+    pub fn new(lookup_url: Option<String>) -> Self {
+        Self {
+            http: reqwest::Client::builder()
+                .timeout(Duration::from_secs(2))
+                .build()
+                .unwrap_or_default(),
+            lookup_url,
+            cache: Arc::new(RwLock::new(HashMap::new())),
+        }
+    }
+
+    pub async fn locate(&self, ip_address: &str) -> Option<String> {
+        let ip: IpAddr = ip_address.parse().ok()?;
+        if is_local(&ip) {
+            return Some("Local network".to_string());
+        }
+
+        if let Some(cached) = self
+            .cache
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(ip_address)
+        {
+            return cached.clone();
+        }
+
+        let url = self.lookup_url.as_ref()?.replace("{ip}", ip_address);
+        let location = match self.http.get(&url).send().await {
+            Ok(response) => response
+                .json::<serde_json::Value>()
+                .await
+                .ok()
+                .and_then(|body| describe(&body)),
+            Err(e) => {
+                // Not cached, so a provider outage doesn't stick
+                tracing::debug!("GeoIP lookup failed for {}: {}", ip_address, e);
+                return None;
+            }
+        };
+
+        let mut cache = self.cache.write().unwrap_or_else(|e| e.into_inner());
+        if cache.len() >= CACHE_LIMIT {
+            cache.clear();
+        }
+        cache.insert(ip_address.to_string(), location.clone());
+        location
+    }
+}
+
+fn is_local(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => v4.is_loopback() || v4.is_private() || v4.is_link_local(),
+        // fc00::/7 is the IPv6 unique local range
+        IpAddr::V6(v6) => v6.is_loopback() || (v6.segments()[0] & 0xfe00) == 0xfc00,
+    }
+}
+
+/// "City, Region, Country" from whichever field names the provider uses.
+fn describe(body: &serde_json::Value) -> Option<String> {
+    let field = |names: &[&str]| {
+        names
+            .iter()
+            .find_map(|n| body.get(*n).and_then(|v| v.as_str()))
+            .filter(|v| !v.is_empty())
+    };
+
+    let parts: Vec<&str> = [
+        field(&["city"]),
+        field(&["region", "regionName", "region_name"]),
+        field(&["country_name", "country"]),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+
+    if parts.is_empty() {
+        None
+    } else {
+        Some(parts.join(", "))
+    }
+}
@@ -0,0 +1,209 @@
+use crate::models::postgres::{account_lockout, login_failure, AccountLockout, LoginFailure};
+use crate::utils::errors::AppError;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use uuid::Uuid;
+
+// Per-account: lock after this many consecutive failures
+const ACCOUNT_MAX_FAILURES: i32 = 5;
+// First lock lasts this long, doubling with each consecutive lock up to the cap
+const BASE_LOCKOUT_MINUTES: i64 = 5;
+const MAX_LOCKOUT_MINUTES: i64 = 24 * 60;
+
+// Sliding windows over recent failures, independent of any one account
+const WINDOW_MINUTES: i64 = 15;
+const IP_MAX_FAILURES: u64 = 20;
+const EMAIL_MAX_FAILURES: u64 = 10;
+
+const FAILURE_RETENTION_HOURS: i64 = 24;
+
+/// Brute-force and credential-stuffing protection for password logins.
+#[derive(Clone)]
+pub struct LoginProtectionService {
+    db: DatabaseConnection,
+}
+
+impl LoginProtectionService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn normalize_email(email: &str) -> String {
+        email.trim().to_lowercase()
+    }
+
+    /// Rejects the attempt before any password check when the client IP or the
+    /// targeted email has failed too often recently.
+    pub async fn check_client(
+        &self,
+        ip_address: Option<&str>,
+        email: &str,
+    ) -> Result<(), AppError> {
+        let since = Utc::now() - Duration::minutes(WINDOW_MINUTES);
+
+        if let Some(ip) = ip_address {
+            let ip_failures = LoginFailure::find()
+                .filter(login_failure::Column::IpAddress.eq(ip))
+                .filter(login_failure::Column::CreatedAt.gt(since))
+                .count(&self.db)
+                .await?;
+            if ip_failures >= IP_MAX_FAILURES {
+                return Err(AppError::RateLimited);
+            }
+        }
+
+        let email_failures = LoginFailure::find()
+            .filter(login_failure::Column::Email.eq(Self::normalize_email(email)))
+            .filter(login_failure::Column::CreatedAt.gt(since))
+            .count(&self.db)
+            .await?;
+        if email_failures >= EMAIL_MAX_FAILURES {
+            return Err(AppError::RateLimited);
+        }
+
+        Ok(())
+    }
+
+    pub async fn get_lockout(
+        &self,
+        user_id: Uuid,
+        user_type: &str,
+    ) -> Result<Option<account_lockout::Model>, AppError> {
+        Ok(AccountLockout::find()
+            .filter(account_lockout::Column::UserId.eq(user_id))
+            .filter(account_lockout::Column::UserType.eq(user_type))
+            .one(&self.db)
+            .await?)
+    }
+
+    /// Rejects a locked account. Checked before the password so a locked
+    /// account can't be used as a password oracle.
+    pub async fn check_account(&self, user_id: Uuid, user_type: &str) -> Result<(), AppError> {
+        let locked = self
+            .get_lockout(user_id, user_type)
+            .await?
+            .and_then(|l| l.locked_until)
+            .is_some_and(|until| until > Utc::now());
+
+        if locked {
+            return Err(AppError::RateLimited);
+        }
+        Ok(())
+    }
+
+    /// Records a failed password check. When the failure belongs to a known
+    /// account and trips the threshold, the account is locked and the lock
+    /// expiry is returned.
+    pub async fn record_failure(
+        &self,
+        email: &str,
+        ip_address: Option<String>,
+        account: Option<(Uuid, &str)>,
+    ) -> Result<Option<DateTime<Utc>>, AppError> {
+        let now = Utc::now();
+
+        login_failure::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            email: Set(Self::normalize_email(email)),
+            ip_address: Set(ip_address),
+            user_id: Set(account.map(|(id, _)| id)),
+            user_type: Set(account.map(|(_, t)| t.to_string())),
+            created_at: Set(now),
+        }
+        .insert(&self.db)
+        .await?;
+
+        let Some((user_id, user_type)) = account else {
+            return Ok(None);
+        };
+
+        // Concurrent failures each count: the increment happens in the upsert
+        let lockout = AccountLockout::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO account_lockouts (id, user_id, user_type, failed_attempts, last_failed_at)
+                   VALUES (gen_random_uuid(), $1, $2, 1, $3)
+                   ON CONFLICT (user_id, user_type) DO UPDATE SET
+                       failed_attempts = account_lockouts.failed_attempts + 1,
+                       last_failed_at = EXCLUDED.last_failed_at
+                   RETURNING *"#,
+                [user_id.into(), user_type.into(), now.into()],
+            ))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::InternalServerError)?;
+        if lockout.failed_attempts < ACCOUNT_MAX_FAILURES {
+            return Ok(None);
+        }
+
+        // 5, 10, 20, 40 ... minutes, capped at a day. Only the failure that
+        // resets the counter locks, so racing failures lock once.
+        let minutes =
+            (BASE_LOCKOUT_MINUTES << lockout.lockout_count.clamp(0, 16)).min(MAX_LOCKOUT_MINUTES);
+        let until = now + Duration::minutes(minutes);
+        let locked = AccountLockout::update_many()
+            .col_expr(account_lockout::Column::FailedAttempts, Expr::value(0))
+            .col_expr(
+                account_lockout::Column::LockoutCount,
+                Expr::col(account_lockout::Column::LockoutCount).add(1),
+            )
+            .col_expr(account_lockout::Column::LockedUntil, Expr::value(until))
+            .filter(account_lockout::Column::Id.eq(lockout.id))
+            .filter(account_lockout::Column::FailedAttempts.gte(ACCOUNT_MAX_FAILURES))
+            .exec(&self.db)
+            .await?;
+
+        Ok((locked.rows_affected == 1).then_some(until))
+    }
+
+    /// A successful login ends the backoff streak.
+    pub async fn record_success(&self, user_id: Uuid, user_type: &str) -> Result<(), AppError> {
+        if let Some(lockout) = self.get_lockout(user_id, user_type).await? {
+            if lockout.failed_attempts == 0 && lockout.lockout_count == 0 {
+                return Ok(());
+            }
+            let mut update: account_lockout::ActiveModel = lockout.into();
+            update.failed_attempts = Set(0);
+            update.lockout_count = Set(0);
+            update.locked_until = Set(None);
+            update.updated_at = Set(Utc::now());
+            update.update(&self.db).await?;
+        }
+        Ok(())
+    }
+
+    /// Clears the lock, the backoff streak and the account's recent failures,
+    /// so the email window doesn't keep blocking it. Returns whether the
+    /// account was locked.
+    pub async fn unlock(&self, user_id: Uuid, user_type: &str) -> Result<bool, AppError> {
+        let was_locked = self
+            .get_lockout(user_id, user_type)
+            .await?
+            .and_then(|l| l.locked_until)
+            .is_some_and(|until| until > Utc::now());
+
+        AccountLockout::delete_many()
+            .filter(account_lockout::Column::UserId.eq(user_id))
+            .filter(account_lockout::Column::UserType.eq(user_type))
+            .exec(&self.db)
+            .await?;
+        LoginFailure::delete_many()
+            .filter(login_failure::Column::UserId.eq(user_id))
+            .exec(&self.db)
+            .await?;
+
+        Ok(was_locked)
+    }
+
+    pub async fn purge_old_failures(&self) -> Result<u64, AppError> {
+        let result = LoginFailure::delete_many()
+            .filter(
+                login_failure::Column::CreatedAt
+                    .lt(Utc::now() - Duration::hours(FAILURE_RETENTION_HOURS)),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
@@ -1,44 +1,74 @@
 pub mod admin_service;
+pub mod announcement_service;
 pub mod api_key_service;
 pub mod audit_service;
 pub mod auth_service;
 pub mod battle_service;
+pub mod chat_channel_service;
+pub mod chat_realtime_service;
 pub mod chat_service;
 pub mod community_service;
+pub mod consumed_token_service;
 pub mod dashboard_service;
+pub mod direct_message_service;
 pub mod email_service;
+pub mod event_subscribers;
+pub mod geo_ip_service;
+pub mod login_protection_service;
 pub mod minio_monitor;
+pub mod moderation_service;
+pub mod moderation_stages;
+pub mod oauth_service;
+pub mod organization_member_service;
 pub mod organization_service;
+pub mod outbox_service;
 pub mod player_game_stats_service;
 pub mod player_service;
 pub mod rate_limit_service;
 pub mod reward_service;
 pub mod s3_service;
 pub mod session_service;
+pub mod suspension_service;
 pub mod team_service;
 pub mod tournament_service;
 pub mod tournament_team_invite_service;
 pub mod tournament_team_service;
 pub mod transaction_service;
+pub mod two_factor_service;
+pub mod webhook_service;
 
 pub use admin_service::AdminService;
+pub use announcement_service::AnnouncementService;
 pub use api_key_service::ApiKeyService;
 pub use audit_service::AuditService;
 pub use auth_service::AuthService;
 pub use battle_service::BattleService;
+pub use chat_channel_service::ChatChannelService;
+pub use chat_realtime_service::ChatRealtimeService;
 pub use chat_service::ChatService;
 pub use community_service::CommunityService;
+pub use consumed_token_service::ConsumedTokenService;
 pub use dashboard_service::DashboardService;
+pub use direct_message_service::DirectMessageService;
 pub use email_service::EmailService;
+pub use geo_ip_service::GeoIpService;
+pub use login_protection_service::LoginProtectionService;
+pub use moderation_service::ModerationService;
+pub use oauth_service::OAuthService;
+pub use organization_member_service::OrganizationMemberService;
 pub use organization_service::OrganizationService;
+pub use outbox_service::OutboxService;
 pub use player_game_stats_service::PlayerGameStatsService;
 pub use player_service::PlayerService;
 pub use rate_limit_service::RateLimitService;
 pub use reward_service::RewardService;
 pub use s3_service::S3Service;
 pub use session_service::SessionService;
+pub use suspension_service::SuspensionService;
 pub use team_service::TeamService;
 pub use tournament_service::TournamentService;
 pub use tournament_team_invite_service::TournamentTeamInviteService;
 pub use tournament_team_service::TournamentTeamService;
 pub use transaction_service::TransactionService;
+pub use two_factor_service::TwoFactorService;
+pub use webhook_service::WebhookService;
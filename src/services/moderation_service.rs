@@ -0,0 +1,409 @@
+use crate::models::enums::{ModeratedContent, ModerationStatus, ReportReason};
+use crate::models::postgres::{
+    chat_message, content_report, moderation_queue_item, ChatMessage, ContentReport,
+    ModerationQueueItem,
+};
+use crate::services::chat_service::ChatService;
+use crate::services::community_service::CommunityService;
+use crate::services::AuditService;
+use crate::utils::errors::AppError;
+use async_trait::async_trait;
+use chrono::Utc;
+use sea_orm::*;
+use serde::Serialize;
+use std::sync::Arc;
+use uuid::Uuid;
+
+// Characters of the content kept on a queue item for moderators
+const EXCERPT_CHARS: usize = 500;
+const MAX_REPORT_DETAILS_CHARS: usize = 1000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Verdict {
+    Flag,     // publish, then queue for review
+    Block,    // reject
+    Throttle, // reject as rate limited
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Finding {
+    pub stage: &'static str,
+    pub verdict: Verdict,
+    pub detail: String, // for moderators
+    #[serde(skip)]
+    pub notice: &'static str, // shown to the author when the content is rejected
+}
+
+/// Content on its way in. `edit_of` is set when an existing message is edited.
+pub struct Submission<'a> {
+    pub content_type: ModeratedContent,
+    pub author_id: Uuid,
+    pub text: &'a str,
+    pub edit_of: Option<Uuid>,
+}
+
+/// Stored content going to the review queue.
+pub struct QueuedContent<'a> {
+    pub content_type: ModeratedContent,
+    pub content_id: Uuid,
+    pub author_id: Uuid,
+    pub text: &'a str,
+}
+
+/// One step of the moderation pipeline. Stages run in order on every new or
+/// edited message and post; any `Block` or `Throttle` finding rejects it.
+#[async_trait]
+pub trait ModerationStage: Send + Sync {
+    /// Stable name, recorded with the stage's findings.
+    fn name(&self) -> &'static str;
+
+    async fn inspect(&self, submission: &Submission<'_>) -> Result<Vec<Finding>, AppError>;
+}
+
+/// Flags raised on content that was let through. Pass it to `queue_flags`
+/// once the content is stored.
+#[derive(Debug, Default)]
+pub struct Screening {
+    pub flags: Vec<Finding>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct QueueItemDetail {
+    #[serde(flatten)]
+    pub item: moderation_queue_item::Model,
+    pub reports: Vec<content_report::Model>,
+}
+
+/// A resolved item, with the chat message it removed so clients can be told.
+pub struct Resolution {
+    pub item: moderation_queue_item::Model,
+    pub removed_message: Option<chat_message::Model>,
+}
+
+#[derive(Clone)]
+pub struct ModerationService {
+    db: DatabaseConnection,
+    audit_service: AuditService,
+    stages: Arc<Vec<Arc<dyn ModerationStage>>>,
+}
+
+impl ModerationService {
+    pub fn new(
+        db: DatabaseConnection,
+        audit_service: AuditService,
+        stages: Vec<Arc<dyn ModerationStage>>,
+    ) -> Self {
+        Self {
+            db,
+            audit_service,
+            stages: Arc::new(stages),
+        }
+    }
+
+    /// Runs every stage. Rejected content is logged and comes back as an
+    /// error for the author; flagged content is allowed with its flags.
+    pub async fn screen(&self, submission: &Submission<'_>) -> Result<Screening, AppError> {
+        let mut findings = Vec::new();
+        for stage in self.stages.iter() {
+            findings.extend(stage.inspect(submission).await?);
+        }
+
+        let Some(rejection) = findings.iter().find(|f| f.verdict != Verdict::Flag) else {
+            return Ok(Screening { flags: findings });
+        };
+        let error = match rejection.verdict {
+            Verdict::Throttle => AppError::RateLimited,
+            _ => AppError::Validation(rejection.notice.to_string()),
+        };
+
+        self.audit(
+            submission.author_id,
+            "moderation_block",
+            None,
+            serde_json::json!({
+                "content_type": submission.content_type,
+                "edit_of": submission.edit_of,
+                "findings": findings,
+            }),
+        )
+        .await;
+        Err(error)
+    }
+
+    /// Queues stored content that screening flagged. Does nothing without flags.
+    pub async fn queue_flags(
+        &self,
+        screening: Screening,
+        content: QueuedContent<'_>,
+    ) -> Result<(), AppError> {
+        if screening.flags.is_empty() {
+            return Ok(());
+        }
+
+        let now = Utc::now();
+        let reasons: Vec<serde_json::Value> = screening
+            .flags
+            .iter()
+            .map(|f| {
+                serde_json::json!({
+                    "source": "filter",
+                    "stage": f.stage,
+                    "detail": f.detail,
+                    "at": now,
+                })
+            })
+            .collect();
+        let item = Self::enqueue(&self.db, &content, reasons, 0).await?;
+
+        self.audit(
+            content.author_id,
+            "moderation_flag",
+            Some(item.id),
+            serde_json::json!({
+                "content_type": content.content_type,
+                "content_id": content.content_id,
+                "findings": screening.flags,
+            }),
+        )
+        .await;
+        Ok(())
+    }
+
+    /// Records a player's report and puts the content in the review queue.
+    pub async fn report(
+        &self,
+        reporter_id: Uuid,
+        content: QueuedContent<'_>,
+        reason: ReportReason,
+        details: Option<String>,
+    ) -> Result<content_report::Model, AppError> {
+        if content.author_id == reporter_id {
+            return Err(AppError::Validation(
+                "You can't report your own content".to_string(),
+            ));
+        }
+        let details = details
+            .map(|d| d.trim().to_string())
+            .filter(|d| !d.is_empty());
+        if details
+            .as_ref()
+            .is_some_and(|d| d.chars().count() > MAX_REPORT_DETAILS_CHARS)
+        {
+            return Err(AppError::Validation(format!(
+                "Report details are limited to {} characters",
+                MAX_REPORT_DETAILS_CHARS
+            )));
+        }
+
+        let already_reported = ContentReport::find()
+            .filter(content_report::Column::ContentType.eq(content.content_type))
+            .filter(content_report::Column::ContentId.eq(content.content_id))
+            .filter(content_report::Column::ReporterId.eq(reporter_id))
+            .one(&self.db)
+            .await?
+            .is_some();
+        if already_reported {
+            return Err(AppError::Validation(
+                "You've already reported this".to_string(),
+            ));
+        }
+
+        let txn = self.db.begin().await?;
+        let item = Self::enqueue(
+            &txn,
+            &content,
+            vec![serde_json::json!({
+                "source": "report",
+                "reason": reason,
+                "at": Utc::now(),
+            })],
+            1,
+        )
+        .await?;
+        let report = content_report::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            content_type: Set(content.content_type),
+            content_id: Set(content.content_id),
+            reporter_id: Set(reporter_id),
+            reason: Set(reason),
+            details: Set(details),
+            queue_item_id: Set(item.id),
+            created_at: Set(Utc::now()),
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+
+        self.audit(
+            reporter_id,
+            "content_report",
+            Some(item.id),
+            serde_json::json!({
+                "content_type": content.content_type,
+                "content_id": content.content_id,
+                "reason": reason,
+            }),
+        )
+        .await;
+        Ok(report)
+    }
+
+    /// The review queue, oldest first so nothing waits forever.
+    pub async fn list_queue(
+        &self,
+        status: ModerationStatus,
+        content_type: Option<ModeratedContent>,
+        limit: u64,
+        offset: u64,
+    ) -> Result<Vec<moderation_queue_item::Model>, AppError> {
+        let mut query =
+            ModerationQueueItem::find().filter(moderation_queue_item::Column::Status.eq(status));
+        if let Some(content_type) = content_type {
+            query = query.filter(moderation_queue_item::Column::ContentType.eq(content_type));
+        }
+        let query = if status == ModerationStatus::Pending {
+            query.order_by_asc(moderation_queue_item::Column::CreatedAt)
+        } else {
+            query.order_by_desc(moderation_queue_item::Column::ReviewedAt)
+        };
+
+        Ok(query.limit(limit).offset(offset).all(&self.db).await?)
+    }
+
+    pub async fn get_item(&self, item_id: Uuid) -> Result<QueueItemDetail, AppError> {
+        let item = ModerationQueueItem::find_by_id(item_id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let reports = ContentReport::find()
+            .filter(content_report::Column::QueueItemId.eq(item_id))
+            .order_by_asc(content_report::Column::CreatedAt)
+            .all(&self.db)
+            .await?;
+
+        Ok(QueueItemDetail { item, reports })
+    }
+
+    /// Closes a pending item. `Removed` takes the content down: chat messages
+    /// and comments are tombstoned, posts soft-deleted.
+    pub async fn resolve(
+        &self,
+        item_id: Uuid,
+        moderator_id: Uuid,
+        status: ModerationStatus,
+        note: Option<String>,
+    ) -> Result<Resolution, AppError> {
+        if status == ModerationStatus::Pending {
+            return Err(AppError::Validation(
+                "Resolve an item as removed or dismissed".to_string(),
+            ));
+        }
+
+        let txn = self.db.begin().await?;
+        let item = ModerationQueueItem::find_by_id(item_id)
+            .lock_exclusive()
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        if item.status != ModerationStatus::Pending {
+            return Err(AppError::Validation(
+                "This item has already been reviewed".to_string(),
+            ));
+        }
+
+        let mut removed_message = None;
+        if status == ModerationStatus::Removed {
+            match item.content_type {
+                ModeratedContent::ChatMessage => {
+                    let message = ChatMessage::find_by_id(item.content_id).one(&txn).await?;
+                    if let Some(message) = message.filter(|m| m.deleted_at.is_none()) {
+                        removed_message =
+                            Some(ChatService::tombstone(&txn, message, moderator_id).await?);
+                    }
+                }
+                ModeratedContent::CommunityPost => {
+                    CommunityService::remove_post(&txn, item.content_id).await?;
+                }
+                ModeratedContent::PostComment => {
+                    CommunityService::tombstone_comment(&txn, item.content_id).await?;
+                }
+            }
+        }
+
+        let mut update: moderation_queue_item::ActiveModel = item.into();
+        update.status = Set(status);
+        update.reviewed_by = Set(Some(moderator_id));
+        update.reviewed_at = Set(Some(Utc::now()));
+        update.resolution_note = Set(note.filter(|n| !n.trim().is_empty()));
+        let item = update.update(&txn).await?;
+        txn.commit().await?;
+
+        Ok(Resolution {
+            item,
+            removed_message,
+        })
+    }
+
+    /// Adds `reasons` to the content's open queue item, opening one if needed.
+    async fn enqueue<C: ConnectionTrait>(
+        conn: &C,
+        content: &QueuedContent<'_>,
+        reasons: Vec<serde_json::Value>,
+        reports: i32,
+    ) -> Result<moderation_queue_item::Model, AppError> {
+        let excerpt: String = content.text.chars().take(EXCERPT_CHARS).collect();
+
+        ModerationQueueItem::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO moderation_queue
+                       (id, content_type, content_id, author_id, excerpt, reasons, report_count)
+                   VALUES (gen_random_uuid(), $1, $2, $3, $4, $5, $6)
+                   ON CONFLICT (content_type, content_id) WHERE status = 'pending'
+                   DO UPDATE SET
+                       excerpt = EXCLUDED.excerpt,
+                       reasons = moderation_queue.reasons || EXCLUDED.reasons,
+                       report_count = moderation_queue.report_count + EXCLUDED.report_count,
+                       updated_at = NOW()
+                   RETURNING *"#,
+                [
+                    content.content_type.to_value().into(),
+                    content.content_id.into(),
+                    content.author_id.into(),
+                    excerpt.into(),
+                    serde_json::Value::Array(reasons).into(),
+                    reports.into(),
+                ],
+            ))
+            .one(conn)
+            .await?
+            .ok_or(AppError::InternalServerError)
+    }
+
+    async fn audit(
+        &self,
+        player_id: Uuid,
+        action: &str,
+        queue_item_id: Option<Uuid>,
+        details: serde_json::Value,
+    ) {
+        let _ = self
+            .audit_service
+            .log_action(
+                Some(player_id),
+                Some("player".to_string()),
+                None,
+                action.to_string(),
+                Some("moderation".to_string()),
+                queue_item_id,
+                None,
+                None,
+                true,
+                None,
+                None,
+                Some(details),
+            )
+            .await;
+    }
+}
@@ -0,0 +1,323 @@
+use crate::config::moderation::{LinkConfig, ModerationConfig, SpamConfig, WordListConfig};
+use crate::models::enums::ModeratedContent;
+use crate::services::moderation_service::{Finding, ModerationStage, Submission, Verdict};
+use crate::utils::errors::AppError;
+use async_trait::async_trait;
+use regex::Regex;
+use sea_orm::*;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+// Endings a listed word still matches with, after repeated letters collapse
+const WORD_SUFFIXES: [&str; 9] = ["s", "es", "ed", "er", "ers", "ing", "in", "y", "z"];
+
+/// The stages every deployment runs, in order: word list, links, then spam.
+pub fn standard_stages(
+    db: &DatabaseConnection,
+    config: &ModerationConfig,
+) -> Vec<Arc<dyn ModerationStage>> {
+    vec![
+        Arc::new(WordFilter::new(&config.words)),
+        Arc::new(LinkFilter::new(&config.links)),
+        Arc::new(SpamFilter {
+            db: db.clone(),
+            config: config.spam.clone(),
+        }),
+    ]
+}
+
+fn leet(c: char) -> Option<char> {
+    Some(match c {
+        '0' => 'o',
+        '1' | '!' | '|' => 'i',
+        '3' => 'e',
+        '4' | '@' => 'a',
+        '5' | '$' => 's',
+        '7' | '+' => 't',
+        '8' => 'b',
+        '9' => 'g',
+        _ => return None,
+    })
+}
+
+fn collapse_repeats(word: &str) -> String {
+    let mut collapsed = String::with_capacity(word.len());
+    for c in word.chars() {
+        if !collapsed.ends_with(c) {
+            collapsed.push(c);
+        }
+    }
+    collapsed
+}
+
+/// Lowercased words with leetspeak undone and repeated letters collapsed.
+/// Symbols only stand in for letters inside a word, so `sh!t` reads as
+/// `shit` while a trailing `!` is punctuation. Runs of single characters are
+/// joined, so `s h i t` and `s.h.i.t` read as one word.
+fn normalize_words(text: &str) -> Vec<String> {
+    let chars: Vec<char> = text.chars().flat_map(char::to_lowercase).collect();
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+
+    for (i, &c) in chars.iter().enumerate() {
+        let mapped = if c.is_alphanumeric() {
+            leet(c).unwrap_or(c)
+        } else {
+            let next_is_word = chars.get(i + 1).is_some_and(|n| n.is_alphanumeric());
+            match leet(c) {
+                Some(letter) if next_is_word => letter,
+                _ => {
+                    if !current.is_empty() {
+                        tokens.push(std::mem::take(&mut current));
+                    }
+                    continue;
+                }
+            }
+        };
+        current.push(mapped);
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    let mut words = Vec::with_capacity(tokens.len());
+    let mut run = String::new();
+    for token in tokens {
+        if token.chars().count() == 1 {
+            run.push_str(&token);
+            continue;
+        }
+        if !run.is_empty() {
+            words.push(collapse_repeats(&std::mem::take(&mut run)));
+        }
+        words.push(collapse_repeats(&token));
+    }
+    if !run.is_empty() {
+        words.push(collapse_repeats(&run));
+    }
+    words
+}
+
+/// Matches the configured block and flag lists against normalized words.
+/// Both lists map the normalized form to the word as configured.
+pub struct WordFilter {
+    block: HashMap<String, String>,
+    flag: HashMap<String, String>,
+}
+
+impl WordFilter {
+    pub fn new(config: &WordListConfig) -> Self {
+        let compile = |words: &[String]| -> HashMap<String, String> {
+            words
+                .iter()
+                .map(|w| (normalize_words(w).concat(), w.trim().to_lowercase()))
+                .filter(|(normalized, _)| !normalized.is_empty())
+                .collect()
+        };
+        Self {
+            block: compile(&config.block),
+            flag: compile(&config.flag),
+        }
+    }
+
+    fn find<'a>(list: &'a HashMap<String, String>, words: &[String]) -> Option<&'a str> {
+        words
+            .iter()
+            .find_map(|word| {
+                list.get(word.as_str()).or_else(|| {
+                    WORD_SUFFIXES.iter().find_map(|suffix| {
+                        word.strip_suffix(suffix).and_then(|stem| list.get(stem))
+                    })
+                })
+            })
+            .map(String::as_str)
+    }
+}
+
+#[async_trait]
+impl ModerationStage for WordFilter {
+    fn name(&self) -> &'static str {
+        "word_list"
+    }
+
+    async fn inspect(&self, submission: &Submission<'_>) -> Result<Vec<Finding>, AppError> {
+        let words = normalize_words(submission.text);
+
+        if let Some(word) = Self::find(&self.block, &words) {
+            return Ok(vec![Finding {
+                stage: self.name(),
+                verdict: Verdict::Block,
+                detail: format!("blocked word \"{}\"", word),
+                notice: "This contains language that isn't allowed",
+            }]);
+        }
+        Ok(Self::find(&self.flag, &words)
+            .map(|word| Finding {
+                stage: self.name(),
+                verdict: Verdict::Flag,
+                detail: format!("flagged word \"{}\"", word),
+                notice: "",
+            })
+            .into_iter()
+            .collect())
+    }
+}
+
+/// Checks every link's host against the allow-list.
+pub struct LinkFilter {
+    pattern: Regex,
+    allowed: Vec<String>,
+    block_others: bool,
+}
+
+impl LinkFilter {
+    pub fn new(config: &LinkConfig) -> Self {
+        Self {
+            // Anything with a scheme or `www.`, or a bare domain on a common TLD
+            pattern: Regex::new(
+                r"(?i)(?:https?://|www\.)([^\s/:?#<>]+)|\b((?:[a-z0-9-]+\.)+(?:com|net|org|gg|io|tv|co|me|ly|xyz|ru|info|biz|app|dev|link|click|shop|to))\b",
+            )
+            .expect("link pattern is valid"),
+            allowed: config
+                .allowed_domains
+                .iter()
+                .map(|d| d.trim().trim_start_matches("www.").to_lowercase())
+                .filter(|d| !d.is_empty())
+                .collect(),
+            block_others: config.block_others,
+        }
+    }
+
+    fn is_allowed(&self, host: &str) -> bool {
+        self.allowed.iter().any(|domain| {
+            host == domain
+                || host
+                    .strip_suffix(domain.as_str())
+                    .is_some_and(|sub| sub.ends_with('.'))
+        })
+    }
+}
+
+#[async_trait]
+impl ModerationStage for LinkFilter {
+    fn name(&self) -> &'static str {
+        "links"
+    }
+
+    async fn inspect(&self, submission: &Submission<'_>) -> Result<Vec<Finding>, AppError> {
+        let mut hosts: Vec<String> = Vec::new();
+        for captures in self.pattern.captures_iter(submission.text) {
+            let Some(host) = captures.get(1).or_else(|| captures.get(2)) else {
+                continue;
+            };
+            let host = host
+                .as_str()
+                .trim_end_matches('.')
+                .to_lowercase()
+                .trim_start_matches("www.")
+                .to_string();
+            if !self.is_allowed(&host) && !hosts.contains(&host) {
+                hosts.push(host);
+            }
+        }
+        if hosts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        Ok(vec![Finding {
+            stage: self.name(),
+            verdict: if self.block_others {
+                Verdict::Block
+            } else {
+                Verdict::Flag
+            },
+            detail: format!("links to {}", hosts.join(", ")),
+            notice: "Links to that site aren't allowed here",
+        }])
+    }
+}
+
+#[derive(Debug, FromQueryResult)]
+struct RecentActivity {
+    recent: i64,
+    duplicates: i64,
+}
+
+/// Throttles authors who post too fast and rejects the same text repeated
+/// within the duplicate window. Edits are not counted.
+pub struct SpamFilter {
+    db: DatabaseConnection,
+    config: SpamConfig,
+}
+
+#[async_trait]
+impl ModerationStage for SpamFilter {
+    fn name(&self) -> &'static str {
+        "spam"
+    }
+
+    async fn inspect(&self, submission: &Submission<'_>) -> Result<Vec<Finding>, AppError> {
+        if submission.edit_of.is_some() {
+            return Ok(Vec::new());
+        }
+        // `body` is the stored form of what gets screened; posts are screened
+        // as their title and body on separate lines
+        let (table, author, body) = match submission.content_type {
+            ModeratedContent::ChatMessage => ("chat_messages", "sender_id", "message"),
+            ModeratedContent::CommunityPost => (
+                "community_posts",
+                "author_id",
+                "COALESCE(title, '') || E'\\n' || content",
+            ),
+            ModeratedContent::PostComment => ("post_comments", "author_id", "content"),
+        };
+        let text = submission.text.trim();
+
+        let activity = RecentActivity::find_by_statement(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            format!(
+                r#"SELECT
+                       COUNT(*) FILTER (WHERE created_at > NOW() - make_interval(secs => $2)) AS recent,
+                       COUNT(*) FILTER (WHERE $4 <> '' AND lower(btrim({body})) = lower($4)
+                                        AND created_at > NOW() - make_interval(secs => $3)) AS duplicates
+                   FROM {table}
+                   WHERE {author} = $1
+                     AND created_at > NOW() - make_interval(secs => GREATEST($2, $3))"#
+            ),
+            [
+                submission.author_id.into(),
+                (self.config.flood_window_secs as f64).into(),
+                (self.config.duplicate_window_secs as f64).into(),
+                text.into(),
+            ],
+        ))
+        .one(&self.db)
+        .await?
+        .ok_or(AppError::InternalServerError)?;
+
+        let mut findings = Vec::new();
+        if activity.recent as u64 >= self.config.flood_max {
+            findings.push(Finding {
+                stage: self.name(),
+                verdict: Verdict::Throttle,
+                detail: format!(
+                    "{} sent in the last {}s",
+                    activity.recent, self.config.flood_window_secs
+                ),
+                notice: "You're posting too fast",
+            });
+        }
+        if activity.duplicates as u64 >= self.config.max_duplicates {
+            findings.push(Finding {
+                stage: self.name(),
+                verdict: Verdict::Block,
+                detail: format!(
+                    "{} identical copies in the last {}s",
+                    activity.duplicates, self.config.duplicate_window_secs
+                ),
+                notice: "You've already posted that",
+            });
+        }
+        Ok(findings)
+    }
+}
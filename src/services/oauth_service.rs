@@ -0,0 +1,626 @@
+use crate::config::{OAuthClientConfig, OAuthSettings};
+use crate::models::enums::OAuthProvider;
+use crate::models::postgres::{
+    oauth_state, player, player_identity, OAuthState, Player, PlayerIdentity,
+};
+use crate::utils::errors::AppError;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{Duration, Utc};
+use jsonwebtoken::jwk::JwkSet;
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng};
+use sea_orm::*;
+use serde::Deserialize;
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+const STATE_TTL_MINUTES: i64 = 10;
+
+/// Endpoints and scopes for one provider after applying config overrides.
+struct ProviderEndpoints {
+    authorize_url: String,
+    token_url: String,
+    userinfo_url: String,
+    jwks_url: Option<String>,
+    issuer: Option<String>,
+    scopes: &'static str,
+}
+
+impl ProviderEndpoints {
+    /// Discord is plain OAuth2; Google and Twitch also return an OIDC ID token.
+    fn is_oidc(&self) -> bool {
+        self.jwks_url.is_some()
+    }
+}
+
+/// What we keep from a provider profile, normalized across providers.
+#[derive(Debug, Clone)]
+pub struct ExternalProfile {
+    pub provider: OAuthProvider,
+    pub subject: String,
+    pub email: Option<String>,
+    pub email_verified: bool,
+    pub username: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    id_token: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    nonce: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct DiscordUser {
+    id: String,
+    username: String,
+    email: Option<String>,
+    verified: Option<bool>,
+}
+
+#[derive(Deserialize)]
+struct OidcUserInfo {
+    sub: String,
+    email: Option<String>,
+    email_verified: Option<bool>,
+    name: Option<String>,
+    preferred_username: Option<String>,
+}
+
+/// A started authorization request. `binding` goes in a cookie on the browser
+/// that started it, and the callback only accepts the state alongside it.
+pub struct AuthorizationRequest {
+    pub url: String,
+    pub binding: String,
+}
+
+/// How a login callback matched an existing player.
+pub enum SocialLogin {
+    Existing(player::Model),
+    Linked(player::Model),
+}
+
+#[derive(Clone)]
+pub struct OAuthService {
+    db: DatabaseConnection,
+    http: reqwest::Client,
+    settings: OAuthSettings,
+}
+
+impl OAuthService {
+    pub fn new(db: DatabaseConnection, settings: OAuthSettings) -> Self {
+        Self {
+            db,
+            http: reqwest::Client::new(),
+            settings,
+        }
+    }
+
+    fn client(&self, provider: OAuthProvider) -> Result<&OAuthClientConfig, AppError> {
+        match provider {
+            OAuthProvider::Discord => self.settings.discord.as_ref(),
+            OAuthProvider::Google => self.settings.google.as_ref(),
+            OAuthProvider::Twitch => self.settings.twitch.as_ref(),
+        }
+        .ok_or(AppError::NotFound)
+    }
+
+    pub fn enabled_providers(&self) -> Vec<OAuthProvider> {
+        use strum::IntoEnumIterator;
+        OAuthProvider::iter()
+            .filter(|p| self.client(*p).is_ok())
+            .collect()
+    }
+
+    fn endpoints(&self, provider: OAuthProvider) -> Result<ProviderEndpoints, AppError> {
+        let client = self.client(provider)?;
+        let pick = |value: &Option<String>, default: &str| {
+            value.clone().unwrap_or_else(|| default.to_string())
+        };
+
+        Ok(match provider {
+            OAuthProvider::Discord => ProviderEndpoints {
+                authorize_url: pick(
+                    &client.authorize_url,
+                    "https://discord.com/oauth2/authorize",
+                ),
+                token_url: pick(&client.token_url, "https://discord.com/api/oauth2/token"),
+                userinfo_url: pick(&client.userinfo_url, "https://discord.com/api/users/@me"),
+                jwks_url: None,
+                issuer: None,
+                scopes: "identify email",
+            },
+            OAuthProvider::Google => ProviderEndpoints {
+                authorize_url: pick(
+                    &client.authorize_url,
+                    "https://accounts.google.com/o/oauth2/v2/auth",
+                ),
+                token_url: pick(&client.token_url, "https://oauth2.googleapis.com/token"),
+                userinfo_url: pick(
+                    &client.userinfo_url,
+                    "https://openidconnect.googleapis.com/v1/userinfo",
+                ),
+                jwks_url: Some(pick(
+                    &client.jwks_url,
+                    "https://www.googleapis.com/oauth2/v3/certs",
+                )),
+                issuer: Some(pick(&client.issuer, "https://accounts.google.com")),
+                scopes: "openid email profile",
+            },
+            OAuthProvider::Twitch => ProviderEndpoints {
+                authorize_url: pick(
+                    &client.authorize_url,
+                    "https://id.twitch.tv/oauth2/authorize",
+                ),
+                token_url: pick(&client.token_url, "https://id.twitch.tv/oauth2/token"),
+                userinfo_url: pick(&client.userinfo_url, "https://id.twitch.tv/oauth2/userinfo"),
+                jwks_url: Some(pick(&client.jwks_url, "https://id.twitch.tv/oauth2/keys")),
+                issuer: Some(pick(&client.issuer, "https://id.twitch.tv/oauth2")),
+                scopes: "openid user:read:email",
+            },
+        })
+    }
+
+    fn redirect_uri(&self, provider: OAuthProvider) -> String {
+        format!(
+            "{}/auth/oauth/{}/callback",
+            self.settings.callback_base_url.trim_end_matches('/'),
+            provider.as_str()
+        )
+    }
+
+    fn random_token(len: usize) -> String {
+        OsRng
+            .sample_iter(&Alphanumeric)
+            .take(len)
+            .map(char::from)
+            .collect()
+    }
+
+    /// Only same-site relative paths are accepted, so the callback can't be
+    /// turned into an open redirect.
+    pub fn sanitize_redirect(redirect_to: Option<String>) -> Option<String> {
+        redirect_to.filter(|r| r.starts_with('/') && !r.starts_with("//") && !r.contains('\\'))
+    }
+
+    pub fn frontend_url(&self, path: &str) -> String {
+        format!(
+            "{}{}",
+            self.settings.frontend_url.trim_end_matches('/'),
+            path
+        )
+    }
+
+    /// Hash of a state token, kept in the starting browser's cookie so the raw
+    /// state never sits next to the callback URL that carries it.
+    pub fn state_binding(state: &str) -> String {
+        URL_SAFE_NO_PAD.encode(Sha256::digest(state.as_bytes()))
+    }
+
+    /// Whether the callback's state was issued to the browser presenting `binding`.
+    pub fn binding_matches(state: &str, binding: Option<&str>) -> bool {
+        binding.is_some_and(|b| b == Self::state_binding(state))
+    }
+
+    /// Records a new authorization request and returns the provider URL to send
+    /// the browser to. `link_player_id` is set when linking from settings.
+    pub async fn authorization_url(
+        &self,
+        provider: OAuthProvider,
+        link_player_id: Option<Uuid>,
+        redirect_to: Option<String>,
+    ) -> Result<AuthorizationRequest, AppError> {
+        let client = self.client(provider)?;
+        let endpoints = self.endpoints(provider)?;
+
+        // Abandoned requests are cleaned up whenever a new one starts
+        OAuthState::delete_many()
+            .filter(oauth_state::Column::ExpiresAt.lt(Utc::now()))
+            .exec(&self.db)
+            .await?;
+
+        let state = Self::random_token(48);
+        let nonce = Self::random_token(32);
+        let code_verifier = Self::random_token(64);
+        let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+        oauth_state::ActiveModel {
+            state: Set(state.clone()),
+            provider: Set(provider),
+            code_verifier: Set(code_verifier),
+            nonce: Set(nonce.clone()),
+            link_player_id: Set(link_player_id),
+            redirect_to: Set(Self::sanitize_redirect(redirect_to)),
+            expires_at: Set(Utc::now() + Duration::minutes(STATE_TTL_MINUTES)),
+            created_at: Set(Utc::now()),
+        }
+        .insert(&self.db)
+        .await?;
+
+        let redirect_uri = self.redirect_uri(provider);
+        let mut params = vec![
+            ("response_type", "code"),
+            ("client_id", client.client_id.as_str()),
+            ("redirect_uri", redirect_uri.as_str()),
+            ("scope", endpoints.scopes),
+            ("state", state.as_str()),
+            ("code_challenge", code_challenge.as_str()),
+            ("code_challenge_method", "S256"),
+        ];
+        if endpoints.is_oidc() {
+            params.push(("nonce", nonce.as_str()));
+        }
+        if provider == OAuthProvider::Twitch {
+            // Twitch only puts the email in userinfo when it is asked for explicitly
+            params.push((
+                "claims",
+                r#"{"userinfo":{"email":null,"email_verified":null,"preferred_username":null}}"#,
+            ));
+        }
+
+        let url = reqwest::Url::parse_with_params(&endpoints.authorize_url, &params)
+            .map_err(|_| AppError::InternalServerError)?;
+        Ok(AuthorizationRequest {
+            url: url.to_string(),
+            binding: Self::state_binding(&state),
+        })
+    }
+
+    /// Looks up and deletes the pending request, so each state works once.
+    pub async fn consume_state(
+        &self,
+        provider: OAuthProvider,
+        state: &str,
+    ) -> Result<oauth_state::Model, AppError> {
+        let pending = OAuthState::find_by_id(state.to_string())
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::Validation(
+                "Invalid or expired login request".to_string(),
+            ))?;
+
+        let deleted = OAuthState::delete_by_id(state.to_string())
+            .exec(&self.db)
+            .await?;
+        if deleted.rows_affected != 1
+            || pending.provider != provider
+            || pending.expires_at < Utc::now()
+        {
+            return Err(AppError::Validation(
+                "Invalid or expired login request".to_string(),
+            ));
+        }
+
+        Ok(pending)
+    }
+
+    /// Redeems the authorization code with the PKCE verifier, checks the ID token
+    /// for OIDC providers and fetches the profile.
+    pub async fn exchange_code(
+        &self,
+        pending: &oauth_state::Model,
+        code: &str,
+    ) -> Result<ExternalProfile, AppError> {
+        let provider = pending.provider;
+        let client = self.client(provider)?;
+        let endpoints = self.endpoints(provider)?;
+        let redirect_uri = self.redirect_uri(provider);
+
+        let response = self
+            .http
+            .post(&endpoints.token_url)
+            .header("Accept", "application/json")
+            .form(&[
+                ("grant_type", "authorization_code"),
+                ("code", code),
+                ("redirect_uri", redirect_uri.as_str()),
+                ("client_id", client.client_id.as_str()),
+                ("client_secret", client.client_secret.as_str()),
+                ("code_verifier", pending.code_verifier.as_str()),
+            ])
+            .send()
+            .await
+            .map_err(|e| {
+                tracing::warn!("{} token request failed: {}", provider.as_str(), e);
+                AppError::Unauthorized
+            })?;
+        if !response.status().is_success() {
+            tracing::warn!(
+                "{} rejected the authorization code: {}",
+                provider.as_str(),
+                response.status()
+            );
+            return Err(AppError::Unauthorized);
+        }
+        let tokens: TokenResponse = response.json().await.map_err(|_| AppError::Unauthorized)?;
+
+        let id_token_subject = match &endpoints.jwks_url {
+            Some(jwks_url) => {
+                let id_token = tokens.id_token.as_deref().ok_or(AppError::Unauthorized)?;
+                Some(
+                    self.verify_id_token(
+                        id_token,
+                        jwks_url,
+                        endpoints.issuer.as_deref(),
+                        &client.client_id,
+                        &pending.nonce,
+                    )
+                    .await?,
+                )
+            }
+            None => None,
+        };
+
+        let request = self
+            .http
+            .get(&endpoints.userinfo_url)
+            .bearer_auth(&tokens.access_token);
+        // Twitch's Helix-style endpoints also want the client id
+        let request = if provider == OAuthProvider::Twitch {
+            request.header("Client-Id", &client.client_id)
+        } else {
+            request
+        };
+        let response = request
+            .send()
+            .await
+            .map_err(|_| AppError::Unauthorized)?
+            .error_for_status()
+            .map_err(|_| AppError::Unauthorized)?;
+
+        let profile = match provider {
+            OAuthProvider::Discord => {
+                let user: DiscordUser =
+                    response.json().await.map_err(|_| AppError::Unauthorized)?;
+                ExternalProfile {
+                    provider,
+                    subject: user.id,
+                    email: user.email,
+                    email_verified: user.verified.unwrap_or(false),
+                    username: Some(user.username),
+                }
+            }
+            OAuthProvider::Google | OAuthProvider::Twitch => {
+                let info: OidcUserInfo =
+                    response.json().await.map_err(|_| AppError::Unauthorized)?;
+                ExternalProfile {
+                    provider,
+                    subject: info.sub,
+                    email: info.email,
+                    email_verified: info.email_verified.unwrap_or(false),
+                    username: info.preferred_username.or(info.name),
+                }
+            }
+        };
+
+        // The userinfo response must describe the same user the ID token was issued for
+        if id_token_subject.is_some_and(|sub| sub != profile.subject) {
+            return Err(AppError::Unauthorized);
+        }
+
+        Ok(profile)
+    }
+
+    /// Checks signature, issuer, audience, expiry and nonce, returning the subject.
+    async fn verify_id_token(
+        &self,
+        id_token: &str,
+        jwks_url: &str,
+        issuer: Option<&str>,
+        client_id: &str,
+        expected_nonce: &str,
+    ) -> Result<String, AppError> {
+        let header = decode_header(id_token).map_err(|_| AppError::Unauthorized)?;
+        if header.alg != Algorithm::RS256 {
+            return Err(AppError::Unauthorized);
+        }
+
+        let jwks: JwkSet = self
+            .http
+            .get(jwks_url)
+            .send()
+            .await
+            .map_err(|_| AppError::InternalServerError)?
+            .json()
+            .await
+            .map_err(|_| AppError::InternalServerError)?;
+        let jwk = match header.kid.as_deref() {
+            Some(kid) => jwks.find(kid),
+            None => jwks.keys.first(),
+        }
+        .ok_or(AppError::Unauthorized)?;
+        let key = DecodingKey::from_jwk(jwk).map_err(|_| AppError::Unauthorized)?;
+
+        let mut validation = Validation::new(Algorithm::RS256);
+        validation.set_audience(&[client_id]);
+        if let Some(issuer) = issuer {
+            validation.set_issuer(&[issuer]);
+        }
+
+        let claims = decode::<IdTokenClaims>(id_token, &key, &validation)
+            .map_err(|_| AppError::Unauthorized)?
+            .claims;
+        if claims.nonce.as_deref() != Some(expected_nonce) {
+            return Err(AppError::Unauthorized);
+        }
+
+        Ok(claims.sub)
+    }
+
+    pub async fn find_identity(
+        &self,
+        provider: OAuthProvider,
+        subject: &str,
+    ) -> Result<Option<player_identity::Model>, AppError> {
+        Ok(PlayerIdentity::find()
+            .filter(player_identity::Column::Provider.eq(provider))
+            .filter(player_identity::Column::ProviderUserId.eq(subject))
+            .one(&self.db)
+            .await?)
+    }
+
+    pub async fn list_identities(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Vec<player_identity::Model>, AppError> {
+        Ok(PlayerIdentity::find()
+            .filter(player_identity::Column::PlayerId.eq(player_id))
+            .order_by_asc(player_identity::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Attaches a provider identity to a player and fills in the matching social
+    /// handle on the profile if it is still empty.
+    pub async fn link_identity(
+        &self,
+        player_id: Uuid,
+        profile: &ExternalProfile,
+    ) -> Result<player_identity::Model, AppError> {
+        if let Some(existing) = self
+            .find_identity(profile.provider, &profile.subject)
+            .await?
+        {
+            if existing.player_id == player_id {
+                return Ok(existing);
+            }
+            return Err(AppError::Validation(format!(
+                "This {} account is already linked to another player",
+                profile.provider.as_str()
+            )));
+        }
+
+        let already_linked = PlayerIdentity::find()
+            .filter(player_identity::Column::PlayerId.eq(player_id))
+            .filter(player_identity::Column::Provider.eq(profile.provider))
+            .one(&self.db)
+            .await?;
+        if already_linked.is_some() {
+            return Err(AppError::Validation(format!(
+                "A different {} account is already linked; unlink it first",
+                profile.provider.as_str()
+            )));
+        }
+
+        let now = Utc::now();
+        let identity = player_identity::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            player_id: Set(player_id),
+            provider: Set(profile.provider),
+            provider_user_id: Set(profile.subject.clone()),
+            email: Set(profile.email.clone()),
+            username: Set(profile.username.clone()),
+            last_login_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&self.db)
+        .await?;
+
+        if let (Some(handle), Some(player)) = (
+            profile.username.clone(),
+            Player::find_by_id(player_id).one(&self.db).await?,
+        ) {
+            let mut update: player::ActiveModel = player.clone().into();
+            let changed = match profile.provider {
+                OAuthProvider::Discord if player.discord_tag.is_empty() => {
+                    update.discord_tag = Set(handle);
+                    true
+                }
+                OAuthProvider::Twitch if player.twitch.is_empty() => {
+                    update.twitch = Set(handle);
+                    true
+                }
+                _ => false,
+            };
+            if changed {
+                update.updated_at = Set(now);
+                update.update(&self.db).await?;
+            }
+        }
+
+        Ok(identity)
+    }
+
+    /// Removes a linked identity, refusing to remove the last way to sign in.
+    pub async fn unlink_identity(
+        &self,
+        player_id: Uuid,
+        provider: OAuthProvider,
+    ) -> Result<(), AppError> {
+        let player = Player::find_by_id(player_id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        let identities = self.list_identities(player_id).await?;
+        let identity = identities
+            .iter()
+            .find(|i| i.provider == provider)
+            .ok_or(AppError::NotFound)?;
+
+        if !player.has_password && identities.len() == 1 {
+            return Err(AppError::Validation(
+                "Set a password before unlinking your only sign-in method".to_string(),
+            ));
+        }
+
+        PlayerIdentity::delete_by_id(identity.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Resolves the player for a login callback: a linked identity wins, then a
+    /// player with the same provider-verified email gets the identity linked.
+    /// Returns `None` when a new account should be created.
+    pub async fn resolve_login(
+        &self,
+        profile: &ExternalProfile,
+    ) -> Result<Option<SocialLogin>, AppError> {
+        if let Some(identity) = self
+            .find_identity(profile.provider, &profile.subject)
+            .await?
+        {
+            let mut update: player_identity::ActiveModel = identity.clone().into();
+            update.last_login_at = Set(Some(Utc::now()));
+            update.updated_at = Set(Utc::now());
+            update.update(&self.db).await?;
+
+            let player = Player::find_by_id(identity.player_id)
+                .one(&self.db)
+                .await?
+                .ok_or(AppError::Unauthorized)?;
+            return Ok(Some(SocialLogin::Existing(player)));
+        }
+
+        let Some(email) = profile.email.as_deref() else {
+            return Err(AppError::Validation(format!(
+                "Your {} account did not share an email address",
+                profile.provider.as_str()
+            )));
+        };
+
+        let existing = Player::find()
+            .filter(player::Column::Email.eq(email))
+            .one(&self.db)
+            .await?;
+        match existing {
+            // An unverified provider email could belong to anyone, so it never
+            // grants access to an existing account
+            Some(_) if !profile.email_verified => Err(AppError::Validation(
+                "An account with this email already exists. Sign in with your password and link it from settings".to_string(),
+            )),
+            Some(player) => {
+                self.link_identity(player.id, profile).await?;
+                Ok(Some(SocialLogin::Linked(player)))
+            }
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,349 @@
+use crate::models::enums::{InviteStatus, OrgPermission, OrgRole};
+use crate::models::postgres::{
+    organization, organization_member, player, OrganizationMember, Player,
+};
+use crate::services::auth_service::{AuthService, Claims, UserType};
+use crate::utils::errors::AppError;
+use chrono::Utc;
+use sea_orm::*;
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct OrganizationMemberService {
+    db: DatabaseConnection,
+    auth_service: AuthService,
+}
+
+impl OrganizationMemberService {
+    pub fn new(db: DatabaseConnection, auth_service: AuthService) -> Self {
+        Self { db, auth_service }
+    }
+
+    /// Resolves the caller's role inside an organization. The organization's own
+    /// login always acts as owner; players act through an accepted membership.
+    pub async fn resolve_role(
+        &self,
+        org_id: Uuid,
+        claims: &Claims,
+    ) -> Result<Option<OrgRole>, AppError> {
+        let user_id = Uuid::parse_str(&claims.sub)?;
+
+        match claims.user_type.as_str() {
+            "organization" if user_id == org_id => Ok(Some(OrgRole::Owner)),
+            "player" => Ok(self
+                .get_active_membership(org_id, user_id)
+                .await?
+                .map(|m| m.role)),
+            _ => Ok(None),
+        }
+    }
+
+    pub async fn require_permission(
+        &self,
+        org_id: Uuid,
+        claims: &Claims,
+        permission: OrgPermission,
+    ) -> Result<OrgRole, AppError> {
+        match self.resolve_role(org_id, claims).await? {
+            Some(role) if role.can(permission) => Ok(role),
+            _ => Err(AppError::Forbidden),
+        }
+    }
+
+    pub async fn get_active_membership(
+        &self,
+        org_id: Uuid,
+        player_id: Uuid,
+    ) -> Result<Option<organization_member::Model>, AppError> {
+        Ok(OrganizationMember::find()
+            .filter(organization_member::Column::OrganizationId.eq(org_id))
+            .filter(organization_member::Column::PlayerId.eq(player_id))
+            .filter(organization_member::Column::Status.eq(InviteStatus::Accepted))
+            .one(&self.db)
+            .await?)
+    }
+
+    pub async fn get_member(
+        &self,
+        org_id: Uuid,
+        member_id: Uuid,
+    ) -> Result<organization_member::Model, AppError> {
+        OrganizationMember::find_by_id(member_id)
+            .filter(organization_member::Column::OrganizationId.eq(org_id))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    /// Creates a pending membership and returns it with the invite token to email.
+    pub async fn invite_member(
+        &self,
+        org_id: Uuid,
+        invited_by: Uuid,
+        email: String,
+        role: OrgRole,
+    ) -> Result<(organization_member::Model, String), AppError> {
+        if role == OrgRole::Owner {
+            return Err(AppError::Validation(
+                "Ownership can only be granted through a transfer".to_string(),
+            ));
+        }
+
+        let email = email.trim().to_lowercase();
+        let existing_player = Player::find()
+            .filter(player::Column::Email.eq(&email))
+            .one(&self.db)
+            .await?;
+
+        if let Some(p) = &existing_player {
+            if self.get_active_membership(org_id, p.id).await?.is_some() {
+                return Err(AppError::Validation(
+                    "Player is already a member of this organization".to_string(),
+                ));
+            }
+        }
+
+        // A player keeps one membership row per organization, so a declined
+        // or expired invite is reopened rather than duplicated
+        let previous = match &existing_player {
+            Some(p) => {
+                OrganizationMember::find()
+                    .filter(organization_member::Column::OrganizationId.eq(org_id))
+                    .filter(organization_member::Column::PlayerId.eq(p.id))
+                    .one(&self.db)
+                    .await?
+            }
+            None => None,
+        };
+
+        let pending = OrganizationMember::find()
+            .filter(organization_member::Column::OrganizationId.eq(org_id))
+            .filter(organization_member::Column::InvitedEmail.eq(&email))
+            .filter(organization_member::Column::Status.eq(InviteStatus::Pending))
+            .one(&self.db)
+            .await?;
+
+        if pending.is_some()
+            || previous
+                .as_ref()
+                .is_some_and(|m| m.status == InviteStatus::Pending)
+        {
+            return Err(AppError::Validation(
+                "An invite is already pending for this email".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        let member = match previous {
+            Some(previous) => {
+                let mut update: organization_member::ActiveModel = previous.into();
+                update.invited_email = Set(Some(email));
+                update.role = Set(role);
+                update.status = Set(InviteStatus::Pending);
+                update.invited_by = Set(Some(invited_by));
+                update.joined_at = Set(None);
+                update.updated_at = Set(now);
+                update.update(&self.db).await?
+            }
+            None => {
+                organization_member::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    organization_id: Set(org_id),
+                    player_id: Set(existing_player.map(|p| p.id)),
+                    invited_email: Set(Some(email)),
+                    role: Set(role),
+                    status: Set(InviteStatus::Pending),
+                    invited_by: Set(Some(invited_by)),
+                    joined_at: Set(None),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(&self.db)
+                .await?
+            }
+        };
+
+        let token = self.auth_service.generate_temp_token(
+            member.id,
+            UserType::Organization,
+            "org_invite",
+            72, // 3 days expiry
+        )?;
+
+        Ok((member, token))
+    }
+
+    pub async fn accept_invite(
+        &self,
+        member_id: Uuid,
+        player: &player::Model,
+    ) -> Result<organization_member::Model, AppError> {
+        let member = OrganizationMember::find_by_id(member_id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        if member.status != InviteStatus::Pending {
+            return Err(AppError::Validation(
+                "Invite is no longer valid".to_string(),
+            ));
+        }
+
+        let email_matches = member
+            .invited_email
+            .as_deref()
+            .map(|e| e.eq_ignore_ascii_case(&player.email))
+            .unwrap_or(false);
+        if member
+            .player_id
+            .map_or(!email_matches, |id| id != player.id)
+        {
+            return Err(AppError::Forbidden);
+        }
+
+        if self
+            .get_active_membership(member.organization_id, player.id)
+            .await?
+            .is_some()
+        {
+            return Err(AppError::Validation(
+                "Player is already a member of this organization".to_string(),
+            ));
+        }
+
+        let now = Utc::now();
+        let mut update: organization_member::ActiveModel = member.into();
+        update.player_id = Set(Some(player.id));
+        update.status = Set(InviteStatus::Accepted);
+        update.joined_at = Set(Some(now));
+        update.updated_at = Set(now);
+
+        Ok(update.update(&self.db).await?)
+    }
+
+    pub async fn list_members(
+        &self,
+        org_id: Uuid,
+    ) -> Result<Vec<organization_member::Model>, AppError> {
+        Ok(OrganizationMember::find()
+            .filter(organization_member::Column::OrganizationId.eq(org_id))
+            .filter(
+                organization_member::Column::Status
+                    .is_in([InviteStatus::Pending, InviteStatus::Accepted]),
+            )
+            .order_by_asc(organization_member::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn list_player_memberships(
+        &self,
+        player_id: Uuid,
+    ) -> Result<Vec<organization_member::Model>, AppError> {
+        Ok(OrganizationMember::find()
+            .filter(organization_member::Column::PlayerId.eq(player_id))
+            .filter(organization_member::Column::Status.eq(InviteStatus::Accepted))
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn update_role(
+        &self,
+        org_id: Uuid,
+        member_id: Uuid,
+        role: OrgRole,
+    ) -> Result<organization_member::Model, AppError> {
+        if role == OrgRole::Owner {
+            return Err(AppError::Validation(
+                "Ownership can only be granted through a transfer".to_string(),
+            ));
+        }
+
+        let member = self.get_member(org_id, member_id).await?;
+        if member.role == OrgRole::Owner {
+            return Err(AppError::Validation(
+                "Transfer ownership before changing the owner's role".to_string(),
+            ));
+        }
+
+        let mut update: organization_member::ActiveModel = member.into();
+        update.role = Set(role);
+        update.updated_at = Set(Utc::now());
+
+        Ok(update.update(&self.db).await?)
+    }
+
+    pub async fn remove_member(&self, org_id: Uuid, member_id: Uuid) -> Result<(), AppError> {
+        let member = self.get_member(org_id, member_id).await?;
+        if member.role == OrgRole::Owner {
+            return Err(AppError::Validation(
+                "The owner cannot be removed; transfer ownership first".to_string(),
+            ));
+        }
+
+        OrganizationMember::delete_by_id(member.id)
+            .exec(&self.db)
+            .await?;
+        Ok(())
+    }
+
+    /// Promotes an accepted member to owner, demoting the previous owner member
+    /// (if any) to manager, and records the new owner's name on the organization.
+    pub async fn transfer_ownership(
+        &self,
+        org_id: Uuid,
+        new_owner_member_id: Uuid,
+    ) -> Result<organization_member::Model, AppError> {
+        let txn = self.db.begin().await?;
+
+        let target = OrganizationMember::find_by_id(new_owner_member_id)
+            .filter(organization_member::Column::OrganizationId.eq(org_id))
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        if target.status != InviteStatus::Accepted {
+            return Err(AppError::Validation(
+                "Ownership can only be transferred to an active member".to_string(),
+            ));
+        }
+        let player_id = target.player_id.ok_or(AppError::NotFound)?;
+
+        let now = Utc::now();
+        OrganizationMember::update_many()
+            .col_expr(
+                organization_member::Column::Role,
+                sea_query::Expr::value(OrgRole::Manager),
+            )
+            .col_expr(
+                organization_member::Column::UpdatedAt,
+                sea_query::Expr::value(now),
+            )
+            .filter(organization_member::Column::OrganizationId.eq(org_id))
+            .filter(organization_member::Column::Role.eq(OrgRole::Owner))
+            .exec(&txn)
+            .await?;
+
+        let mut update: organization_member::ActiveModel = target.into();
+        update.role = Set(OrgRole::Owner);
+        update.updated_at = Set(now);
+        let new_owner = update.update(&txn).await?;
+
+        let player = Player::find_by_id(player_id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::NotFound)?;
+        organization::Entity::update_many()
+            .col_expr(
+                organization::Column::OwnerName,
+                sea_query::Expr::value(player.real_name.unwrap_or(player.username)),
+            )
+            .col_expr(organization::Column::UpdatedAt, sea_query::Expr::value(now))
+            .filter(organization::Column::Id.eq(org_id))
+            .exec(&txn)
+            .await?;
+
+        txn.commit().await?;
+        Ok(new_owner)
+    }
+}
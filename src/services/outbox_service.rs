@@ -0,0 +1,230 @@
+use crate::models::enums::{ApprovalStatus, OutboxStatus, TournamentStatus};
+use crate::models::postgres::{outbox_event, OutboxEvent};
+use crate::utils::errors::AppError;
+use async_trait::async_trait;
+use chrono::{DateTime, Duration, Utc};
+use sea_orm::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use uuid::Uuid;
+
+const MAX_ATTEMPTS: i32 = 10;
+// Retry delays double from here: 10s, 20s, 40s ... capped below
+const RETRY_BASE_SECS: i64 = 10;
+const RETRY_MAX_SECS: i64 = 3600;
+// A claimed event is hidden from other dispatchers for this long
+const CLAIM_LEASE_SECS: i64 = 120;
+const DISPATCH_BATCH: i64 = 100;
+const PROCESSED_RETENTION_DAYS: i64 = 7;
+
+/// Something that happened in the domain, recorded in the outbox with the
+/// change itself and handed to subscribers afterwards.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEvent {
+    #[serde(rename = "player.registered")]
+    PlayerRegistered {
+        player_id: Uuid,
+        email: String,
+        username: String,
+        verified: bool,
+        method: String, // "password" or "oauth"
+    },
+    #[serde(rename = "team.joined")]
+    TeamJoined {
+        tournament_team_id: Uuid,
+        tournament_id: Uuid,
+        team_id: Uuid,
+    },
+    #[serde(rename = "tournament.transitioned")]
+    TournamentTransitioned {
+        tournament_id: Uuid,
+        tournament_name: String,
+        organization_id: Option<Uuid>,
+        status: TournamentStatus,
+        approval_status: ApprovalStatus,
+        previous_approval_status: ApprovalStatus,
+    },
+    #[serde(rename = "result.verified")]
+    ResultVerified {
+        battle_id: Uuid,
+        tournament_id: Uuid,
+    },
+    #[serde(rename = "chat.activity")]
+    ChatActivity {
+        chat_id: Uuid,
+        actor_id: Uuid,
+        action: String,
+        details: serde_json::Value,
+        occurred_at: DateTime<Utc>,
+    },
+}
+
+impl DomainEvent {
+    pub fn event_type(&self) -> &'static str {
+        match self {
+            DomainEvent::PlayerRegistered { .. } => "player.registered",
+            DomainEvent::TeamJoined { .. } => "team.joined",
+            DomainEvent::TournamentTransitioned { .. } => "tournament.transitioned",
+            DomainEvent::ResultVerified { .. } => "result.verified",
+            DomainEvent::ChatActivity { .. } => "chat.activity",
+        }
+    }
+
+    fn aggregate_id(&self) -> Uuid {
+        match self {
+            DomainEvent::PlayerRegistered { player_id, .. } => *player_id,
+            DomainEvent::TeamJoined { team_id, .. } => *team_id,
+            DomainEvent::TournamentTransitioned { tournament_id, .. } => *tournament_id,
+            DomainEvent::ResultVerified { battle_id, .. } => *battle_id,
+            DomainEvent::ChatActivity { chat_id, .. } => *chat_id,
+        }
+    }
+}
+
+/// An in-process consumer of domain events. Delivery is at least once, so
+/// `handle` must tolerate seeing the same event again. Events a subscriber
+/// doesn't care about should just return `Ok`.
+#[async_trait]
+pub trait EventSubscriber: Send + Sync {
+    /// Stable name, stored with events the subscriber has finished.
+    fn name(&self) -> &'static str;
+
+    /// `event_id` is the outbox row id, the same on every redelivery.
+    async fn handle(&self, event_id: Uuid, event: &DomainEvent) -> Result<(), AppError>;
+}
+
+#[derive(Clone)]
+pub struct OutboxService {
+    db: DatabaseConnection,
+    subscribers: Arc<Vec<Arc<dyn EventSubscriber>>>,
+}
+
+impl OutboxService {
+    pub fn new(db: DatabaseConnection, subscribers: Vec<Arc<dyn EventSubscriber>>) -> Self {
+        Self {
+            db,
+            subscribers: Arc::new(subscribers),
+        }
+    }
+
+    /// Adds `event` to the outbox. Pass the transaction that makes the change,
+    /// so the event exists exactly when the change commits.
+    pub async fn record<C: ConnectionTrait>(conn: &C, event: &DomainEvent) -> Result<(), AppError> {
+        let now = Utc::now();
+        outbox_event::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            event_type: Set(event.event_type().to_string()),
+            aggregate_id: Set(event.aggregate_id()),
+            payload: Set(serde_json::to_value(event)?),
+            status: Set(OutboxStatus::Pending),
+            attempts: Set(0),
+            completed_subscribers: Set(Vec::new()),
+            next_attempt_at: Set(now),
+            last_error: Set(None),
+            processed_at: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(conn)
+        .await?;
+        Ok(())
+    }
+
+    /// Hands every due event to the subscribers that haven't handled it yet.
+    /// Events are claimed with a lease first, so several nodes can dispatch.
+    pub async fn dispatch_pending(&self) -> Result<usize, AppError> {
+        let mut claimed = OutboxEvent::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"UPDATE outbox_events
+                   SET next_attempt_at = NOW() + make_interval(secs => $1)
+                   WHERE id IN (
+                       SELECT id FROM outbox_events
+                       WHERE status = 'pending' AND next_attempt_at <= NOW()
+                       ORDER BY created_at
+                       LIMIT $2
+                       FOR UPDATE SKIP LOCKED
+                   )
+                   RETURNING *"#,
+                [(CLAIM_LEASE_SECS as f64).into(), DISPATCH_BATCH.into()],
+            ))
+            .all(&self.db)
+            .await?;
+
+        let count = claimed.len();
+        // RETURNING doesn't keep the subquery's order
+        claimed.sort_by_key(|e| e.created_at);
+        for record in claimed {
+            self.dispatch(record).await?;
+        }
+        Ok(count)
+    }
+
+    async fn dispatch(&self, record: outbox_event::Model) -> Result<(), AppError> {
+        let now = Utc::now();
+        let mut completed = record.completed_subscribers.clone();
+        let mut errors = Vec::new();
+
+        match serde_json::from_value::<DomainEvent>(record.payload.clone()) {
+            Ok(event) => {
+                for subscriber in self.subscribers.iter() {
+                    if completed.iter().any(|name| name == subscriber.name()) {
+                        continue;
+                    }
+                    match subscriber.handle(record.id, &event).await {
+                        Ok(()) => completed.push(subscriber.name().to_string()),
+                        Err(e) => {
+                            tracing::warn!(
+                                "{} failed to handle {} {}: {:?}",
+                                subscriber.name(),
+                                record.event_type,
+                                record.id,
+                                e
+                            );
+                            errors.push(format!("{}: {}", subscriber.name(), e));
+                        }
+                    }
+                }
+            }
+            Err(e) => errors.push(format!("Unreadable payload: {}", e)),
+        }
+
+        let attempts = record.attempts + 1;
+        let mut update: outbox_event::ActiveModel = record.into();
+        update.attempts = Set(attempts);
+        update.completed_subscribers = Set(completed);
+        update.updated_at = Set(now);
+
+        if errors.is_empty() {
+            update.status = Set(OutboxStatus::Processed);
+            update.processed_at = Set(Some(now));
+            update.last_error = Set(None);
+        } else {
+            let retry_in = (RETRY_BASE_SECS << (attempts - 1).clamp(0, 20)).min(RETRY_MAX_SECS);
+            update.status = Set(if attempts >= MAX_ATTEMPTS {
+                OutboxStatus::Failed
+            } else {
+                OutboxStatus::Pending
+            });
+            update.next_attempt_at = Set(now + Duration::seconds(retry_in));
+            update.last_error = Set(Some(errors.join("; ")));
+        }
+        update.update(&self.db).await?;
+
+        Ok(())
+    }
+
+    /// Processed events are only kept for a short while for debugging.
+    pub async fn purge_processed(&self) -> Result<u64, AppError> {
+        let result = OutboxEvent::delete_many()
+            .filter(outbox_event::Column::Status.eq(OutboxStatus::Processed))
+            .filter(
+                outbox_event::Column::ProcessedAt
+                    .lt(Utc::now() - Duration::days(PROCESSED_RETENTION_DAYS)),
+            )
+            .exec(&self.db)
+            .await?;
+        Ok(result.rows_affected)
+    }
+}
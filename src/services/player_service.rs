@@ -1,7 +1,8 @@
 use crate::handlers::players::{PlayerListQuery, PlayerProfileResponse};
-use crate::models::enums::GameType;
+use crate::models::enums::{DmPrivacy, GameType};
 use crate::models::postgres::{player, Player};
 use crate::services::auth_service::{AuthService, UserType};
+use crate::services::outbox_service::{DomainEvent, OutboxService};
 use crate::utils::errors::AppError;
 use crate::utils::validation::validate_password;
 use anyhow::Result;
@@ -26,6 +27,7 @@ pub struct UpdateProfileRequest {
     pub youtube: Option<String>,
     pub twitter: Option<String>,
     pub profile_visibility: Option<String>,
+    pub dm_privacy: Option<DmPrivacy>,
     pub card_theme: Option<String>,
 }
 
@@ -40,6 +42,129 @@ impl PlayerService {
         Self { db, auth_service }
     }
 
+    fn new_player_model(
+        username: String,
+        email: String,
+        hashed_password: String,
+        has_password: bool,
+        verified: bool,
+    ) -> player::ActiveModel {
+        let now = chrono::Utc::now();
+        player::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            username: Set(username),
+            in_game_name: Set(None),
+            real_name: Set(None),
+            email: Set(email),
+            password: Set(hashed_password),
+            has_password: Set(has_password),
+            verified: Set(verified),
+            country: Set(None),
+            bio: Set(String::new()),
+            profile_picture: Set(String::new()),
+            primary_game: NotSet,
+            earnings: Set(rust_decimal::Decimal::ZERO),
+            in_game_role: Set(vec![]),
+            location: Set(None),
+            age: Set(None),
+            languages: Set(vec![]),
+            aegis_rating: Set(1000),
+            tournaments_played: Set(0),
+            battles_played: Set(0),
+            qualified_events: Set(false),
+            qualified_event_details: Set(vec![]),
+            team_status: Set(None),
+            team_id: Set(None),
+            availability: Set(None),
+            discord_tag: Set(String::new()),
+            twitch: Set(String::new()),
+            youtube: Set(String::new()),
+            twitter: Set(String::new()),
+            profile_visibility: Set("public".to_string()),
+            dm_privacy: Set(DmPrivacy::Everyone),
+            card_theme: Set("default".to_string()),
+            coins: Set(0),
+            last_check_in: Set(None),
+            check_in_streak: Set(0),
+            total_check_ins: Set(0),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+    }
+
+    /// Creates a player for a first-time social login. The account has no usable
+    /// password until one is set through the reset flow.
+    pub async fn create_social_player(
+        &self,
+        email: String,
+        username_hint: &str,
+        verified: bool,
+    ) -> Result<player::Model, AppError> {
+        let existing_email = Player::find()
+            .filter(player::Column::Email.eq(&email))
+            .one(&self.db)
+            .await?;
+        if existing_email.is_some() {
+            return Err(AppError::Validation("Email already exists".to_string()));
+        }
+
+        let username = self.available_username(username_hint).await?;
+        let hashed_password = self.auth_service.hash_unusable_password()?;
+
+        let txn = self.db.begin().await?;
+        let player = Self::new_player_model(username, email, hashed_password, false, verified)
+            .insert(&txn)
+            .await?;
+        OutboxService::record(&txn, &Self::registered_event(&player, "oauth")).await?;
+        txn.commit().await?;
+
+        Ok(player)
+    }
+
+    fn registered_event(player: &player::Model, method: &str) -> DomainEvent {
+        DomainEvent::PlayerRegistered {
+            player_id: player.id,
+            email: player.email.clone(),
+            username: player.username.clone(),
+            verified: player.verified,
+            method: method.to_string(),
+        }
+    }
+
+    /// Turns a provider display name into a free username, adding a numeric
+    /// suffix when the base is taken.
+    async fn available_username(&self, hint: &str) -> Result<String, AppError> {
+        let mut base: String = hint
+            .chars()
+            .filter(|c| c.is_ascii_alphanumeric() || *c == '_' || *c == '-')
+            .take(24)
+            .collect();
+        if base.len() < 3 {
+            base = format!("player_{}", base);
+        }
+
+        for attempt in 0..20u32 {
+            let candidate = if attempt == 0 {
+                base.clone()
+            } else {
+                format!("{}{}", base, rand::random::<u16>() % 10000)
+            };
+            let taken = Player::find()
+                .filter(player::Column::Username.eq(&candidate))
+                .one(&self.db)
+                .await?
+                .is_some();
+            if !taken {
+                return Ok(candidate);
+            }
+        }
+
+        Ok(format!(
+            "player_{}",
+            &Uuid::new_v4().simple().to_string()[..12]
+        ))
+    }
+
     pub async fn create_player(
         &self,
         email: String,
@@ -92,52 +217,15 @@ impl PlayerService {
             }
         };
 
-        let now = chrono::Utc::now();
         println!("DEBUG: Creating ActiveModel");
 
-        let new_player = player::ActiveModel {
-            id: Set(Uuid::new_v4()),
-            username: Set(username),
-            in_game_name: Set(None),
-            real_name: Set(None),
-            email: Set(email),
-            password: Set(hashed_password),
-            verified: Set(false),
-            country: Set(None),
-            bio: Set(String::new()),
-            profile_picture: Set(String::new()),
-            primary_game: NotSet,
-            earnings: Set(rust_decimal::Decimal::ZERO),
-            in_game_role: Set(vec![]),
-            location: Set(None),
-            age: Set(None),
-            languages: Set(vec![]),
-            aegis_rating: Set(1000),
-            tournaments_played: Set(0),
-            battles_played: Set(0),
-            qualified_events: Set(false),
-            qualified_event_details: Set(vec![]),
-            team_status: Set(None),
-            team_id: Set(None),
-            availability: Set(None),
-            discord_tag: Set(String::new()),
-            twitch: Set(String::new()),
-            youtube: Set(String::new()),
-            twitter: Set(String::new()),
-            profile_visibility: Set("public".to_string()),
-            card_theme: Set("default".to_string()),
-            coins: Set(0),
-            last_check_in: Set(None),
-            check_in_streak: Set(0),
-            total_check_ins: Set(0),
-            created_at: Set(now),
-            updated_at: Set(now),
-        };
+        let new_player = Self::new_player_model(username, email, hashed_password, true, false);
         println!("DEBUG: ActiveModel created successfully");
 
         // Attempt database insert with detailed error handling
         println!("DEBUG: Attempting database insert");
-        let player = match new_player.insert(&self.db).await {
+        let txn = self.db.begin().await?;
+        let player = match new_player.insert(&txn).await {
             Ok(p) => {
                 println!("DEBUG: Database insert successful! Player ID: {}", p.id);
                 p
@@ -169,6 +257,8 @@ impl PlayerService {
                 return Err(AppError::Database(e));
             }
         };
+        OutboxService::record(&txn, &Self::registered_event(&player, "password")).await?;
+        txn.commit().await?;
 
         // Generate JWT token
         println!("DEBUG: Generating JWT token for player ID: {}", player.id);
@@ -405,6 +495,9 @@ impl PlayerService {
         if let Some(profile_visibility) = update_data.profile_visibility {
             active_model.profile_visibility = Set(profile_visibility);
         }
+        if let Some(dm_privacy) = update_data.dm_privacy {
+            active_model.dm_privacy = Set(dm_privacy);
+        }
         if let Some(card_theme) = update_data.card_theme {
             active_model.card_theme = Set(card_theme);
         }
@@ -496,6 +589,7 @@ impl PlayerService {
                 youtube: player.youtube,
                 twitter: player.twitter,
                 profile_visibility: player.profile_visibility,
+                dm_privacy: player.dm_privacy,
                 card_theme: player.card_theme,
                 coins: player.coins,
                 check_in_streak: player.check_in_streak,
@@ -525,6 +619,7 @@ impl PlayerService {
         if let Some(player) = Player::find_by_id(user_id).one(&self.db).await? {
             let mut player_update: player::ActiveModel = player.into();
             player_update.password = Set(hashed_password);
+            player_update.has_password = Set(true);
             player_update.updated_at = Set(chrono::Utc::now());
             Player::update(player_update).exec(&self.db).await?;
             Ok(true)
@@ -1,114 +1,250 @@
 use crate::models::postgres::{rate_limit, RateLimit};
 use crate::utils::errors::AppError;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Utc};
+use redis::aio::ConnectionManager;
+use redis::{Client as RedisClient, Script};
 use sea_orm::*;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
 use uuid::Uuid;
 
+const REDIS_KEY_PREFIX: &str = "ratelimit";
+const REDIS_TIMEOUT: Duration = Duration::from_millis(250);
+// After Redis fails to connect, stay on Postgres this long before trying again
+const REDIS_RETRY_AFTER: Duration = Duration::from_secs(30);
+
+// Sliding window over a sorted set of request timestamps (ms). Runs atomically
+// so concurrent requests on any node see one consistent count.
+const SLIDING_WINDOW_SCRIPT: &str = r#"
+local now = tonumber(ARGV[1])
+local window = tonumber(ARGV[2])
+local limit = tonumber(ARGV[3])
+
+redis.call('ZREMRANGEBYSCORE', KEYS[1], 0, now - window)
+local count = redis.call('ZCARD', KEYS[1])
+local allowed = 0
+if count < limit then
+    redis.call('ZADD', KEYS[1], now, ARGV[4])
+    count = count + 1
+    allowed = 1
+end
+redis.call('PEXPIRE', KEYS[1], window)
+
+local reset_at = now + window
+local oldest = redis.call('ZRANGE', KEYS[1], 0, 0, 'WITHSCORES')
+if oldest[2] then
+    reset_at = tonumber(oldest[2]) + window
+end
+return {allowed, count, reset_at}
+"#;
+
+/// Outcome of counting one request against a limit.
+#[derive(Debug, Clone, Copy)]
+pub struct RateLimitDecision {
+    pub allowed: bool,
+    pub limit: u32,
+    pub remaining: u32,
+    pub reset_after_secs: u64, // until the window frees up a slot
+}
+
+#[derive(Default)]
+struct RedisConnection {
+    manager: Option<ConnectionManager>,
+    retry_at: Option<Instant>,
+}
+
 #[derive(Clone)]
 pub struct RateLimitService {
     db: DatabaseConnection,
+    redis: Option<RedisClient>,
+    connection: Arc<Mutex<RedisConnection>>,
+    script: Arc<Script>,
 }
 
 impl RateLimitService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, redis_url: Option<String>) -> Self {
+        let redis = redis_url.and_then(|url| match RedisClient::open(url) {
+            Ok(client) => Some(client),
+            Err(e) => {
+                tracing::warn!("Rate limiter running without Redis: {}", e);
+                None
+            }
+        });
+
+        Self {
+            db,
+            redis,
+            connection: Arc::new(Mutex::new(RedisConnection::default())),
+            script: Arc::new(Script::new(SLIDING_WINDOW_SCRIPT)),
+        }
     }
 
-    pub async fn check_rate_limit(
+    fn redis_key(identifier: &str, identifier_type: &str, action: &str) -> String {
+        format!(
+            "{}:{}:{}:{}",
+            REDIS_KEY_PREFIX, action, identifier_type, identifier
+        )
+    }
+
+    async fn redis_connection(&self) -> Option<ConnectionManager> {
+        let client = self.redis.as_ref()?;
+        let mut connection = self.connection.lock().await;
+
+        if let Some(manager) = &connection.manager {
+            return Some(manager.clone());
+        }
+        if connection.retry_at.is_some_and(|at| Instant::now() < at) {
+            return None;
+        }
+
+        match tokio::time::timeout(REDIS_TIMEOUT, ConnectionManager::new(client.clone())).await {
+            Ok(Ok(manager)) => {
+                connection.manager = Some(manager.clone());
+                connection.retry_at = None;
+                Some(manager)
+            }
+            Ok(Err(e)) => {
+                tracing::warn!("Rate limiter cannot reach Redis, using Postgres: {}", e);
+                connection.retry_at = Some(Instant::now() + REDIS_RETRY_AFTER);
+                None
+            }
+            Err(_) => {
+                tracing::warn!("Rate limiter timed out connecting to Redis, using Postgres");
+                connection.retry_at = Some(Instant::now() + REDIS_RETRY_AFTER);
+                None
+            }
+        }
+    }
+
+    /// Counts one request against `limit` per `window_secs`. Uses a Redis
+    /// sliding window shared by every node, or a fixed window in Postgres when
+    /// Redis is unavailable.
+    pub async fn hit(
         &self,
-        identifier: String,
-        identifier_type: String,
-        action: String,
-        max_attempts: i32,
-        window_minutes: i64,
-    ) -> Result<bool, AppError> {
-        let window_start = Utc::now() - Duration::minutes(window_minutes);
-
-        // Find existing rate limit record
-        let existing = RateLimit::find()
-            .filter(rate_limit::Column::Identifier.eq(&identifier))
-            .filter(rate_limit::Column::IdentifierType.eq(&identifier_type))
-            .filter(rate_limit::Column::Action.eq(&action))
-            .one(&self.db)
-            .await?;
+        identifier: &str,
+        identifier_type: &str,
+        action: &str,
+        limit: u32,
+        window_secs: u64,
+    ) -> Result<RateLimitDecision, AppError> {
+        let key = Self::redis_key(identifier, identifier_type, action);
+        if let Some(decision) = self.redis_hit(&key, limit, window_secs).await {
+            return Ok(decision);
+        }
+
+        self.postgres_hit(identifier, identifier_type, action, limit, window_secs)
+            .await
+    }
 
-        match existing {
-            Some(record) => {
-                // Check if currently blocked
-                if let Some(blocked_until) = record.blocked_until {
-                    if blocked_until > Utc::now() {
-                        return Err(AppError::RateLimited);
-                    }
-                }
-
-                // Check if within current window
-                if record.window_start > window_start {
-                    if record.attempts >= max_attempts {
-                        // Block for next window
-                        let mut update: rate_limit::ActiveModel = record.into();
-                        update.blocked_until =
-                            Set(Some(Utc::now() + Duration::minutes(window_minutes)));
-                        update.updated_at = Set(Utc::now());
-                        RateLimit::update(update).exec(&self.db).await?;
-                        return Err(AppError::RateLimited);
-                    } else {
-                        // Increment attempts - store attempts value before move
-                        let current_attempts = record.attempts;
-                        let mut update: rate_limit::ActiveModel = record.into();
-                        update.attempts = Set(current_attempts + 1);
-                        update.updated_at = Set(Utc::now());
-                        RateLimit::update(update).exec(&self.db).await?;
-                        return Ok(true);
-                    }
-                } else {
-                    // New window, reset
-                    let mut update: rate_limit::ActiveModel = record.into();
-                    update.attempts = Set(1);
-                    update.window_start = Set(Utc::now());
-                    update.blocked_until = Set(None);
-                    update.updated_at = Set(Utc::now());
-                    RateLimit::update(update).exec(&self.db).await?;
-                    return Ok(true);
-                }
+    async fn redis_hit(
+        &self,
+        key: &str,
+        limit: u32,
+        window_secs: u64,
+    ) -> Option<RateLimitDecision> {
+        let mut conn = self.redis_connection().await?;
+        let now_ms = Utc::now().timestamp_millis();
+        let window_ms = window_secs as i64 * 1000;
+        let member = format!("{}-{}", now_ms, Uuid::new_v4());
+
+        let mut invocation = self.script.key(key);
+        invocation.arg(now_ms).arg(window_ms).arg(limit).arg(member);
+
+        let result = tokio::time::timeout(
+            REDIS_TIMEOUT,
+            invocation.invoke_async::<_, (i64, i64, i64)>(&mut conn),
+        )
+        .await;
+
+        match result {
+            Ok(Ok((allowed, count, reset_at_ms))) => Some(RateLimitDecision {
+                allowed: allowed == 1,
+                limit,
+                remaining: limit.saturating_sub(count as u32),
+                reset_after_secs: ((reset_at_ms - now_ms).max(0) as u64).div_ceil(1000),
+            }),
+            Ok(Err(e)) => {
+                tracing::warn!("Redis rate limit check failed, using Postgres: {}", e);
+                None
             }
-            None => {
-                // Create new record
-                let new_record = rate_limit::ActiveModel {
-                    id: Set(Uuid::new_v4()),
-                    identifier: Set(identifier),
-                    identifier_type: Set(identifier_type),
-                    action: Set(action),
-                    attempts: Set(1),
-                    window_start: Set(Utc::now()),
-                    blocked_until: Set(None),
-                    created_at: Set(Utc::now()),
-                    updated_at: Set(Utc::now()),
-                };
-                new_record.insert(&self.db).await?;
-                return Ok(true);
+            Err(_) => {
+                tracing::warn!("Redis rate limit check timed out, using Postgres");
+                None
             }
         }
     }
 
-    pub async fn is_blocked(
+    /// Single-statement upsert so concurrent requests can't lose increments.
+    async fn postgres_hit(
+        &self,
+        identifier: &str,
+        identifier_type: &str,
+        action: &str,
+        limit: u32,
+        window_secs: u64,
+    ) -> Result<RateLimitDecision, AppError> {
+        let row = self
+            .db
+            .query_one(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"INSERT INTO rate_limits (identifier, identifier_type, action, attempts, window_start)
+                   VALUES ($1, $2, $3, 1, NOW())
+                   ON CONFLICT (identifier, identifier_type, action) DO UPDATE SET
+                       attempts = CASE
+                           WHEN rate_limits.window_start <= NOW() - make_interval(secs => $4) THEN 1
+                           ELSE rate_limits.attempts + 1
+                       END,
+                       window_start = CASE
+                           WHEN rate_limits.window_start <= NOW() - make_interval(secs => $4) THEN NOW()
+                           ELSE rate_limits.window_start
+                       END,
+                       updated_at = NOW()
+                   RETURNING attempts, window_start"#,
+                [
+                    identifier.into(),
+                    identifier_type.into(),
+                    action.into(),
+                    (window_secs as f64).into(),
+                ],
+            ))
+            .await?
+            .ok_or(AppError::InternalServerError)?;
+
+        let attempts: i32 = row.try_get("", "attempts")?;
+        let window_start: DateTime<Utc> = row.try_get("", "window_start")?;
+        let reset_after = window_start + chrono::Duration::seconds(window_secs as i64) - Utc::now();
+
+        Ok(RateLimitDecision {
+            allowed: attempts as u32 <= limit,
+            limit,
+            remaining: limit.saturating_sub(attempts as u32),
+            reset_after_secs: reset_after.num_seconds().max(0) as u64,
+        })
+    }
+
+    pub async fn check_rate_limit(
         &self,
         identifier: String,
         identifier_type: String,
         action: String,
+        max_attempts: i32,
+        window_minutes: i64,
     ) -> Result<bool, AppError> {
-        let record = RateLimit::find()
-            .filter(rate_limit::Column::Identifier.eq(identifier))
-            .filter(rate_limit::Column::IdentifierType.eq(identifier_type))
-            .filter(rate_limit::Column::Action.eq(action))
-            .one(&self.db)
+        let decision = self
+            .hit(
+                &identifier,
+                &identifier_type,
+                &action,
+                max_attempts.max(0) as u32,
+                window_minutes.max(0) as u64 * 60,
+            )
             .await?;
 
-        if let Some(r) = record {
-            if let Some(blocked_until) = r.blocked_until {
-                return Ok(blocked_until > Utc::now());
-            }
+        if !decision.allowed {
+            return Err(AppError::RateLimited);
         }
-        Ok(false)
+        Ok(true)
     }
 
     pub async fn reset_rate_limit(
@@ -117,6 +253,17 @@ impl RateLimitService {
         identifier_type: String,
         action: String,
     ) -> Result<(), AppError> {
+        if let Some(mut conn) = self.redis_connection().await {
+            let key = Self::redis_key(&identifier, &identifier_type, &action);
+            if let Err(e) = redis::cmd("DEL")
+                .arg(key)
+                .query_async::<_, ()>(&mut conn)
+                .await
+            {
+                tracing::warn!("Failed to clear Redis rate limit: {}", e);
+            }
+        }
+
         RateLimit::delete_many()
             .filter(rate_limit::Column::Identifier.eq(identifier))
             .filter(rate_limit::Column::IdentifierType.eq(identifier_type))
@@ -97,18 +97,20 @@ impl S3Service {
         self.upload_file(&key, data, content_type).await
     }
 
+    /// Returns the object key and URL. The content type is whatever the caller
+    /// determined from the file itself.
     pub async fn upload_chat_attachment(
         &self,
         chat_id: &str,
         filename: &str,
+        content_type: &str,
         data: Vec<u8>,
-    ) -> Result<String> {
+    ) -> Result<(String, String)> {
         let timestamp = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
 
         let key = format!("chats/{}/attachments/{}_{}", chat_id, timestamp, filename);
-        let content_type = self.get_content_type_from_filename(filename);
-
-        self.upload_file(&key, data, &content_type).await
+        let url = self.upload_file(&key, data, content_type).await?;
+        Ok((key, url))
     }
 
     pub async fn upload_tournament_media(
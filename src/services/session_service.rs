@@ -1,17 +1,31 @@
+use crate::config::SessionSettings;
 use crate::models::postgres::{user_session, UserSession};
 use crate::utils::errors::AppError;
-use chrono::{Duration, Utc};
+use chrono::{DateTime, Duration, Utc};
 use sea_orm::{sea_query::Expr, *};
 use uuid::Uuid;
 
+/// Result of presenting a refresh token.
+pub enum RefreshOutcome {
+    Rotated(user_session::Model),
+    /// The token had already been rotated, so it was stolen or replayed. The
+    /// whole family has been revoked; carries the session the token belonged to.
+    Reused(user_session::Model),
+    Invalid,
+}
+
+const ROTATED_REASON: &str = "rotated";
+const REUSE_REASON: &str = "refresh_token_reuse";
+
 #[derive(Clone)]
 pub struct SessionService {
     db: DatabaseConnection,
+    settings: SessionSettings,
 }
 
 impl SessionService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, settings: SessionSettings) -> Self {
+        Self { db, settings }
     }
 
     pub async fn create_session(
@@ -20,13 +34,34 @@ impl SessionService {
         user_type: String,
         ip_address: Option<String>,
         user_agent: Option<String>,
+    ) -> Result<user_session::Model, AppError> {
+        self.insert_session(user_id, user_type, ip_address, user_agent, None)
+            .await
+    }
+
+    /// Inserts a session, either starting a new family or joining `family`
+    /// (id and absolute expiry) when rotating.
+    async fn insert_session(
+        &self,
+        user_id: Uuid,
+        user_type: String,
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+        family: Option<(Uuid, DateTime<Utc>)>,
     ) -> Result<user_session::Model, AppError> {
         let now = Utc::now();
+        let id = Uuid::new_v4();
+        let (family_id, family_expires_at) = family.unwrap_or((
+            id,
+            now + Duration::hours(self.settings.absolute_lifetime_hours),
+        ));
         let session_token = Uuid::new_v4().to_string();
         let refresh_token = Uuid::new_v4().to_string();
+        let expires_at =
+            (now + Duration::hours(self.settings.refresh_lifetime_hours)).min(family_expires_at);
 
         let new_session = user_session::ActiveModel {
-            id: Set(Uuid::new_v4()),
+            id: Set(id),
             user_id: Set(user_id),
             session_token: Set(session_token),
             refresh_token: Set(refresh_token),
@@ -34,7 +69,9 @@ impl SessionService {
             ip_address: Set(ip_address),
             user_agent: Set(user_agent),
             device_fingerprint: Set(None),
-            expires_at: Set(now + Duration::days(30)),
+            expires_at: Set(expires_at),
+            family_id: Set(family_id),
+            family_expires_at: Set(family_expires_at),
             revoked: Set(false),
             revoked_at: Set(None),
             revoked_reason: Set(None),
@@ -58,12 +95,67 @@ impl SessionService {
             .filter(user_session::Column::Id.eq(session_uuid)) // ✅ FIXED: Use ID instead of SessionToken
             .filter(user_session::Column::Revoked.eq(false))
             .filter(user_session::Column::ExpiresAt.gt(Utc::now()))
+            .filter(user_session::Column::FamilyExpiresAt.gt(Utc::now()))
+            .filter(user_session::Column::LastActivity.gt(self.idle_cutoff()))
             .one(&self.db)
             .await?;
 
         Ok(session)
     }
 
+    fn idle_cutoff(&self) -> DateTime<Utc> {
+        Utc::now() - Duration::minutes(self.settings.idle_timeout_minutes)
+    }
+
+    /// Records activity on a session, at most once per `activity_update_secs`.
+    /// Returns whether a write happened.
+    pub async fn touch_session(&self, session: &user_session::Model) -> Result<bool, AppError> {
+        let now = Utc::now();
+        let threshold = now - Duration::seconds(self.settings.activity_update_secs);
+        if session.last_activity > threshold {
+            return Ok(false);
+        }
+
+        // The filter keeps concurrent requests from all writing the same bump
+        let result = UserSession::update_many()
+            .col_expr(
+                user_session::Column::LastActivity,
+                Expr::value(Value::ChronoDateTimeUtc(Some(Box::new(now)))),
+            )
+            .filter(user_session::Column::Id.eq(session.id))
+            .filter(user_session::Column::LastActivity.lte(threshold))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected > 0)
+    }
+
+    /// Sessions that can still be used, most recently active first.
+    pub async fn list_active_sessions(
+        &self,
+        user_id: Uuid,
+        user_type: &str,
+    ) -> Result<Vec<user_session::Model>, AppError> {
+        let now = Utc::now();
+        Ok(UserSession::find()
+            .filter(user_session::Column::UserId.eq(user_id))
+            .filter(user_session::Column::UserType.eq(user_type))
+            .filter(user_session::Column::Revoked.eq(false))
+            .filter(user_session::Column::ExpiresAt.gt(now))
+            .filter(user_session::Column::FamilyExpiresAt.gt(now))
+            .filter(user_session::Column::LastActivity.gt(self.idle_cutoff()))
+            .order_by_desc(user_session::Column::LastActivity)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn get_session(
+        &self,
+        session_id: Uuid,
+    ) -> Result<Option<user_session::Model>, AppError> {
+        Ok(UserSession::find_by_id(session_id).one(&self.db).await?)
+    }
+
     pub async fn revoke_session_by_id(&self, session_id: &str) -> Result<(), AppError> {
         let session_uuid = session_id
             .parse::<Uuid>()
@@ -131,29 +223,105 @@ impl SessionService {
         Ok(())
     }
 
+    /// Revokes every live session descended from the same login.
+    pub async fn revoke_family(&self, family_id: Uuid, reason: &str) -> Result<u64, AppError> {
+        let result = UserSession::update_many()
+            .col_expr(
+                user_session::Column::Revoked,
+                Expr::value(Value::Bool(Some(true))),
+            )
+            .col_expr(
+                user_session::Column::RevokedAt,
+                Expr::value(Value::ChronoDateTimeUtc(Some(Box::new(Utc::now())))),
+            )
+            .col_expr(
+                user_session::Column::RevokedReason,
+                Expr::value(reason.to_string()),
+            )
+            .col_expr(
+                user_session::Column::UpdatedAt,
+                Expr::value(Value::ChronoDateTimeUtc(Some(Box::new(Utc::now())))),
+            )
+            .filter(user_session::Column::FamilyId.eq(family_id))
+            .filter(user_session::Column::Revoked.eq(false))
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    /// Rotates a refresh token: the presented session is retired and a new one
+    /// joins its family. A token that was already rotated revokes the family.
     pub async fn refresh_session(
         &self,
         refresh_token: &str,
-    ) -> Result<Option<user_session::Model>, AppError> {
-        let session = UserSession::find()
+        ip_address: Option<String>,
+        user_agent: Option<String>,
+    ) -> Result<RefreshOutcome, AppError> {
+        let Some(session) = UserSession::find()
             .filter(user_session::Column::RefreshToken.eq(refresh_token))
-            .filter(user_session::Column::Revoked.eq(false))
-            .filter(user_session::Column::ExpiresAt.gt(Utc::now()))
             .one(&self.db)
-            .await?;
+            .await?
+        else {
+            return Ok(RefreshOutcome::Invalid);
+        };
 
-        if let Some(s) = session {
-            // Create new session
-            let new_session = self
-                .create_session(s.user_id, s.user_type, s.ip_address, s.user_agent)
-                .await?;
+        if session.revoked {
+            // Logged-out or admin-revoked sessions just fail; only a rotated
+            // token showing up again means two parties hold it
+            if session.revoked_reason.as_deref() == Some(ROTATED_REASON) {
+                self.revoke_family(session.family_id, REUSE_REASON).await?;
+                return Ok(RefreshOutcome::Reused(session));
+            }
+            return Ok(RefreshOutcome::Invalid);
+        }
 
-            // Revoke old session
-            self.revoke_session(&s.session_token).await?;
+        let now = Utc::now();
+        if session.expires_at <= now
+            || session.family_expires_at <= now
+            || session.last_activity <= self.idle_cutoff()
+        {
+            return Ok(RefreshOutcome::Invalid);
+        }
 
-            Ok(Some(new_session))
-        } else {
-            Ok(None)
+        // Retire the old session only if nobody else did first, so two
+        // concurrent refreshes with the same token can't both succeed
+        let retired = UserSession::update_many()
+            .col_expr(
+                user_session::Column::Revoked,
+                Expr::value(Value::Bool(Some(true))),
+            )
+            .col_expr(
+                user_session::Column::RevokedAt,
+                Expr::value(Value::ChronoDateTimeUtc(Some(Box::new(now)))),
+            )
+            .col_expr(
+                user_session::Column::RevokedReason,
+                Expr::value(ROTATED_REASON.to_string()),
+            )
+            .col_expr(
+                user_session::Column::UpdatedAt,
+                Expr::value(Value::ChronoDateTimeUtc(Some(Box::new(now)))),
+            )
+            .filter(user_session::Column::Id.eq(session.id))
+            .filter(user_session::Column::Revoked.eq(false))
+            .exec(&self.db)
+            .await?;
+        if retired.rows_affected == 0 {
+            self.revoke_family(session.family_id, REUSE_REASON).await?;
+            return Ok(RefreshOutcome::Reused(session));
         }
+
+        let new_session = self
+            .insert_session(
+                session.user_id,
+                session.user_type,
+                ip_address.or(session.ip_address),
+                user_agent.or(session.user_agent),
+                Some((session.family_id, session.family_expires_at)),
+            )
+            .await?;
+
+        Ok(RefreshOutcome::Rotated(new_session))
     }
 }
@@ -0,0 +1,121 @@
+use crate::models::enums::SuspensionKind;
+use crate::models::postgres::{account_suspension, AccountSuspension};
+use crate::utils::errors::AppError;
+use chrono::{Duration, Utc};
+use sea_orm::{sea_query::Expr, *};
+use uuid::Uuid;
+
+#[derive(Clone)]
+pub struct SuspensionService {
+    db: DatabaseConnection,
+}
+
+impl SuspensionService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    /// Returns the suspension or ban currently in force for a user, if any.
+    pub async fn get_active(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Option<account_suspension::Model>, AppError> {
+        Ok(AccountSuspension::find()
+            .filter(account_suspension::Column::UserId.eq(user_id))
+            .filter(account_suspension::Column::LiftedAt.is_null())
+            .filter(
+                Condition::any()
+                    .add(account_suspension::Column::ExpiresAt.is_null())
+                    .add(account_suspension::Column::ExpiresAt.gt(Utc::now())),
+            )
+            .order_by_desc(account_suspension::Column::CreatedAt)
+            .one(&self.db)
+            .await?)
+    }
+
+    pub async fn ensure_not_suspended(&self, user_id: Uuid) -> Result<(), AppError> {
+        match self.get_active(user_id).await? {
+            Some(_) => Err(AppError::Forbidden),
+            None => Ok(()),
+        }
+    }
+
+    /// Records a suspension or ban. Bans never expire; a suspension without a
+    /// duration stays in force until an admin lifts it.
+    pub async fn suspend(
+        &self,
+        user_id: Uuid,
+        user_type: String,
+        kind: SuspensionKind,
+        reason: String,
+        issued_by: Uuid,
+        duration_hours: Option<i64>,
+    ) -> Result<account_suspension::Model, AppError> {
+        if reason.trim().is_empty() {
+            return Err(AppError::Validation("A reason is required".to_string()));
+        }
+
+        let now = Utc::now();
+        let expires_at = match (&kind, duration_hours) {
+            (SuspensionKind::Ban, _) => None,
+            (SuspensionKind::Suspension, Some(hours)) if hours > 0 => {
+                Some(now + Duration::hours(hours))
+            }
+            (SuspensionKind::Suspension, Some(_)) => {
+                return Err(AppError::Validation(
+                    "Duration must be a positive number of hours".to_string(),
+                ))
+            }
+            (SuspensionKind::Suspension, None) => None,
+        };
+
+        let suspension = account_suspension::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            user_id: Set(user_id),
+            user_type: Set(user_type),
+            kind: Set(kind),
+            reason: Set(reason),
+            issued_by: Set(issued_by),
+            expires_at: Set(expires_at),
+            lifted_at: Set(None),
+            lifted_by: Set(None),
+            lift_reason: Set(None),
+            created_at: Set(now),
+            updated_at: Set(now),
+        };
+
+        Ok(suspension.insert(&self.db).await?)
+    }
+
+    /// Lifts every suspension currently in force for a user. Returns how many were lifted.
+    pub async fn lift(
+        &self,
+        user_id: Uuid,
+        lifted_by: Uuid,
+        reason: Option<String>,
+    ) -> Result<u64, AppError> {
+        let now = Utc::now();
+        let result = AccountSuspension::update_many()
+            .col_expr(account_suspension::Column::LiftedAt, Expr::value(now))
+            .col_expr(account_suspension::Column::LiftedBy, Expr::value(lifted_by))
+            .col_expr(account_suspension::Column::LiftReason, Expr::value(reason))
+            .col_expr(account_suspension::Column::UpdatedAt, Expr::value(now))
+            .filter(account_suspension::Column::UserId.eq(user_id))
+            .filter(account_suspension::Column::LiftedAt.is_null())
+            .exec(&self.db)
+            .await?;
+
+        Ok(result.rows_affected)
+    }
+
+    pub async fn list_for_user(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<account_suspension::Model>, AppError> {
+        Ok(AccountSuspension::find()
+            .filter(account_suspension::Column::UserId.eq(user_id))
+            .order_by_desc(account_suspension::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+}
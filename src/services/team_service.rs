@@ -1,4 +1,5 @@
 use crate::models::postgres::{team, Team};
+use crate::services::chat_channel_service::ChatChannelService;
 use crate::utils::errors::AppError;
 use sea_orm::*;
 use uuid::Uuid;
@@ -13,6 +14,7 @@ impl TeamService {
         Self { db }
     }
 
+    /// Creates the team along with its team chat.
     pub async fn create_team(
         &self,
         team_name: String,
@@ -31,7 +33,13 @@ impl TeamService {
             ..Default::default()
         };
 
-        Ok(new_team.insert(&self.db).await?)
+        let txn = self.db.begin().await?;
+        let team = new_team.insert(&txn).await?;
+        // The chat is new, so the sync has no memberships to end
+        ChatChannelService::provision_team_chat(&txn, team.id).await?;
+        txn.commit().await?;
+
+        Ok(team)
     }
 
     pub async fn get_by_id(&self, id: Uuid) -> Result<Option<team::Model>, AppError> {
@@ -1,4 +1,7 @@
+use crate::models::enums::ApprovalStatus;
 use crate::models::postgres::{tournament, Tournament};
+use crate::services::chat_channel_service::ChatChannelService;
+use crate::services::outbox_service::{DomainEvent, OutboxService};
 use crate::utils::errors::AppError;
 use sea_orm::*;
 use uuid::Uuid;
@@ -28,6 +31,17 @@ impl TournamentService {
             .await?)
     }
 
+    pub async fn get_by_organizer(
+        &self,
+        organizer_id: Uuid,
+    ) -> Result<Vec<tournament::Model>, AppError> {
+        Ok(Tournament::find()
+            .filter(tournament::Column::SubmittedBy.eq(organizer_id))
+            .order_by_desc(tournament::Column::StartDate)
+            .all(&self.db)
+            .await?)
+    }
+
     pub async fn get_featured(&self) -> Result<Vec<tournament::Model>, AppError> {
         Ok(Tournament::find()
             .filter(tournament::Column::Featured.eq(true))
@@ -57,6 +71,67 @@ impl TournamentService {
             ..Default::default()
         };
 
-        Ok(new_tournament.insert(&self.db).await?)
+        let txn = self.db.begin().await?;
+        let tournament = new_tournament.insert(&txn).await?;
+        // The lobby is new, so the sync has no memberships to end
+        ChatChannelService::provision_tournament_lobby(&txn, tournament.id).await?;
+        txn.commit().await?;
+
+        Ok(tournament)
+    }
+
+    /// Records an admin's approval or rejection of a submitted tournament.
+    pub async fn review_tournament(
+        &self,
+        tournament_id: Uuid,
+        admin_id: Uuid,
+        status: ApprovalStatus,
+        rejection_reason: Option<String>,
+    ) -> Result<tournament::Model, AppError> {
+        let tournament = Tournament::find_by_id(tournament_id)
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        let now = chrono::Utc::now();
+        let previous_approval_status = tournament.approval_status.clone();
+        let mut update: tournament::ActiveModel = tournament.into();
+        match status {
+            ApprovalStatus::Approved => {
+                update.approved_by = Set(Some(admin_id));
+                update.approved_at = Set(Some(now));
+                update.rejection_reason = Set(None);
+            }
+            ApprovalStatus::Rejected => {
+                update.rejected_by = Set(Some(admin_id));
+                update.rejected_at = Set(Some(now));
+                update.rejection_reason = Set(rejection_reason);
+            }
+            _ => {
+                return Err(AppError::Validation(
+                    "Tournaments can only be approved or rejected".to_string(),
+                ))
+            }
+        }
+        update.approval_status = Set(status);
+        update.updated_at = Set(now);
+
+        let txn = self.db.begin().await?;
+        let tournament = update.update(&txn).await?;
+        OutboxService::record(
+            &txn,
+            &DomainEvent::TournamentTransitioned {
+                tournament_id: tournament.id,
+                tournament_name: tournament.tournament_name.clone(),
+                organization_id: tournament.submitted_by,
+                status: tournament.status.clone(),
+                approval_status: tournament.approval_status.clone(),
+                previous_approval_status,
+            },
+        )
+        .await?;
+        txn.commit().await?;
+
+        Ok(tournament)
     }
 }
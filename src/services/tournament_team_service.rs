@@ -1,26 +1,33 @@
 use crate::models::postgres::{tournament_team, TournamentTeam};
+use crate::services::chat_channel_service::ChatChannelService;
+use crate::services::outbox_service::{DomainEvent, OutboxService};
 use crate::utils::errors::AppError;
+use sea_orm::sea_query::OnConflict;
 use sea_orm::*;
 use uuid::Uuid;
 
 #[derive(Clone)]
 pub struct TournamentTeamService {
     db: DatabaseConnection,
+    chat_channels: ChatChannelService,
 }
 
 impl TournamentTeamService {
-    pub fn new(db: DatabaseConnection) -> Self {
-        Self { db }
+    pub fn new(db: DatabaseConnection, chat_channels: ChatChannelService) -> Self {
+        Self { db, chat_channels }
     }
 
+    /// Registers a team for a tournament and brings its players into the
+    /// tournament lobby. A team can only be registered once.
     pub async fn join_tournament(
         &self,
         tournament_id: Uuid,
         team_id: Uuid,
         qualified_through: Option<String>,
     ) -> Result<tournament_team::Model, AppError> {
+        let id = Uuid::new_v4();
         let new_entry = tournament_team::ActiveModel {
-            id: Set(Uuid::new_v4()),
+            id: Set(id),
             tournament_id: Set(tournament_id),
             team_id: Set(team_id),
             qualified_through: Set(qualified_through),
@@ -28,7 +35,41 @@ impl TournamentTeamService {
             ..Default::default()
         };
 
-        Ok(new_entry.insert(&self.db).await?)
+        let txn = self.db.begin().await?;
+        let inserted = TournamentTeam::insert(new_entry)
+            .on_conflict(
+                OnConflict::columns([
+                    tournament_team::Column::TournamentId,
+                    tournament_team::Column::TeamId,
+                ])
+                .do_nothing()
+                .to_owned(),
+            )
+            .exec_without_returning(&txn)
+            .await?;
+        if inserted == 0 {
+            return Err(AppError::Validation(
+                "This team is already registered for the tournament".to_string(),
+            ));
+        }
+        let entry = TournamentTeam::find_by_id(id)
+            .one(&txn)
+            .await?
+            .ok_or(AppError::InternalServerError)?;
+        let ended = ChatChannelService::provision_tournament_lobby(&txn, tournament_id).await?;
+        OutboxService::record(
+            &txn,
+            &DomainEvent::TeamJoined {
+                tournament_team_id: entry.id,
+                tournament_id: entry.tournament_id,
+                team_id: entry.team_id,
+            },
+        )
+        .await?;
+        txn.commit().await?;
+        self.chat_channels.evict(ended).await;
+
+        Ok(entry)
     }
 
     pub async fn get_tournament_teams(
@@ -0,0 +1,302 @@
+use crate::models::postgres::{
+    two_factor_recovery_code, user_two_factor, TwoFactorRecoveryCode, UserTwoFactor,
+};
+use crate::utils::errors::AppError;
+use chrono::Utc;
+use rand::{distributions::Alphanumeric, rngs::OsRng, Rng, RngCore};
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use sha2::{Digest, Sha256};
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+const TOTP_ISSUER: &str = "Aegis Gaming";
+const TOTP_STEP_SECONDS: u64 = 30;
+const RECOVERY_CODE_COUNT: usize = 10;
+
+#[derive(Clone)]
+pub struct TwoFactorService {
+    db: DatabaseConnection,
+}
+
+impl TwoFactorService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn build_totp(secret: &str, account_name: &str) -> Result<TOTP, AppError> {
+        let bytes = Secret::Encoded(secret.to_string())
+            .to_bytes()
+            .map_err(|_| AppError::InternalServerError)?;
+
+        TOTP::new(
+            Algorithm::SHA1,
+            6,
+            1,
+            TOTP_STEP_SECONDS,
+            bytes,
+            Some(TOTP_ISSUER.to_string()),
+            account_name.replace(':', ""),
+        )
+        .map_err(|_| AppError::InternalServerError)
+    }
+
+    fn hash_recovery_code(code: &str) -> String {
+        let normalized = code.trim().replace('-', "").to_uppercase();
+        hex::encode(Sha256::digest(normalized.as_bytes()))
+    }
+
+    pub async fn get(&self, user_id: Uuid) -> Result<Option<user_two_factor::Model>, AppError> {
+        Ok(UserTwoFactor::find()
+            .filter(user_two_factor::Column::UserId.eq(user_id))
+            .one(&self.db)
+            .await?)
+    }
+
+    pub async fn is_enabled(&self, user_id: Uuid) -> Result<bool, AppError> {
+        Ok(self.get(user_id).await?.is_some_and(|tf| tf.enabled))
+    }
+
+    /// Starts (or restarts) enrollment with a fresh secret. Returns the base32
+    /// secret and the otpauth:// provisioning URI for the authenticator QR code.
+    pub async fn begin_enrollment(
+        &self,
+        user_id: Uuid,
+        user_type: String,
+        account_name: &str,
+    ) -> Result<(String, String), AppError> {
+        let existing = self.get(user_id).await?;
+        if existing.as_ref().is_some_and(|tf| tf.enabled) {
+            return Err(AppError::Validation(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        let mut raw = [0u8; 20];
+        OsRng.fill_bytes(&mut raw);
+        let secret = match Secret::Raw(raw.to_vec()).to_encoded() {
+            Secret::Encoded(s) => s,
+            Secret::Raw(_) => return Err(AppError::InternalServerError),
+        };
+        let uri = Self::build_totp(&secret, account_name)?.get_url();
+
+        let now = Utc::now();
+        match existing {
+            Some(tf) => {
+                let mut update: user_two_factor::ActiveModel = tf.into();
+                update.secret = Set(secret.clone());
+                update.last_used_step = Set(None);
+                update.updated_at = Set(now);
+                update.update(&self.db).await?;
+            }
+            None => {
+                user_two_factor::ActiveModel {
+                    id: Set(Uuid::new_v4()),
+                    user_id: Set(user_id),
+                    user_type: Set(user_type),
+                    secret: Set(secret.clone()),
+                    enabled: Set(false),
+                    confirmed_at: Set(None),
+                    last_used_step: Set(None),
+                    created_at: Set(now),
+                    updated_at: Set(now),
+                }
+                .insert(&self.db)
+                .await?;
+            }
+        }
+
+        Ok((secret, uri))
+    }
+
+    /// Confirms enrollment with the first code from the authenticator and
+    /// returns the one-time recovery codes, which are only ever shown here.
+    pub async fn confirm_enrollment(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<Vec<String>, AppError> {
+        let tf = self.get(user_id).await?.ok_or(AppError::Validation(
+            "Two-factor enrollment has not been started".to_string(),
+        ))?;
+        if tf.enabled {
+            return Err(AppError::Validation(
+                "Two-factor authentication is already enabled".to_string(),
+            ));
+        }
+
+        let step = Self::match_totp(&tf, code)?.ok_or(AppError::Validation(
+            "Invalid authentication code".to_string(),
+        ))?;
+
+        if !self.claim_step(&tf, step, true).await? {
+            return Err(AppError::Validation(
+                "Invalid authentication code".to_string(),
+            ));
+        }
+
+        self.replace_recovery_codes(user_id).await
+    }
+
+    /// Checks a TOTP code or, failing that, an unused recovery code. A matching
+    /// recovery code is burned.
+    pub async fn verify(&self, user_id: Uuid, code: &str) -> Result<bool, AppError> {
+        let tf = match self.get(user_id).await? {
+            Some(tf) if tf.enabled => tf,
+            _ => return Ok(false),
+        };
+
+        if let Some(step) = Self::match_totp(&tf, code)? {
+            return self.claim_step(&tf, step, false).await;
+        }
+
+        // Burning the code is the check: of two requests racing with the
+        // same code, only one sees its update land
+        let burned = TwoFactorRecoveryCode::update_many()
+            .col_expr(
+                two_factor_recovery_code::Column::UsedAt,
+                Expr::value(Some(Utc::now())),
+            )
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id))
+            .filter(two_factor_recovery_code::Column::CodeHash.eq(Self::hash_recovery_code(code)))
+            .filter(two_factor_recovery_code::Column::UsedAt.is_null())
+            .exec(&self.db)
+            .await?;
+        Ok(burned.rows_affected == 1)
+    }
+
+    /// Records `step` as the last accepted one unless a concurrent request got
+    /// there first, so each code is accepted once. `enable` also completes
+    /// enrollment.
+    async fn claim_step(
+        &self,
+        tf: &user_two_factor::Model,
+        step: i64,
+        enable: bool,
+    ) -> Result<bool, AppError> {
+        let now = Utc::now();
+        let mut update = UserTwoFactor::update_many()
+            .col_expr(
+                user_two_factor::Column::LastUsedStep,
+                Expr::value(Some(step)),
+            )
+            .col_expr(user_two_factor::Column::UpdatedAt, Expr::value(now))
+            .filter(user_two_factor::Column::Id.eq(tf.id))
+            .filter(
+                Condition::any()
+                    .add(user_two_factor::Column::LastUsedStep.is_null())
+                    .add(user_two_factor::Column::LastUsedStep.lt(step)),
+            );
+        if enable {
+            update = update
+                .col_expr(user_two_factor::Column::Enabled, Expr::value(true))
+                .col_expr(user_two_factor::Column::ConfirmedAt, Expr::value(Some(now)))
+                .filter(user_two_factor::Column::Enabled.eq(false));
+        }
+
+        Ok(update.exec(&self.db).await?.rows_affected == 1)
+    }
+
+    /// Returns the matched time step, rejecting steps at or before the last
+    /// accepted one so a code cannot be replayed.
+    fn match_totp(tf: &user_two_factor::Model, code: &str) -> Result<Option<i64>, AppError> {
+        let code = code.trim();
+        if code.len() != 6 || !code.chars().all(|c| c.is_ascii_digit()) {
+            return Ok(None);
+        }
+
+        let totp = Self::build_totp(&tf.secret, &tf.user_id.to_string())?;
+        let now = Utc::now().timestamp() as u64;
+
+        for offset in [0i64, -1, 1] {
+            let time = (now as i64 + offset * TOTP_STEP_SECONDS as i64) as u64;
+            let step = (time / TOTP_STEP_SECONDS) as i64;
+            if tf.last_used_step.is_some_and(|last| step <= last) {
+                continue;
+            }
+            if totp.generate(time) == code {
+                return Ok(Some(step));
+            }
+        }
+
+        Ok(None)
+    }
+
+    pub async fn regenerate_recovery_codes(
+        &self,
+        user_id: Uuid,
+        code: &str,
+    ) -> Result<Vec<String>, AppError> {
+        if !self.verify(user_id, code).await? {
+            return Err(AppError::Validation(
+                "Invalid authentication code".to_string(),
+            ));
+        }
+        self.replace_recovery_codes(user_id).await
+    }
+
+    async fn replace_recovery_codes(&self, user_id: Uuid) -> Result<Vec<String>, AppError> {
+        let codes: Vec<String> = (0..RECOVERY_CODE_COUNT)
+            .map(|_| {
+                let raw: String = OsRng
+                    .sample_iter(&Alphanumeric)
+                    .take(10)
+                    .map(|c| (c as char).to_ascii_uppercase())
+                    .collect();
+                format!("{}-{}", &raw[..5], &raw[5..])
+            })
+            .collect();
+
+        let txn = self.db.begin().await?;
+        TwoFactorRecoveryCode::delete_many()
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+
+        let now = Utc::now();
+        TwoFactorRecoveryCode::insert_many(codes.iter().map(|code| {
+            two_factor_recovery_code::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                user_id: Set(user_id),
+                code_hash: Set(Self::hash_recovery_code(code)),
+                used_at: Set(None),
+                created_at: Set(now),
+            }
+        }))
+        .exec(&txn)
+        .await?;
+        txn.commit().await?;
+
+        Ok(codes)
+    }
+
+    pub async fn remaining_recovery_codes(&self, user_id: Uuid) -> Result<u64, AppError> {
+        Ok(TwoFactorRecoveryCode::find()
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id))
+            .filter(two_factor_recovery_code::Column::UsedAt.is_null())
+            .count(&self.db)
+            .await?)
+    }
+
+    /// Turns 2FA off after checking a current code or recovery code.
+    pub async fn disable(&self, user_id: Uuid, code: &str) -> Result<(), AppError> {
+        if !self.verify(user_id, code).await? {
+            return Err(AppError::Validation(
+                "Invalid authentication code".to_string(),
+            ));
+        }
+
+        let txn = self.db.begin().await?;
+        UserTwoFactor::delete_many()
+            .filter(user_two_factor::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        TwoFactorRecoveryCode::delete_many()
+            .filter(two_factor_recovery_code::Column::UserId.eq(user_id))
+            .exec(&txn)
+            .await?;
+        txn.commit().await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,563 @@
+use crate::models::enums::{WebhookDeliveryStatus, WebhookEvent};
+use crate::models::postgres::{
+    webhook_delivery, webhook_endpoint, WebhookDelivery, WebhookEndpoint,
+};
+use crate::utils::errors::AppError;
+use chrono::{Duration, Utc};
+use hmac::{Hmac, Mac};
+use rand::RngCore;
+use sea_orm::sea_query::OnConflict;
+use sea_orm::*;
+use sha2::Sha256;
+use std::net::{IpAddr, SocketAddr};
+use uuid::Uuid;
+
+pub const SIGNATURE_HEADER: &str = "x-aegis-signature";
+pub const TIMESTAMP_HEADER: &str = "x-aegis-timestamp";
+pub const EVENT_HEADER: &str = "x-aegis-event";
+pub const DELIVERY_HEADER: &str = "x-aegis-delivery";
+
+const MAX_ATTEMPTS: i32 = 8;
+// Retry delays double from here: 30s, 1m, 2m ... capped below
+const RETRY_BASE_SECS: i64 = 30;
+const RETRY_MAX_SECS: i64 = 6 * 3600;
+// A claimed delivery is hidden from other workers for this long
+const CLAIM_LEASE_SECS: i64 = 300;
+const DELIVERY_BATCH: i64 = 50;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+const RESPONSE_BODY_LIMIT: usize = 1024;
+
+/// `v1=<hex HMAC-SHA256 of "{timestamp}.{body}">`, keyed with the endpoint secret.
+/// Receivers should recompute it and reject stale timestamps.
+pub fn sign_payload(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any length");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("v1={}", hex::encode(mac.finalize().into_bytes()))
+}
+
+/// Whether an address is reachable on the public internet. Endpoints are
+/// organization-supplied, so anything pointing back into our own network
+/// (loopback, private ranges, link-local metadata services) is refused.
+fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(v4) => {
+            let [a, b, ..] = v4.octets();
+            !(v4.is_loopback()
+                || v4.is_private()
+                || v4.is_link_local()
+                || v4.is_unspecified()
+                || v4.is_broadcast()
+                || v4.is_documentation()
+                || a == 0
+                || (a == 100 && (64..128).contains(&b)) // carrier-grade NAT
+                || a >= 224) // multicast and reserved
+        }
+        IpAddr::V6(v6) => {
+            if let Some(v4) = v6.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(v4));
+            }
+            let first = v6.segments()[0];
+            !(v6.is_loopback()
+                || v6.is_unspecified()
+                || v6.is_multicast()
+                || (first & 0xfe00) == 0xfc00 // unique local
+                || (first & 0xffc0) == 0xfe80) // link-local
+        }
+    }
+}
+
+/// Resolves a webhook URL and returns its addresses, refusing it unless every
+/// one of them is public. The message is shown to whoever registered the URL.
+pub async fn resolve_destination(url: &str) -> Result<Vec<SocketAddr>, String> {
+    let parsed = reqwest::Url::parse(url).map_err(|_| "Invalid webhook URL".to_string())?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err("Webhook URLs must use http or https".to_string());
+    }
+    let port = parsed.port_or_known_default().unwrap_or(443);
+
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| "Invalid webhook URL".to_string())?;
+
+    // IPv6 literals keep their brackets in the host string
+    let addrs: Vec<SocketAddr> = match host.trim_matches(['[', ']']).parse::<IpAddr>() {
+        Ok(ip) => vec![SocketAddr::new(ip, port)],
+        Err(_) => tokio::net::lookup_host((host, port))
+            .await
+            .map_err(|_| format!("Could not resolve {}", host))?
+            .collect(),
+    };
+    if addrs.is_empty() {
+        return Err("Invalid webhook URL".to_string());
+    }
+    if addrs.iter().any(|addr| !is_public_address(addr.ip())) {
+        return Err("Webhook URLs must point to a public address".to_string());
+    }
+    Ok(addrs)
+}
+
+/// A client for one delivery, pinned to the addresses `resolve_destination`
+/// approved so a second DNS lookup can't point it elsewhere. Redirects are
+/// not followed, since their targets were never checked.
+fn delivery_client(url: &str, addrs: &[SocketAddr]) -> reqwest::Result<reqwest::Client> {
+    let mut builder = reqwest::Client::builder().redirect(reqwest::redirect::Policy::none());
+    if let Some(domain) = reqwest::Url::parse(url)
+        .ok()
+        .and_then(|u| u.domain().map(str::to_string))
+    {
+        builder = builder.resolve_to_addrs(&domain, addrs);
+    }
+    builder.build()
+}
+
+/// Reads at most `RESPONSE_BODY_LIMIT` bytes of a response, so a receiver
+/// can't make us buffer an arbitrarily large body.
+async fn read_limited(mut response: reqwest::Response) -> String {
+    let mut body = Vec::with_capacity(RESPONSE_BODY_LIMIT);
+    while body.len() < RESPONSE_BODY_LIMIT {
+        match response.chunk().await {
+            Ok(Some(chunk)) => {
+                let take = chunk.len().min(RESPONSE_BODY_LIMIT - body.len());
+                body.extend_from_slice(&chunk[..take]);
+            }
+            _ => break,
+        }
+    }
+    // Drop a character cut off at the limit rather than storing half of it
+    match String::from_utf8(body) {
+        Ok(text) => text,
+        Err(e) => {
+            let valid = e.utf8_error().valid_up_to();
+            let mut bytes = e.into_bytes();
+            bytes.truncate(valid);
+            String::from_utf8(bytes).unwrap_or_default()
+        }
+    }
+}
+
+/// What happened when one delivery was attempted.
+#[derive(Debug, Clone)]
+pub struct DeliveryAttempt {
+    pub status: Option<u16>,
+    pub body: Option<String>,
+    pub error: Option<String>,
+}
+
+impl DeliveryAttempt {
+    pub fn succeeded(&self) -> bool {
+        self.status.is_some_and(|s| (200..300).contains(&s))
+    }
+}
+
+/// Posts one signed webhook body. Never fails; errors end up in the attempt.
+pub async fn post_signed(
+    client: &reqwest::Client,
+    url: &str,
+    secret: &str,
+    event: WebhookEvent,
+    delivery_id: Uuid,
+    body: &str,
+) -> DeliveryAttempt {
+    let timestamp = Utc::now().timestamp();
+
+    let result = client
+        .post(url)
+        .header(reqwest::header::CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, event.as_str())
+        .header(DELIVERY_HEADER, delivery_id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(SIGNATURE_HEADER, sign_payload(secret, timestamp, body))
+        .body(body.to_string())
+        .timeout(std::time::Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .send()
+        .await;
+
+    match result {
+        Ok(response) => {
+            let status = response.status().as_u16();
+            let body = read_limited(response).await;
+            DeliveryAttempt {
+                status: Some(status),
+                body: Some(body),
+                error: None,
+            }
+        }
+        Err(e) => DeliveryAttempt {
+            status: None,
+            body: None,
+            error: Some(e.to_string()),
+        },
+    }
+}
+
+#[derive(Clone)]
+pub struct WebhookService {
+    db: DatabaseConnection,
+}
+
+impl WebhookService {
+    pub fn new(db: DatabaseConnection) -> Self {
+        Self { db }
+    }
+
+    fn generate_secret() -> String {
+        let mut bytes = [0u8; 24];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        format!("whsec_{}", hex::encode(bytes))
+    }
+
+    async fn validate(url: &str, events: &[String]) -> Result<(), AppError> {
+        resolve_destination(url)
+            .await
+            .map_err(AppError::Validation)?;
+
+        if events.is_empty() {
+            return Err(AppError::Validation(
+                "Subscribe to at least one event".to_string(),
+            ));
+        }
+        if let Some(unknown) = events.iter().find(|e| WebhookEvent::parse(e).is_none()) {
+            return Err(AppError::Validation(format!("Unknown event: {}", unknown)));
+        }
+        Ok(())
+    }
+
+    // ========================================
+    // ENDPOINTS
+    // ========================================
+
+    /// Registers an endpoint and returns it with its signing secret, which is
+    /// not shown again.
+    pub async fn create_endpoint(
+        &self,
+        organization_id: Uuid,
+        url: String,
+        description: Option<String>,
+        events: Vec<String>,
+    ) -> Result<(webhook_endpoint::Model, String), AppError> {
+        Self::validate(&url, &events).await?;
+
+        let secret = Self::generate_secret();
+        let now = Utc::now();
+        let endpoint = webhook_endpoint::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            organization_id: Set(organization_id),
+            url: Set(url),
+            description: Set(description),
+            secret: Set(secret.clone()),
+            events: Set(events),
+            is_active: Set(true),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&self.db)
+        .await?;
+
+        Ok((endpoint, secret))
+    }
+
+    pub async fn list_endpoints(
+        &self,
+        organization_id: Uuid,
+    ) -> Result<Vec<webhook_endpoint::Model>, AppError> {
+        Ok(WebhookEndpoint::find()
+            .filter(webhook_endpoint::Column::OrganizationId.eq(organization_id))
+            .order_by_desc(webhook_endpoint::Column::CreatedAt)
+            .all(&self.db)
+            .await?)
+    }
+
+    pub async fn get_endpoint(
+        &self,
+        organization_id: Uuid,
+        endpoint_id: Uuid,
+    ) -> Result<webhook_endpoint::Model, AppError> {
+        WebhookEndpoint::find_by_id(endpoint_id)
+            .filter(webhook_endpoint::Column::OrganizationId.eq(organization_id))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)
+    }
+
+    pub async fn update_endpoint(
+        &self,
+        endpoint: webhook_endpoint::Model,
+        url: Option<String>,
+        description: Option<String>,
+        events: Option<Vec<String>>,
+        is_active: Option<bool>,
+    ) -> Result<webhook_endpoint::Model, AppError> {
+        Self::validate(
+            url.as_deref().unwrap_or(&endpoint.url),
+            events.as_deref().unwrap_or(&endpoint.events),
+        )
+        .await?;
+
+        let mut update: webhook_endpoint::ActiveModel = endpoint.into();
+        if let Some(url) = url {
+            update.url = Set(url);
+        }
+        if let Some(description) = description {
+            update.description = Set(Some(description));
+        }
+        if let Some(events) = events {
+            update.events = Set(events);
+        }
+        if let Some(is_active) = is_active {
+            update.is_active = Set(is_active);
+        }
+        update.updated_at = Set(Utc::now());
+
+        Ok(update.update(&self.db).await?)
+    }
+
+    pub async fn delete_endpoint(&self, endpoint: webhook_endpoint::Model) -> Result<(), AppError> {
+        endpoint.delete(&self.db).await?;
+        Ok(())
+    }
+
+    // ========================================
+    // DELIVERIES
+    // ========================================
+
+    /// Queues `event` for every active endpoint of the organization subscribed
+    /// to it. The delivery worker sends them.
+    pub async fn dispatch(
+        &self,
+        organization_id: Uuid,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) -> Result<usize, AppError> {
+        self.dispatch_event(organization_id, Uuid::new_v4(), event, data)
+            .await
+    }
+
+    /// [`dispatch`](Self::dispatch) with a caller-chosen event id. Queuing the
+    /// same id for an endpoint twice is a no-op, so at-least-once producers
+    /// don't duplicate deliveries.
+    pub async fn dispatch_event(
+        &self,
+        organization_id: Uuid,
+        event_id: Uuid,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) -> Result<usize, AppError> {
+        let endpoints: Vec<_> = WebhookEndpoint::find()
+            .filter(webhook_endpoint::Column::OrganizationId.eq(organization_id))
+            .filter(webhook_endpoint::Column::IsActive.eq(true))
+            .all(&self.db)
+            .await?
+            .into_iter()
+            .filter(|e| e.events.iter().any(|name| name == event.as_str()))
+            .collect();
+        if endpoints.is_empty() {
+            return Ok(0);
+        }
+
+        let now = Utc::now();
+        let payload = serde_json::json!({
+            "id": event_id,
+            "type": event.as_str(),
+            "created_at": now,
+            "data": data,
+        });
+
+        let deliveries = endpoints
+            .iter()
+            .map(|endpoint| webhook_delivery::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                endpoint_id: Set(endpoint.id),
+                event_id: Set(event_id),
+                event_type: Set(event),
+                payload: Set(payload.clone()),
+                status: Set(WebhookDeliveryStatus::Pending),
+                attempts: Set(0),
+                next_attempt_at: Set(now),
+                last_attempt_at: Set(None),
+                response_status: Set(None),
+                response_body: Set(None),
+                error: Set(None),
+                replay_of: Set(None),
+                created_at: Set(now),
+                updated_at: Set(now),
+            });
+        // Endpoints that already have this event are skipped by the unique index
+        let queued = WebhookDelivery::insert_many(deliveries)
+            .on_conflict(OnConflict::new().do_nothing().to_owned())
+            .exec_without_returning(&self.db)
+            .await?;
+
+        Ok(queued as usize)
+    }
+
+    /// Same as [`dispatch`](Self::dispatch) but only logs failures, for callers
+    /// whose own work already succeeded.
+    pub async fn notify(
+        &self,
+        organization_id: Uuid,
+        event: WebhookEvent,
+        data: serde_json::Value,
+    ) {
+        if let Err(e) = self.dispatch(organization_id, event, data).await {
+            tracing::warn!("Failed to queue {} webhooks: {:?}", event.as_str(), e);
+        }
+    }
+
+    pub async fn list_deliveries(
+        &self,
+        endpoint_id: Uuid,
+        limit: u64,
+    ) -> Result<Vec<webhook_delivery::Model>, AppError> {
+        Ok(WebhookDelivery::find()
+            .filter(webhook_delivery::Column::EndpointId.eq(endpoint_id))
+            .order_by_desc(webhook_delivery::Column::CreatedAt)
+            .limit(limit)
+            .all(&self.db)
+            .await?)
+    }
+
+    /// Queues the exact payload of an earlier delivery again, as a new delivery.
+    pub async fn replay(
+        &self,
+        endpoint_id: Uuid,
+        delivery_id: Uuid,
+    ) -> Result<webhook_delivery::Model, AppError> {
+        let original = WebhookDelivery::find_by_id(delivery_id)
+            .filter(webhook_delivery::Column::EndpointId.eq(endpoint_id))
+            .one(&self.db)
+            .await?
+            .ok_or(AppError::NotFound)?;
+
+        let now = Utc::now();
+        Ok(webhook_delivery::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            endpoint_id: Set(original.endpoint_id),
+            event_id: Set(original.event_id),
+            event_type: Set(original.event_type),
+            payload: Set(original.payload),
+            status: Set(WebhookDeliveryStatus::Pending),
+            attempts: Set(0),
+            next_attempt_at: Set(now),
+            last_attempt_at: Set(None),
+            response_status: Set(None),
+            response_body: Set(None),
+            error: Set(None),
+            replay_of: Set(Some(original.id)),
+            created_at: Set(now),
+            updated_at: Set(now),
+        }
+        .insert(&self.db)
+        .await?)
+    }
+
+    /// Sends every delivery that is due. Rows are claimed with a lease first, so
+    /// several nodes can run the worker without sending anything twice.
+    pub async fn process_due_deliveries(&self) -> Result<usize, AppError> {
+        let claimed = WebhookDelivery::find()
+            .from_raw_sql(Statement::from_sql_and_values(
+                DbBackend::Postgres,
+                r#"UPDATE webhook_deliveries
+                   SET next_attempt_at = NOW() + make_interval(secs => $1)
+                   WHERE id IN (
+                       SELECT id FROM webhook_deliveries
+                       WHERE status = 'pending' AND next_attempt_at <= NOW()
+                       ORDER BY next_attempt_at
+                       LIMIT $2
+                       FOR UPDATE SKIP LOCKED
+                   )
+                   RETURNING *"#,
+                [(CLAIM_LEASE_SECS as f64).into(), DELIVERY_BATCH.into()],
+            ))
+            .all(&self.db)
+            .await?;
+
+        let count = claimed.len();
+        let mut tasks = tokio::task::JoinSet::new();
+        for delivery in claimed {
+            let service = self.clone();
+            tasks.spawn(async move {
+                if let Err(e) = service.attempt(delivery).await {
+                    tracing::warn!("Webhook delivery failed to record: {:?}", e);
+                }
+            });
+        }
+        while tasks.join_next().await.is_some() {}
+
+        Ok(count)
+    }
+
+    async fn attempt(&self, delivery: webhook_delivery::Model) -> Result<(), AppError> {
+        let endpoint = WebhookEndpoint::find_by_id(delivery.endpoint_id)
+            .one(&self.db)
+            .await?
+            .filter(|e| e.is_active);
+
+        let outcome = match &endpoint {
+            Some(endpoint) => self.deliver(endpoint, &delivery).await,
+            None => DeliveryAttempt {
+                status: None,
+                body: None,
+                error: Some("Endpoint is disabled".to_string()),
+            },
+        };
+
+        let now = Utc::now();
+        let attempts = delivery.attempts + 1;
+        let status = if outcome.succeeded() {
+            WebhookDeliveryStatus::Succeeded
+        } else if endpoint.is_none() || attempts >= MAX_ATTEMPTS {
+            WebhookDeliveryStatus::Failed
+        } else {
+            WebhookDeliveryStatus::Pending
+        };
+        let retry_in = (RETRY_BASE_SECS << (attempts - 1).clamp(0, 20)).min(RETRY_MAX_SECS);
+
+        let mut update: webhook_delivery::ActiveModel = delivery.into();
+        update.status = Set(status);
+        update.attempts = Set(attempts);
+        update.last_attempt_at = Set(Some(now));
+        update.next_attempt_at = Set(now + Duration::seconds(retry_in));
+        update.response_status = Set(outcome.status.map(i32::from));
+        update.response_body = Set(outcome.body);
+        update.error = Set(outcome.error);
+        update.updated_at = Set(now);
+        update.update(&self.db).await?;
+
+        Ok(())
+    }
+
+    /// Checks the endpoint's address again before every attempt, since its
+    /// DNS may have changed since it was registered.
+    async fn deliver(
+        &self,
+        endpoint: &webhook_endpoint::Model,
+        delivery: &webhook_delivery::Model,
+    ) -> DeliveryAttempt {
+        let refused = |error: String| DeliveryAttempt {
+            status: None,
+            body: None,
+            error: Some(error),
+        };
+        let addrs = match resolve_destination(&endpoint.url).await {
+            Ok(addrs) => addrs,
+            Err(e) => return refused(e),
+        };
+        let client = match delivery_client(&endpoint.url, &addrs) {
+            Ok(client) => client,
+            Err(e) => return refused(e.to_string()),
+        };
+
+        post_signed(
+            &client,
+            &endpoint.url,
+            &endpoint.secret,
+            delivery.event_type,
+            delivery.id,
+            &delivery.payload.to_string(),
+        )
+        .await
+    }
+}
@@ -1,2 +1,3 @@
 pub mod errors;
+pub mod user_agent;
 pub mod validation;
@@ -0,0 +1,89 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeviceInfo {
+    pub browser: Option<String>,
+    pub os: Option<String>,
+    pub device_type: &'static str, // "desktop", "mobile", "tablet", "bot" or "unknown"
+}
+
+/// Best-effort browser/OS detection for the session list. Order matters: most
+/// browsers also claim to be the ones they are built on (Edge says Chrome,
+/// Chrome says Safari).
+pub fn parse_user_agent(user_agent: &str) -> DeviceInfo {
+    let ua = user_agent.to_lowercase();
+
+    if ua.is_empty() {
+        return DeviceInfo {
+            browser: None,
+            os: None,
+            device_type: "unknown",
+        };
+    }
+
+    let browser = [
+        ("edg/", "Edge"),
+        ("opr/", "Opera"),
+        ("samsungbrowser/", "Samsung Internet"),
+        ("firefox/", "Firefox"),
+        ("fxios/", "Firefox"),
+        ("crios/", "Chrome"),
+        ("chrome/", "Chrome"),
+        ("safari/", "Safari"),
+    ]
+    .iter()
+    .find_map(|(token, name)| {
+        ua.find(token).map(|pos| {
+            let version: String = ua[pos + token.len()..]
+                .chars()
+                .take_while(|c| c.is_ascii_digit())
+                .collect();
+            if version.is_empty() {
+                name.to_string()
+            } else {
+                format!("{} {}", name, version)
+            }
+        })
+    })
+    .or_else(|| {
+        [
+            ("curl/", "curl"),
+            ("postman", "Postman"),
+            ("okhttp", "OkHttp"),
+        ]
+        .iter()
+        .find(|(token, _)| ua.contains(token))
+        .map(|(_, name)| name.to_string())
+    });
+
+    let os = [
+        ("windows nt", "Windows"),
+        ("iphone", "iOS"),
+        ("ipad", "iPadOS"),
+        ("android", "Android"),
+        ("cros", "ChromeOS"),
+        ("mac os x", "macOS"),
+        ("linux", "Linux"),
+    ]
+    .iter()
+    .find(|(token, _)| ua.contains(token))
+    .map(|(_, name)| name.to_string());
+
+    let device_type = if ua.contains("bot") || ua.contains("spider") || ua.contains("crawl") {
+        "bot"
+    } else if ua.contains("ipad") || ua.contains("tablet") {
+        "tablet"
+    } else if ua.contains("mobi") || ua.contains("iphone") || ua.contains("android") {
+        "mobile"
+    } else if os.is_some() {
+        "desktop"
+    } else {
+        "unknown"
+    };
+
+    DeviceInfo {
+        browser,
+        os,
+        device_type,
+    }
+}
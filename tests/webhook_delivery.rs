@@ -0,0 +1,143 @@
+use aegis_backend::models::enums::WebhookEvent;
+use aegis_backend::services::webhook_service::{
+    post_signed, resolve_destination, sign_payload, DELIVERY_HEADER, EVENT_HEADER,
+    SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use axum::{http::HeaderMap, http::StatusCode, routing::post, Router};
+use std::sync::{Arc, Mutex};
+use uuid::Uuid;
+
+type Received = Arc<Mutex<Vec<(HeaderMap, String)>>>;
+
+/// Starts a stand-in receiver on a free local port. `/ok` answers 200 and
+/// `/fail` answers 500; both record what they were sent.
+async fn stand_in() -> (String, Received) {
+    let received: Received = Arc::default();
+
+    let ok = received.clone();
+    let fail = received.clone();
+    let app = Router::new()
+        .route(
+            "/ok",
+            post(move |headers: HeaderMap, body: String| async move {
+                ok.lock().unwrap().push((headers, body));
+                StatusCode::OK
+            }),
+        )
+        .route(
+            "/fail",
+            post(move |headers: HeaderMap, body: String| async move {
+                fail.lock().unwrap().push((headers, body));
+                (StatusCode::INTERNAL_SERVER_ERROR, "boom")
+            }),
+        );
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+    (format!("http://{}", addr), received)
+}
+
+#[tokio::test]
+async fn delivery_is_signed_with_the_endpoint_secret() {
+    let (base, received) = stand_in().await;
+    let client = reqwest::Client::new();
+    let delivery_id = Uuid::new_v4();
+    let body = r#"{"type":"invite.accepted","data":{}}"#;
+
+    let attempt = post_signed(
+        &client,
+        &format!("{}/ok", base),
+        "whsec_test",
+        WebhookEvent::InviteAccepted,
+        delivery_id,
+        body,
+    )
+    .await;
+    assert!(attempt.succeeded());
+    assert_eq!(attempt.status, Some(200));
+
+    let received = received.lock().unwrap();
+    let (headers, received_body) = &received[0];
+    assert_eq!(received_body, body);
+    assert_eq!(headers[EVENT_HEADER], "invite.accepted");
+    assert_eq!(headers[DELIVERY_HEADER], delivery_id.to_string().as_str());
+
+    let timestamp: i64 = headers[TIMESTAMP_HEADER].to_str().unwrap().parse().unwrap();
+    let signature = headers[SIGNATURE_HEADER].to_str().unwrap();
+    assert_eq!(signature, sign_payload("whsec_test", timestamp, body));
+    assert_ne!(signature, sign_payload("whsec_other", timestamp, body));
+}
+
+#[tokio::test]
+async fn error_responses_are_reported_as_failures() {
+    let (base, _) = stand_in().await;
+    let client = reqwest::Client::new();
+
+    let attempt = post_signed(
+        &client,
+        &format!("{}/fail", base),
+        "whsec_test",
+        WebhookEvent::TeamRegistered,
+        Uuid::new_v4(),
+        "{}",
+    )
+    .await;
+    assert!(!attempt.succeeded());
+    assert_eq!(attempt.status, Some(500));
+    assert_eq!(attempt.body.as_deref(), Some("boom"));
+}
+
+#[tokio::test]
+async fn unreachable_endpoints_are_reported_as_failures() {
+    // Bind then drop to get a port nothing is listening on
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    drop(listener);
+
+    let attempt = post_signed(
+        &reqwest::Client::new(),
+        &format!("http://{}/", addr),
+        "whsec_test",
+        WebhookEvent::BattleResultVerified,
+        Uuid::new_v4(),
+        "{}",
+    )
+    .await;
+    assert!(!attempt.succeeded());
+    assert!(attempt.status.is_none());
+    assert!(attempt.error.is_some());
+}
+
+#[tokio::test]
+async fn internal_addresses_are_refused() {
+    for url in [
+        "http://127.0.0.1:8080/hook",
+        "http://localhost/hook",
+        "http://10.0.0.5/hook",
+        "http://192.168.1.1/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://0.0.0.0/hook",
+        "http://[::1]/hook",
+        "http://[::ffff:127.0.0.1]/hook",
+        "http://[fd00::1]/hook",
+    ] {
+        assert!(
+            resolve_destination(url).await.is_err(),
+            "{} was allowed",
+            url
+        );
+    }
+}
+
+#[tokio::test]
+async fn public_addresses_are_allowed() {
+    let addrs = resolve_destination("https://93.184.216.34/hook")
+        .await
+        .unwrap();
+    assert_eq!(addrs[0].to_string(), "93.184.216.34:443");
+    assert!(resolve_destination("ftp://93.184.216.34/hook")
+        .await
+        .is_err());
+}